@@ -1,49 +1,40 @@
 use smartnoise_validator::errors::*;
 
-use crate::base::NodeArguments;
-use smartnoise_validator::base::{Value, Array, Jagged};
+use crate::NodeArguments;
+use smartnoise_validator::base::{Array, Jagged, ReleaseNode};
 use crate::components::Evaluable;
-use ndarray::{ArrayD};
+use ndarray::ArrayD;
 use smartnoise_validator::proto;
+use smartnoise_validator::components::bin::{BinSide, bin_representatives};
+use crate::components::digitize::bin_index;
 use crate::utilities::get_num_columns;
-use std::ops::{Div, Add};
-use smartnoise_validator::utilities::{get_argument, standardize_categorical_argument, standardize_numeric_argument, standardize_float_argument};
+use std::ops::{Add, Div, Sub};
+use smartnoise_validator::utilities::{take_argument, standardize_categorical_argument, standardize_numeric_argument, standardize_float_argument};
 
 impl Evaluable for proto::Bin {
-    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<Value> {
-        let inclusive_left: &ArrayD<bool> = get_argument(&mut arguments, "inclusive_left")?.array()?.bool()?;
+    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let inclusive_left: ArrayD<bool> = take_argument(&mut arguments, "inclusive_left")?.array()?.bool()?;
 
-        let side = match self.side.as_str() {
-            "lower" => BinSide::Lower,
-            "midpoint" => BinSide::Midpoint,
-            "upper" => BinSide::Upper,
-            _ => return Err("bin side must be lower, midpoint or upper".into())
-        };
+        let side = BinSide::new(&self.side)?;
 
-        let data = get_argument(&mut arguments, "data")?.array()?;
-        let edges = get_argument(&mut arguments, "edges")?.jagged()?;
-        let null = get_argument(&mut arguments, "null_value")?.array()?;
+        let data = take_argument(&mut arguments, "data")?.array()?;
+        let edges = take_argument(&mut arguments, "edges")?.jagged()?;
+        let null = take_argument(&mut arguments, "null_value")?.array()?;
 
-        let num_columns = data.num_columns()?;
+        let num_columns = data.num_columns()? as i64;
 
-        match (data, edges, null) {
-            (Array::F64(data), Jagged::F64(edges), Array::F64(null)) =>
-                Ok(bin(&data, standardize_float_argument(edges, &num_columns)?, &inclusive_left, &null, &side)?.into()),
+        Ok(ReleaseNode::new(match (data, edges, null) {
+            (Array::Float(data), Jagged::Float(edges), Array::Float(null)) =>
+                bin(data, standardize_float_argument(edges, num_columns)?, inclusive_left, null, &side)?.into(),
 
-            (Array::I64(data), Jagged::I64(edges), Array::I64(null)) =>
-                Ok(bin(&data, standardize_categorical_argument(edges, &num_columns)?, &inclusive_left, &null, &side)?.into()),
+            (Array::Int(data), Jagged::Int(edges), Array::Int(null)) =>
+                bin(data, standardize_categorical_argument(edges, num_columns)?, inclusive_left, null, &side)?.into(),
 
-            _ => return Err("data and edges must both be f64 or i64".into())
-        }
+            _ => return Err("data, edges and null_value must all be float, or all be integer".into())
+        }))
     }
 }
 
-pub enum BinSide {
-    Lower,
-    Midpoint,
-    Upper,
-}
-
 /// Maps data to bins.
 ///
 /// Bins will be of the form [lower, upper) or (lower, upper].
@@ -53,79 +44,77 @@ pub enum BinSide {
 /// * `edges` - Values representing the edges of bins.
 /// * `inclusive_left` - Whether or not the left edge of the bin is inclusive, i.e. the bins are of the form [lower, upper).
 /// * `null` - Value to which to map if there is no valid bin (e.g. if the element falls outside the bin range).
-/// * `side` - How to refer to each bin. Will be either the `left` edge, the `right` edge, or the `center` (the arithmetic mean of the two).
+/// * `side` - How to refer to each bin. Will be either the `lower` edge, the `upper` edge, or the `midpoint` (the arithmetic mean of the two).
 ///
 /// # Return
 /// Binned data.
 ///
 /// # Example
 /// ```
-/// use ndarray::{ArrayD, arr2, arr1};
-/// use smartnoise_runtime::components::bin::{bin, BinSide};
+/// use ndarray::{ArrayD, arr1};
+/// use smartnoise_runtime::components::bin::bin;
+/// use smartnoise_validator::components::bin::BinSide;
 ///
 /// let data = arr1(&[1.1, 2., 2.9, 4.1, 6.4]).into_dyn();
 /// let edges = vec![vec![0., 1., 2., 3., 4., 5.]];
 /// let inclusive_left = arr1(&[true]).into_dyn();
 /// let null = arr1(&[-1.]).into_dyn();
-/// let side = BinSide::Midpoint;
 ///
-/// let binned = bin(&data, edges, &inclusive_left, &null, &side).unwrap();
-/// assert!(binned == arr1(&[1.5, 2.5, 2.5, 4.5, -1.]).into_dyn());
+/// let binned = bin(data, edges, inclusive_left, null, &BinSide::Midpoint).unwrap();
+/// assert_eq!(binned, arr1(&[1.5, 2.5, 2.5, 4.5, -1.]).into_dyn());
 /// ```
-pub fn bin<T: std::cmp::PartialOrd + Clone + Div<T, Output=T> + Add<T, Output=T> + From<i32> + Copy>(
-    data: &ArrayD<T>,
+pub fn bin<T>(
+    mut data: ArrayD<T>,
     edges: Vec<Vec<T>>,
-    inclusive_left: &ArrayD<bool>,
-    null: &ArrayD<T>,
+    inclusive_left: ArrayD<bool>,
+    null: ArrayD<T>,
     side: &BinSide
-)-> Result<ArrayD<T>> {
-    let mut data = data.clone();
+) -> Result<ArrayD<T>>
+    where T: Copy + PartialOrd + Add<Output=T> + Sub<Output=T> + Div<Output=T> + From<i32> {
 
     let num_columns = get_num_columns(&data)?;
 
-    let inclusive_left = standardize_numeric_argument(&inclusive_left, &num_columns)?;
-    let null = standardize_numeric_argument(&null, &num_columns)?;
+    let inclusive_left = standardize_numeric_argument(inclusive_left, num_columns)?;
+    let null = standardize_numeric_argument(null, num_columns)?;
 
     // iterate over the generalized columns
     data.gencolumns_mut().into_iter()
         // pair generalized columns with arguments
-        .zip(edges.into_iter().zip(null.into_iter()))
+        .zip(edges.iter().zip(null.into_iter()))
         .zip(inclusive_left.iter())
         // for each pairing, iterate over the cells
-        .for_each(|((mut column, (mut edges, null)), inclusive_left)| {
-            edges.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+        .try_for_each(|((mut column, (edges, null)), inclusive_left)| {
+            let representatives = bin_representatives(edges, side)?;
             column.iter_mut()
                 // mutate the cell via the operator
-                .for_each(|v| {
-                    // checks for nullity
-                    if edges.len() == 0 || *v < edges[0] || *v > edges[edges.len() - 1] {
-                        *v = null.clone();
-                        return;
-                    }
-
-                    // assign to edge
-                    for idx in 0..(edges.len() - 1) {
-                        // check whether left or right side of bin should be considered inclusive
-                        if match inclusive_left {
-                            true => edges[idx] <= *v && *v < edges[idx + 1],
-                            false => edges[idx] < *v && *v <= edges[idx + 1]
-                        } {
-                            // assign element a new name based on bin naming rule
-                            *v = match side {
-                                BinSide::Lower => edges[idx],
-                                BinSide::Upper => edges[idx + 1],
-                                BinSide::Midpoint => (edges[idx] / T::from(2)) + (edges[idx + 1] / T::from(2))
-                            };
-                            return;
-                        }
-                    }
-                    *v = edges[edges.len() - 1];
-                })
-        });
+                .for_each(|v| *v = bin_index(v, edges, *inclusive_left)
+                    .map(|index| representatives[index])
+                    .unwrap_or(*null));
+            Ok::<_, Error>(())
+        })?;
 
     Ok(data)
 }
 
+#[cfg(test)]
+mod test_bin {
+    use ndarray::arr1;
+
+    use super::*;
+
+    #[test]
+    fn test_bin_sides() {
+        let data = arr1(&[0, 3, 5, 9, 10, 12]).into_dyn();
+        let edges = vec![vec![0, 5, 10]];
+        let bin_side = |side: BinSide, inclusive_left: bool| bin(
+            data.clone(), edges.clone(), arr1(&[inclusive_left]).into_dyn(), arr1(&[-1]).into_dyn(), &side).unwrap();
+
+        assert_eq!(bin_side(BinSide::Lower, true), arr1(&[0, 0, 5, 5, -1, -1]).into_dyn());
+        assert_eq!(bin_side(BinSide::Upper, false), arr1(&[-1, 5, 5, 10, 10, -1]).into_dyn());
+        assert_eq!(bin_side(BinSide::Midpoint, true), arr1(&[2, 2, 7, 7, -1, -1]).into_dyn());
+    }
+}
+
 //pub fn bin<T>(data: &ArrayD<T>, edges: &ArrayD<T>, inclusive_left: &ArrayD<bool>)
 //              -> Result<ArrayD<String>> where T: Clone, T: PartialOrd, T: std::fmt::Display {
 //    /// Accepts vector of data and assigns each element to a bin
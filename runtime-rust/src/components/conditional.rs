@@ -0,0 +1,139 @@
+use std::cmp::Ordering;
+
+use ndarray::{ArrayD, Axis, Zip};
+
+use smartnoise_validator::proto;
+use smartnoise_validator::base::{Array, ReleaseNode};
+use smartnoise_validator::errors::*;
+use smartnoise_validator::utilities::take_argument;
+
+use crate::components::Evaluable;
+use crate::NodeArguments;
+use crate::utilities::to_nd;
+
+impl Evaluable for proto::IfElse {
+    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let condition = take_argument(&mut arguments, "condition")?.array()?.bool()?;
+        Ok(ReleaseNode::new(match (
+            take_argument(&mut arguments, "then")?.array()?,
+            take_argument(&mut arguments, "otherwise")?.array()?) {
+            (Array::Float(then), Array::Float(otherwise)) => if_else(condition, then, otherwise)?.into(),
+            (Array::Int(then), Array::Int(otherwise)) => if_else(condition, then, otherwise)?.into(),
+            (Array::Str(then), Array::Str(otherwise)) => if_else(condition, then, otherwise)?.into(),
+            (Array::Bool(then), Array::Bool(otherwise)) => if_else(condition, then, otherwise)?.into(),
+            _ => return Err("IfElse: then and otherwise must share the same data type".into())
+        }))
+    }
+}
+
+impl Evaluable for proto::CaseWhen {
+    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let conditions = take_argument(&mut arguments, "conditions")?.array()?.bool()?;
+        Ok(ReleaseNode::new(match (
+            take_argument(&mut arguments, "values")?.array()?,
+            take_argument(&mut arguments, "otherwise")?.array()?) {
+            (Array::Float(values), Array::Float(otherwise)) => case_when(conditions, values, otherwise)?.into(),
+            (Array::Int(values), Array::Int(otherwise)) => case_when(conditions, values, otherwise)?.into(),
+            (Array::Str(values), Array::Str(otherwise)) => case_when(conditions, values, otherwise)?.into(),
+            (Array::Bool(values), Array::Bool(otherwise)) => case_when(conditions, values, otherwise)?.into(),
+            _ => return Err("CaseWhen: values and otherwise must share the same data type".into())
+        }))
+    }
+}
+
+/// Choose between two arrays, elementwise.
+///
+/// # Arguments
+/// * `condition` - Whether to take each value from `then` or from `otherwise`.
+/// * `then` - Values to take where the condition is true.
+/// * `otherwise` - Values to take where the condition is false.
+///
+/// # Return
+/// Array with the broadcasted shape of the arguments.
+///
+/// # Example
+/// ```
+/// use ndarray::{arr0, arr1};
+/// use smartnoise_runtime::components::conditional::if_else;
+///
+/// let condition = arr1(&[true, false, true]).into_dyn();
+/// let then = arr1(&[1, 2, 3]).into_dyn();
+/// let otherwise = arr0(0).into_dyn();
+/// let chosen = if_else(condition, then, otherwise).unwrap();
+/// assert_eq!(chosen, arr1(&[1, 0, 3]).into_dyn());
+/// ```
+pub fn if_else<T: Clone + Default>(
+    condition: ArrayD<bool>, then: ArrayD<T>, otherwise: ArrayD<T>
+) -> Result<ArrayD<T>> {
+    // take the shape of the argument with the most dimensions, then the most elements
+    let shape = vec![condition.shape(), then.shape(), otherwise.shape()].into_iter()
+        .max_by(|l, r| match l.len().cmp(&r.len()) {
+            Ordering::Equal => l.iter().product::<usize>().cmp(&r.iter().product()),
+            ordering => ordering
+        })
+        .unwrap().to_vec();
+
+    let condition = to_nd(condition, shape.len())?;
+    let then = to_nd(then, shape.len())?;
+    let otherwise = to_nd(otherwise, shape.len())?;
+
+    let mut output = ArrayD::<T>::default(shape.clone());
+    Zip::from(&mut output)
+        .and(condition.broadcast(shape.clone()).ok_or("could not broadcast condition argument")?)
+        .and(then.broadcast(shape.clone()).ok_or("could not broadcast then argument")?)
+        .and(otherwise.broadcast(shape).ok_or("could not broadcast otherwise argument")?)
+        .apply(|output, condition, then, otherwise|
+            *output = if *condition { then.clone() } else { otherwise.clone() });
+
+    Ok(output)
+}
+
+/// Choose the value of the first column whose condition is true, for each row.
+///
+/// # Arguments
+/// * `conditions` - One column of conditions for each column of values.
+/// * `values` - Candidate values for each row.
+/// * `otherwise` - Values to take where no condition is true.
+///
+/// # Return
+/// One value for each row.
+///
+/// # Example
+/// ```
+/// use ndarray::{arr0, arr1, arr2};
+/// use smartnoise_runtime::components::conditional::case_when;
+///
+/// let conditions = arr2(&[[true, true], [false, true], [false, false]]).into_dyn();
+/// let values = arr2(&[["low".to_string(), "medium".to_string()]]).into_dyn();
+/// let otherwise = arr0("high".to_string()).into_dyn();
+/// let chosen = case_when(conditions, values, otherwise).unwrap();
+/// assert_eq!(chosen, arr1(&["low".to_string(), "medium".to_string(), "high".to_string()]).into_dyn());
+/// ```
+pub fn case_when<T: Clone + Default>(
+    conditions: ArrayD<bool>, values: ArrayD<T>, otherwise: ArrayD<T>
+) -> Result<ArrayD<T>> {
+    let conditions = to_nd(conditions, 2)?;
+    let values = to_nd(values, 2)?;
+    let otherwise = to_nd(otherwise, 1)?;
+
+    let num_records = conditions.len_of(Axis(0))
+        .max(values.len_of(Axis(0)))
+        .max(otherwise.len_of(Axis(0)));
+    let num_columns = conditions.len_of(Axis(1)).max(values.len_of(Axis(1)));
+
+    let conditions = conditions.broadcast(vec![num_records, num_columns])
+        .ok_or("could not broadcast conditions argument")?;
+    let values = values.broadcast(vec![num_records, num_columns])
+        .ok_or("could not broadcast values argument")?;
+    let otherwise = otherwise.broadcast(vec![num_records])
+        .ok_or("could not broadcast otherwise argument")?;
+
+    Ok(ndarray::Array::from(conditions.outer_iter()
+        .zip(values.outer_iter())
+        .zip(otherwise.iter())
+        .map(|((conditions, values), otherwise)| conditions.iter().zip(values.iter())
+            .find(|(condition, _)| **condition)
+            .map(|(_, value)| value)
+            .unwrap_or(otherwise).clone())
+        .collect::<Vec<T>>()).into_dyn())
+}
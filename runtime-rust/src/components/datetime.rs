@@ -0,0 +1,67 @@
+use ndarray::{ArrayD, Zip};
+
+use smartnoise_validator::{Integer, proto};
+use smartnoise_validator::base::ReleaseNode;
+use smartnoise_validator::errors::*;
+use smartnoise_validator::utilities::{standardize_null_target_argument, take_argument};
+use smartnoise_validator::utilities::datetime::{DatetimePart, DatetimeUnit, extract_timestamp, parse_datetime, truncate_timestamp};
+
+use crate::components::Evaluable;
+use crate::NodeArguments;
+use crate::utilities::get_num_columns;
+
+impl Evaluable for proto::ParseDatetime {
+    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let data = take_argument(&mut arguments, "data")?.array()?.string()?;
+        let null = take_argument(&mut arguments, "null_value")?.array()?.int()?;
+        Ok(ReleaseNode::new(parse(&data, &self.format, null)?.into()))
+    }
+}
+
+impl Evaluable for proto::DatetimeTrunc {
+    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let unit = DatetimeUnit::new(&self.unit)?;
+        let data = take_argument(&mut arguments, "data")?.array()?.int()?;
+        Ok(ReleaseNode::new(data.mapv(|v| truncate_timestamp(v, &unit)).into()))
+    }
+}
+
+impl Evaluable for proto::DatetimeExtract {
+    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let part = DatetimePart::new(&self.part)?;
+        let data = take_argument(&mut arguments, "data")?.array()?.int()?;
+        Ok(ReleaseNode::new(data.mapv(|v| extract_timestamp(v, &part)).into()))
+    }
+}
+
+/// Parse strings into timestamps.
+///
+/// # Arguments
+/// * `data` - Strings to parse.
+/// * `format` - Format of the strings, with the directives `%Y`, `%m`, `%d`, `%H`, `%M`, `%S` and `%%`.
+/// * `null` - Timestamp for each column, to which to map strings that do not match the format.
+///
+/// # Return
+/// Seconds since the Unix epoch.
+///
+/// # Example
+/// ```
+/// use ndarray::arr1;
+/// use smartnoise_runtime::components::datetime::parse;
+///
+/// let data = arr1(&["2020-03-01".to_string(), "yesterday".to_string()]).into_dyn();
+/// let parsed = parse(&data, "%Y-%m-%d", arr1(&[0]).into_dyn()).unwrap();
+/// assert_eq!(parsed, arr1(&[1583020800, 0]).into_dyn());
+/// ```
+pub fn parse(data: &ArrayD<String>, format: &str, null: ArrayD<Integer>) -> Result<ArrayD<Integer>> {
+    let num_columns = get_num_columns(data)?;
+    let mut parsed = ArrayD::<Integer>::zeros(data.shape());
+
+    parsed.gencolumns_mut().into_iter()
+        .zip(data.gencolumns())
+        .zip(standardize_null_target_argument(null, num_columns)?)
+        .for_each(|((parsed, data), null)| Zip::from(parsed).and(data)
+            .apply(|parsed, data| *parsed = parse_datetime(data, format).unwrap_or(null)));
+
+    Ok(parsed)
+}
@@ -0,0 +1,243 @@
+use noisy_float::types::n64;
+use probability::distribution::{Binomial, Inverse};
+
+use smartnoise_validator::{Float, Integer, proto};
+use smartnoise_validator::base::{Array, IndexKey, Jagged, ReleaseNode};
+use smartnoise_validator::errors::*;
+use smartnoise_validator::utilities::privacy::get_epsilon;
+use smartnoise_validator::utilities::take_argument;
+
+use crate::components::dp_marginals::{contingency_tables, encode_categories};
+use crate::components::Evaluable;
+use crate::NodeArguments;
+use crate::utilities::noise;
+use crate::utilities::mechanisms::{laplace_mechanism, simple_geometric_mechanism};
+
+impl Evaluable for proto::DpChiSquareTest {
+    fn evaluate(&self, privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let codes = match (take_argument(&mut arguments, "data")?.array()?, take_argument(&mut arguments, "categories")?.jagged()?) {
+            (Array::Bool(data), Jagged::Bool(categories)) =>
+                encode_categories(&data, &categories)?,
+            (Array::Float(data), Jagged::Float(categories)) =>
+                encode_categories(
+                    &data.mapv(|v| n64(v as f64)),
+                    &categories.into_iter()
+                        .map(|column| column.into_iter().map(|v| n64(v as f64)).collect())
+                        .collect::<Vec<Vec<_>>>())?,
+            (Array::Int(data), Jagged::Int(categories)) =>
+                encode_categories(&data, &categories)?,
+            (Array::Str(data), Jagged::Str(categories)) =>
+                encode_categories(&data, &categories)?,
+            _ => return Err("data and categories must be homogeneously typed".into())
+        };
+        if codes.len() != 2 {
+            return Err("data must contain two columns".into())
+        }
+        let num_categories = codes.iter().map(|(_, num_categories)| *num_categories).collect::<Vec<usize>>();
+        let codes = codes.into_iter().map(|(codes, _)| codes).collect::<Vec<_>>();
+
+        let table = contingency_tables(&codes, &num_categories, &[vec![0, 1]]).remove(0);
+
+        let privatize = get_privatizer(&self.mechanism, &self.privacy_usage, privacy_definition, &mut arguments)?;
+        let noisy_table = table.into_iter().map(&privatize).collect::<Result<Vec<Float>>>()?;
+
+        let (statistic, p_value) = independence_test(
+            &noisy_table, num_categories[0], num_categories[1],
+            self.num_simulations as usize, &privatize)?;
+
+        Ok(ReleaseNode {
+            value: ndarray::arr1(&[statistic, p_value]).into_dyn().into(),
+            privacy_usages: Some(self.privacy_usage.clone()),
+            public: true,
+        })
+    }
+}
+
+/// Retrieve a function that privatizes one count with the mechanism of the test.
+///
+/// The geometric mechanism clamps counts to the public bounds.
+pub(crate) fn get_privatizer(
+    mechanism: &str,
+    privacy_usage: &[proto::PrivacyUsage],
+    privacy_definition: &Option<proto::PrivacyDefinition>,
+    arguments: &mut NodeArguments,
+) -> Result<impl Fn(Float) -> Result<Float>> {
+    let sensitivity = take_argument(arguments, "sensitivity")?.array()?.first_float()?;
+    let upper = match arguments.remove::<IndexKey>(&"upper".into()) {
+        Some(upper) => upper.array()?.first_int()?,
+        None => Integer::MAX
+    };
+
+    if privacy_usage.len() != 1 {
+        return Err(Error::from("the test is not vectorized, only one privacy parameter may be passed"))
+    }
+    let epsilon = get_epsilon(&privacy_usage[0])?;
+
+    let enforce_constant_time = privacy_definition.as_ref()
+        .ok_or_else(|| Error::from("privacy_definition must be known"))?
+        .protect_elapsed_time;
+
+    let mechanism = mechanism.to_lowercase();
+    if mechanism != "simplegeometric" && mechanism != "laplace" {
+        return Err(format!("`{}` is not recognized as a valid mechanism. Must be one of [`SimpleGeometric`, `Laplace`]", mechanism).into())
+    }
+
+    Ok(move |count: Float| Ok(match mechanism.as_str() {
+        "simplegeometric" => (count as Integer + simple_geometric_mechanism(
+            epsilon, sensitivity, 0, upper, enforce_constant_time)?).max(0).min(upper) as Float,
+        _ => count + laplace_mechanism(epsilon, sensitivity, enforce_constant_time)?
+    }))
+}
+
+/// Chi-square test of independence on a noisy contingency table.
+///
+/// The null distribution is estimated from the noisy row and column totals.
+/// Tables are simulated from the null distribution and privatized with the same mechanism,
+/// such that the p-value accounts for the noise.
+///
+/// Gaboardi, Lim, Rogers and Vadhan (2016), Differentially Private Chi-Squared Hypothesis Testing:
+/// Goodness of Fit and Independence Testing, Algorithm 4
+///
+/// # Arguments
+/// * `noisy_table` - Noisy counts of shape `(num_rows, num_columns)`, flattened in row-major order.
+/// * `num_rows` - Number of categories in the first column.
+/// * `num_columns` - Number of categories in the second column.
+/// * `num_simulations` - Number of tables to simulate under the null hypothesis.
+/// * `privatize` - Adds noise to one count.
+///
+/// # Return
+/// The chi-square statistic of the noisy table, and the p-value.
+pub fn independence_test(
+    noisy_table: &[Float], num_rows: usize, num_columns: usize,
+    num_simulations: usize, privatize: impl Fn(Float) -> Result<Float>,
+) -> Result<(Float, Float)> {
+    if noisy_table.len() != num_rows * num_columns {
+        return Err("table must have one count for each pair of categories".into())
+    }
+    let statistic = independence_statistic(noisy_table, num_rows, num_columns);
+
+    let (row_proportions, column_proportions, num_records) = estimate_marginals(noisy_table, num_rows, num_columns);
+    let null_proportions = row_proportions.iter()
+        .flat_map(|row| column_proportions.iter().map(move |column| row * column))
+        .collect::<Vec<Float>>();
+
+    let p_value = monte_carlo_p_value(statistic, num_simulations, || {
+        let table = sample_multinomial(num_records, &null_proportions)?.into_iter()
+            .map(&privatize).collect::<Result<Vec<Float>>>()?;
+        Ok(independence_statistic(&table, num_rows, num_columns))
+    })?;
+    Ok((statistic, p_value))
+}
+
+/// Chi-square statistic of a table against the product of its own row and column proportions.
+pub fn independence_statistic(table: &[Float], num_rows: usize, num_columns: usize) -> Float {
+    let (row_proportions, column_proportions, num_records) = estimate_marginals(table, num_rows, num_columns);
+    let expected = row_proportions.iter()
+        .flat_map(|row| column_proportions.iter().map(move |column| row * column * num_records as Float))
+        .collect::<Vec<Float>>();
+    chi_square_statistic(table, &expected)
+}
+
+/// Estimate the row and column proportions and the number of records of a noisy table.
+///
+/// Negative totals are clamped to zero. If every total is zero, the proportions are uniform.
+fn estimate_marginals(table: &[Float], num_rows: usize, num_columns: usize) -> (Vec<Float>, Vec<Float>, usize) {
+    let row_totals = (0..num_rows)
+        .map(|row| table[row * num_columns..(row + 1) * num_columns].iter().sum::<Float>())
+        .collect::<Vec<Float>>();
+    let column_totals = (0..num_columns)
+        .map(|column| (0..num_rows).map(|row| table[row * num_columns + column]).sum::<Float>())
+        .collect::<Vec<Float>>();
+    let num_records = table.iter().sum::<Float>().round().max(0.) as usize;
+    (normalize(&row_totals), normalize(&column_totals), num_records)
+}
+
+/// Clamp to non-negative values and rescale to sum to one.
+pub(crate) fn normalize(values: &[Float]) -> Vec<Float> {
+    let values = values.iter().map(|v| v.max(0.)).collect::<Vec<Float>>();
+    let total = values.iter().sum::<Float>();
+    if total > 0. {
+        values.iter().map(|v| v / total).collect()
+    } else {
+        vec![1. / values.len() as Float; values.len()]
+    }
+}
+
+/// Pearson's chi-square statistic. Cells with no expected count are skipped.
+pub fn chi_square_statistic(observed: &[Float], expected: &[Float]) -> Float {
+    observed.iter().zip(expected.iter())
+        .filter(|(_, expected)| **expected > 0.)
+        .map(|(observed, expected)| (observed - expected).powi(2) / expected)
+        .sum()
+}
+
+/// Fraction of simulated statistics at least as large as the observed statistic.
+///
+/// The observed statistic is counted among the simulations, so the p-value is never zero.
+pub fn monte_carlo_p_value(
+    statistic: Float, num_simulations: usize,
+    mut simulate: impl FnMut() -> Result<Float>,
+) -> Result<Float> {
+    let num_extreme = (0..num_simulations)
+        .map(|_| simulate())
+        .collect::<Result<Vec<Float>>>()?.into_iter()
+        .filter(|simulated| *simulated >= statistic)
+        .count();
+    Ok((1 + num_extreme) as Float / (1 + num_simulations) as Float)
+}
+
+/// Sample counts from the multinomial distribution, as a sequence of conditional binomial draws.
+///
+/// The draws are post-processing of public values, so they are not run in constant time.
+pub fn sample_multinomial(num_records: usize, probabilities: &[Float]) -> Result<Vec<Float>> {
+    let mut remaining_records = num_records;
+    let mut remaining_probability = 1.;
+
+    probabilities.iter()
+        .map(|probability| {
+            let conditional = if remaining_probability > 0. { probability / remaining_probability } else { 0. };
+            let count = if remaining_records == 0 || conditional <= 0. {
+                0
+            } else if conditional >= 1. {
+                remaining_records
+            } else {
+                Binomial::new(remaining_records, conditional as f64)
+                    .inverse(noise::sample_uniform(0., 1., false)?)
+                    .min(remaining_records)
+            };
+            remaining_records -= count;
+            remaining_probability -= probability;
+            Ok(count as Float)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test_dp_chi_square_test {
+    use super::*;
+
+    #[test]
+    fn test_sample_multinomial() {
+        let counts = sample_multinomial(10_000, &[0.2, 0.0, 0.3, 0.5]).unwrap();
+        assert_eq!(counts.iter().sum::<Float>(), 10_000.);
+        assert_eq!(counts[1], 0.);
+        assert!((counts[0] - 2000.).abs() < 300.);
+        assert!((counts[3] - 5000.).abs() < 300.);
+    }
+
+    #[test]
+    fn test_independence_test() {
+        let privatize = |count: Float| Ok(count + laplace_mechanism(1., 1., false)?);
+
+        // strongly dependent table
+        let dependent = vec![500., 0., 0., 500.].into_iter().map(privatize).collect::<Result<Vec<Float>>>().unwrap();
+        let (statistic, p_value) = independence_test(&dependent, 2, 2, 200, privatize).unwrap();
+        assert!(statistic > 100.);
+        assert!(p_value < 0.05);
+
+        // independent table
+        let independent = vec![250., 250., 250., 250.];
+        let (_, p_value) = independence_test(&independent, 2, 2, 200, privatize).unwrap();
+        assert!(p_value > 0.05);
+    }
+}
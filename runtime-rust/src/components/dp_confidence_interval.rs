@@ -0,0 +1,132 @@
+use ndarray::arr1;
+
+use smartnoise_validator::{Float, proto};
+use smartnoise_validator::base::{Array, ReleaseNode};
+use smartnoise_validator::errors::*;
+use smartnoise_validator::utilities::privacy::get_epsilon;
+use smartnoise_validator::utilities::take_argument;
+
+use crate::components::Evaluable;
+use crate::NodeArguments;
+use crate::utilities::noise;
+use crate::utilities::mechanisms::laplace_mechanism;
+
+impl Evaluable for proto::DpConfidenceInterval {
+    fn evaluate(&self, privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let (values, is_proportion) = match take_argument(&mut arguments, "data")?.array()? {
+            Array::Bool(data) => (data.iter().map(|v| if *v { 1. } else { 0. }).collect::<Vec<Float>>(), true),
+            Array::Float(data) => (data.iter().cloned().collect(), false),
+            Array::Int(data) => (data.iter().map(|v| *v as Float).collect(), false),
+            _ => return Err("data must be numeric or boolean".into())
+        };
+        if values.len() < 2 {
+            return Err("data must contain at least two records".into())
+        }
+        let num_records = values.len() as Float;
+
+        if self.privacy_usage.len() != 1 {
+            return Err(Error::from("DPConfidenceInterval is not vectorized, only one privacy parameter may be passed"))
+        }
+        let epsilon = get_epsilon(&self.privacy_usage[0])?;
+
+        let enforce_constant_time = privacy_definition.as_ref()
+            .ok_or_else(|| Error::from("privacy_definition must be known"))?
+            .protect_elapsed_time;
+
+        let mean_sensitivity = take_argument(&mut arguments, "mean_sensitivity")?.array()?.first_float()?;
+        let mean = values.iter().sum::<Float>() / num_records;
+
+        let (noisy_mean, noisy_variance, epsilon_mean) = if is_proportion {
+            // the variance of a proportion is a function of the proportion, so the entire budget is spent on the mean
+            let noisy_mean = mean + laplace_mechanism(epsilon, mean_sensitivity, enforce_constant_time)?;
+            let proportion = noisy_mean.max(0.).min(1.);
+            (noisy_mean, proportion * (1. - proportion), epsilon)
+        } else {
+            let variance_sensitivity = take_argument(&mut arguments, "variance_sensitivity")?.array()?.first_float()?;
+            let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<Float>() / (num_records - 1.);
+
+            let noisy_mean = mean + laplace_mechanism(epsilon / 2., mean_sensitivity, enforce_constant_time)?;
+            let noisy_variance = variance + laplace_mechanism(epsilon / 2., variance_sensitivity, enforce_constant_time)?;
+            (noisy_mean, noisy_variance.max(0.), epsilon / 2.)
+        };
+
+        let (mut lower, mut upper) = bootstrap_interval(
+            noisy_mean, noisy_variance, num_records as usize, mean_sensitivity / epsilon_mean,
+            self.alpha, self.num_simulations as usize)?;
+        if is_proportion {
+            lower = lower.max(0.);
+            upper = upper.min(1.);
+        }
+
+        Ok(ReleaseNode {
+            value: arr1(&[noisy_mean, lower, upper]).into_dyn().into(),
+            privacy_usages: Some(self.privacy_usage.clone()),
+            public: true,
+        })
+    }
+}
+
+/// Confidence interval for a mean privatized with the Laplace mechanism, by parametric bootstrap.
+///
+/// The error of the noisy mean is the sum of the sampling error, approximately normal with the noisy variance,
+/// and the Laplace noise. Errors are simulated from this distribution,
+/// and their quantiles are subtracted from the noisy mean.
+///
+/// Du, Foot, Moniz, Bray, Dang and Xu (2020), Differentially Private Confidence Intervals
+///
+/// # Arguments
+/// * `noisy_mean` - Privatized estimate of the mean.
+/// * `noisy_variance` - Privatized estimate of the variance of one record.
+/// * `num_records` - Number of records in the data.
+/// * `noise_scale` - Scale of the Laplace noise added to the mean.
+/// * `alpha` - Significance level of the interval.
+/// * `num_simulations` - Number of errors to simulate.
+///
+/// # Return
+/// The lower and upper bounds of the interval.
+pub fn bootstrap_interval(
+    noisy_mean: Float, noisy_variance: Float, num_records: usize,
+    noise_scale: Float, alpha: Float, num_simulations: usize,
+) -> Result<(Float, Float)> {
+    if !(0. < alpha && alpha < 1.) {
+        return Err("alpha must be in (0, 1)".into())
+    }
+    if num_simulations == 0 {
+        return Err("num_simulations must be greater than zero".into())
+    }
+    let standard_error = (noisy_variance / num_records as Float).sqrt();
+
+    // the simulations are post-processing of public values, so they are not run in constant time
+    let mut errors = (0..num_simulations)
+        .map(|_| Ok(standard_error * noise::sample_gaussian(0., 1., false)?
+            + noise::sample_laplace(0., noise_scale, false)?))
+        .collect::<Result<Vec<Float>>>()?;
+    if !errors.iter().all(|error| error.is_finite()) {
+        return Err("simulated errors must be finite".into())
+    }
+    errors.sort_by(Float::total_cmp);
+
+    let quantile = |probability: Float| errors[((probability * num_simulations as Float).ceil() as usize)
+        .max(1).min(num_simulations) - 1];
+
+    Ok((noisy_mean - quantile(1. - alpha / 2.), noisy_mean - quantile(alpha / 2.)))
+}
+
+#[cfg(test)]
+mod test_dp_confidence_interval {
+    use super::*;
+
+    #[test]
+    fn test_bootstrap_interval() {
+        // with negligible privatization noise, the interval approaches the normal interval
+        let (lower, upper) = bootstrap_interval(10., 100., 100, 1e-9, 0.05, 10_000).unwrap();
+        assert!((lower - (10. - 1.96)).abs() < 0.15);
+        assert!((upper - (10. + 1.96)).abs() < 0.15);
+
+        // the interval widens with the noise
+        let (noisy_lower, noisy_upper) = bootstrap_interval(10., 100., 100, 1., 0.05, 10_000).unwrap();
+        assert!(noisy_upper - noisy_lower > upper - lower);
+
+        assert!(bootstrap_interval(10., Float::NAN, 100, 1., 0.05, 100).is_err());
+    }
+}
@@ -0,0 +1,147 @@
+use indexmap::map::IndexMap;
+use ndarray::{Array2, Axis};
+
+use smartnoise_validator::{Float, proto};
+use smartnoise_validator::base::{IndexKey, ReleaseNode, Value};
+use smartnoise_validator::errors::*;
+use smartnoise_validator::utilities::privacy::{get_delta, get_epsilon};
+use smartnoise_validator::utilities::take_argument;
+
+use crate::components::covariance::matrix_covariance;
+use crate::components::dp_pca::upper_triangle_to_symmetric;
+use crate::components::Evaluable;
+use crate::NodeArguments;
+use crate::utilities::linalg::nearest_correlation;
+use crate::utilities::mechanisms::{gaussian_mechanism, laplace_mechanism};
+
+/// Variances are floored to this value before normalizing, as the noisy variances may not be positive.
+const MIN_VARIANCE: Float = 1e-8;
+/// Stopping criteria of the projection onto the correlation matrices.
+const PROJECTION_TOLERANCE: Float = 1e-8;
+const PROJECTION_MAX_ITERATIONS: usize = 100;
+
+impl Evaluable for proto::DpCorrelation {
+    fn evaluate(&self, privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let mut data = take_argument(&mut arguments, "data")?.array()?.float()?;
+        let lower = take_argument(&mut arguments, "lower")?.array()?.float()?;
+        let upper = take_argument(&mut arguments, "upper")?.array()?.float()?;
+        let sensitivity = take_argument(&mut arguments, "sensitivity")?.array()?.first_float()?;
+
+        if data.ndim() != 2 || data.len_of(Axis(1)) != lower.len() || lower.len() != upper.len() {
+            return Err("data must be a matrix with one lower and upper bound for each column".into())
+        }
+
+        if self.privacy_usage.len() != 1 {
+            return Err(Error::from("DPCorrelation is not vectorized, only one privacy parameter may be passed"))
+        }
+        let epsilon = get_epsilon(&self.privacy_usage[0])?;
+        let delta = get_delta(&self.privacy_usage[0])?;
+
+        let enforce_constant_time = privacy_definition.as_ref()
+            .ok_or_else(|| Error::from("privacy_definition must be known"))?
+            .protect_elapsed_time;
+
+        let mechanism = self.mechanism.to_lowercase();
+        let privatize = |value: Float, enforce_constant_time: bool| Ok(value + match mechanism.as_str() {
+            "laplace" => laplace_mechanism(epsilon, sensitivity, enforce_constant_time)?,
+            "gaussian" => gaussian_mechanism(epsilon, delta, sensitivity, false, enforce_constant_time)?,
+            "analyticgaussian" => gaussian_mechanism(epsilon, delta, sensitivity, true, enforce_constant_time)?,
+            _ => return Err(format!("`{}` is not recognized as a valid mechanism. Must be one of [`Laplace`, `Gaussian`, `AnalyticGaussian`]", mechanism).into())
+        });
+
+        // rescale each column to the unit interval. This does not change the correlations
+        data.gencolumns_mut().into_iter().zip(lower.iter().zip(upper.iter()))
+            .for_each(|(mut column, (lower, upper))| column
+                .mapv_inplace(|v| (v - lower) / (upper - lower)));
+
+        let noisy_covariance = matrix_covariance(&data, 1)?.concat().into_iter()
+            .map(|v| privatize(v, enforce_constant_time))
+            .collect::<Result<Vec<Float>>>()?;
+        let correlation = noisy_covariance_to_correlation(&noisy_covariance)?;
+
+        // the simulations are post-processing of public values, so they are not run in constant time
+        let simulations = (0..self.num_simulations)
+            .map(|_| noisy_covariance_to_correlation(&noisy_covariance.iter()
+                .map(|v| privatize(*v, false))
+                .collect::<Result<Vec<Float>>>()?))
+            .collect::<Result<Vec<Array2<Float>>>>()?;
+        let accuracy = simulated_accuracy(&correlation, &simulations, self.alpha as Float)?;
+
+        Ok(ReleaseNode {
+            value: Value::Dataframe(vec![
+                (IndexKey::from("correlation"), correlation.into_dyn().into()),
+                (IndexKey::from("accuracy"), accuracy.into_dyn().into())
+            ].into_iter().collect::<IndexMap<IndexKey, Value>>()),
+            privacy_usages: Some(self.privacy_usage.clone()),
+            public: true,
+        })
+    }
+}
+
+/// Normalize a noisy covariance matrix into the nearest valid correlation matrix.
+///
+/// Variances are floored at a small positive value and correlations are clamped to `[-1, 1]`
+/// before projecting onto the positive semi-definite matrices with unit diagonal.
+///
+/// # Arguments
+/// * `noisy_covariance` - Row-major upper triangle of a noisy covariance matrix.
+///
+/// # Return
+/// A correlation matrix of shape `(d, d)`.
+pub fn noisy_covariance_to_correlation(noisy_covariance: &[Float]) -> Result<Array2<Float>> {
+    let covariance = upper_triangle_to_symmetric(noisy_covariance)?;
+    let deviations = covariance.diag().mapv(|v| v.max(MIN_VARIANCE).sqrt());
+
+    let correlation = Array2::from_shape_fn(covariance.dim(), |(i, j)| if i == j { 1. } else {
+        (covariance[[i, j]] / (deviations[i] * deviations[j])).max(-1.).min(1.)
+    });
+    nearest_correlation(&correlation, PROJECTION_TOLERANCE, PROJECTION_MAX_ITERATIONS)
+}
+
+/// Accuracy of each entry, as the `1 - alpha` quantile of the absolute deviation of the simulated entries.
+fn simulated_accuracy(estimate: &Array2<Float>, simulations: &[Array2<Float>], alpha: Float) -> Result<Array2<Float>> {
+    if simulations.is_empty() {
+        return Err("num_simulations must be greater than zero".into())
+    }
+    if !estimate.iter().chain(simulations.iter().flatten()).all(|v| v.is_finite()) {
+        return Err("simulated correlations must be finite".into())
+    }
+    let index = (((1. - alpha) * simulations.len() as Float).ceil() as usize).max(1).min(simulations.len()) - 1;
+
+    Ok(Array2::from_shape_fn(estimate.dim(), |(i, j)| {
+        let mut deviations = simulations.iter()
+            .map(|simulation| (simulation[[i, j]] - estimate[[i, j]]).abs())
+            .collect::<Vec<Float>>();
+        deviations.sort_by(Float::total_cmp);
+        deviations[index]
+    }))
+}
+
+#[cfg(test)]
+mod test_dp_correlation {
+    use super::*;
+
+    #[test]
+    fn test_noisy_covariance_to_correlation() {
+        // the off-diagonal covariance exceeds the product of the deviations, and one variance is negative
+        let correlation = noisy_covariance_to_correlation(&[1., 2., 0.5, 1., 0.5, -0.1]).unwrap();
+
+        assert_eq!(correlation.dim(), (3, 3));
+        assert!(correlation.diag().iter().all(|v| (v - 1.).abs() < 1e-12));
+        assert!(correlation.iter().all(|v| v.abs() <= 1. + 1e-8));
+        assert!((correlation[[0, 1]] - correlation[[1, 0]]).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_simulated_accuracy() {
+        let estimate = Array2::<Float>::zeros((2, 2));
+        let mut simulations = (1..=100)
+            .map(|i| Array2::from_elem((2, 2), i as Float / 100.))
+            .collect::<Vec<_>>();
+        let accuracy = simulated_accuracy(&estimate, &simulations, 0.05).unwrap();
+        assert!((accuracy[[0, 1]] - 0.95).abs() < 1e-12);
+
+        simulations[0][[0, 1]] = Float::NAN;
+        assert!(simulated_accuracy(&estimate, &simulations, 0.05).is_err());
+    }
+}
@@ -0,0 +1,298 @@
+use indexmap::map::IndexMap;
+use ndarray::Array2;
+
+use smartnoise_validator::{Float, Integer, proto};
+use smartnoise_validator::base::{Array, IndexKey, Jagged, ReleaseNode, Value};
+use smartnoise_validator::errors::*;
+use smartnoise_validator::utilities::privacy::get_epsilon;
+use smartnoise_validator::utilities::take_argument;
+
+use crate::components::dp_naive_bayes::{encode, flatten_categories};
+use crate::components::Evaluable;
+use crate::NodeArguments;
+use crate::utilities::mechanisms::{exponential_mechanism, laplace_mechanism};
+use crate::utilities::noise;
+
+impl Evaluable for proto::DpDecisionTree {
+    fn evaluate(&self, privacy_definition: &Option<proto::PrivacyDefinition>, arguments: NodeArguments) -> Result<ReleaseNode> {
+        evaluate_forest(privacy_definition, arguments, &self.privacy_usage, self.max_depth as usize, 1)
+    }
+}
+
+/// A complete binary tree, with the splits stored in breadth-first order.
+#[derive(Debug)]
+pub struct Tree {
+    /// column of each split
+    pub features: Vec<usize>,
+    /// records whose value is at most the threshold go to the left child
+    pub thresholds: Vec<Float>,
+    /// noisy count of each class in each leaf
+    pub leaf_counts: Vec<Vec<Float>>,
+}
+
+/// Grow one tree per partition of the records, and release the forest as a dataframe.
+///
+/// When there is more than one tree, each record is assigned to one tree uniformly at random.
+pub(crate) fn evaluate_forest(
+    privacy_definition: &Option<proto::PrivacyDefinition>,
+    mut arguments: NodeArguments,
+    privacy_usage: &[proto::PrivacyUsage],
+    max_depth: usize,
+    num_trees: usize,
+) -> Result<ReleaseNode> {
+    let label = take_argument(&mut arguments, "label")?.array()?;
+    let classes = take_argument(&mut arguments, "classes")?.jagged()?;
+    let columns = numeric_columns(take_argument(&mut arguments, "data")?.array()?)?;
+    let splits = match take_argument(&mut arguments, "splits")?.jagged()? {
+        Jagged::Float(splits) => splits,
+        Jagged::Int(splits) => splits.into_iter()
+            .map(|column| column.into_iter().map(|v| v as Float).collect())
+            .collect(),
+        _ => return Err("splits must be numeric".into())
+    };
+    let sensitivity = take_argument(&mut arguments, "sensitivity")?.array()?.first_float()?;
+
+    if splits.len() != columns.len() {
+        return Err("splits must be defined for each column of data".into())
+    }
+    let candidates = splits.iter().enumerate()
+        .flat_map(|(feature, thresholds)| thresholds.iter().map(move |threshold| (feature, *threshold)))
+        .collect::<Vec<(usize, Float)>>();
+    if candidates.is_empty() {
+        return Err("splits must contain at least one candidate threshold".into())
+    }
+
+    if privacy_usage.len() != 1 {
+        return Err(Error::from("the tree is not vectorized, only one privacy parameter may be passed"))
+    }
+    let epsilon = get_epsilon(&privacy_usage[0])?;
+
+    let enforce_constant_time = privacy_definition.as_ref()
+        .ok_or_else(|| Error::from("privacy_definition must be known"))?
+        .protect_elapsed_time;
+
+    let mut label_codes = encode(label, classes.clone())?;
+    if label_codes.len() != 1 {
+        return Err("label must contain one column".into())
+    }
+    let (labels, num_classes) = label_codes.remove(0);
+    if columns.iter().any(|column| column.len() != labels.len()) {
+        return Err("label and data must be same length".into())
+    }
+
+    // records whose label is not among the classes are not used
+    let mut partitions = vec![Vec::new(); num_trees];
+    for (record, label) in labels.iter().enumerate() {
+        if label.is_some() {
+            let tree = if num_trees == 1 { 0 } else {
+                noise::sample_uniform_int(0, num_trees as Integer - 1)? as usize
+            };
+            partitions[tree].push(record);
+        }
+    }
+    let labels = labels.into_iter().map(|label| label.unwrap_or(0)).collect::<Vec<usize>>();
+
+    let trees = partitions.into_iter()
+        .map(|records| grow_tree(
+            &columns, &labels, records, num_classes, &candidates,
+            max_depth, epsilon, sensitivity, enforce_constant_time))
+        .collect::<Result<Vec<Tree>>>()?;
+
+    let num_splits = (1 << max_depth) - 1;
+    let features = trees.iter()
+        .flat_map(|tree| tree.features.iter().map(|v| *v as Integer))
+        .collect::<Vec<Integer>>();
+    let thresholds = trees.iter()
+        .flat_map(|tree| tree.thresholds.iter().cloned())
+        .collect::<Vec<Float>>();
+    let leaf_counts = trees.iter()
+        .flat_map(|tree| tree.leaf_counts.iter().flatten().cloned())
+        .collect::<Vec<Float>>();
+
+    Ok(ReleaseNode {
+        value: Value::Dataframe(vec![
+            (IndexKey::from("classes"), Value::Array(flatten_categories(classes))),
+            (IndexKey::from("features"), Array2::from_shape_vec((num_trees, num_splits), features)?.into_dyn().into()),
+            (IndexKey::from("thresholds"), Array2::from_shape_vec((num_trees, num_splits), thresholds)?.into_dyn().into()),
+            (IndexKey::from("leaf_counts"), Array2::from_shape_vec(
+                (num_trees << max_depth, num_classes), leaf_counts)?.into_dyn().into()),
+        ].into_iter().collect::<IndexMap<IndexKey, Value>>()),
+        privacy_usages: Some(privacy_usage.to_vec()),
+        public: true,
+    })
+}
+
+/// Grow a complete tree over a subset of the records.
+///
+/// At each level, the split of each node is chosen with the exponential mechanism,
+/// scored by the number of records of the majority class in each child.
+/// The nodes at each level partition the records, so each level spends its share of the budget once.
+/// Half of the budget is split evenly over the levels, and the other half is spent on the leaf counts.
+///
+/// Friedman and Schuster (2010), Data Mining with Differential Privacy
+///
+/// # Arguments
+/// * `columns` - Values of each column.
+/// * `labels` - Class index of each record.
+/// * `records` - Indices of the records to grow the tree on.
+/// * `num_classes` - Number of classes.
+/// * `candidates` - Column and threshold of each candidate split.
+/// * `max_depth` - Depth of the tree.
+/// * `epsilon` - Privacy usage of the tree.
+/// * `sensitivity` - Sensitivity of the split scores and of the class counts of each leaf.
+/// * `enforce_constant_time` - Whether to run the mechanisms in constant time.
+///
+/// # Return
+/// The tree, with `2^max_depth - 1` splits and `2^max_depth` leaves.
+#[allow(clippy::too_many_arguments)]
+pub fn grow_tree(
+    columns: &[Vec<Float>], labels: &[usize], records: Vec<usize>, num_classes: usize,
+    candidates: &[(usize, Float)], max_depth: usize,
+    epsilon: Float, sensitivity: Float, enforce_constant_time: bool,
+) -> Result<Tree> {
+    let epsilon_level = epsilon / 2. / max_depth as Float;
+    let epsilon_leaf = epsilon / 2.;
+
+    let mut features = Vec::new();
+    let mut thresholds = Vec::new();
+    let mut nodes = vec![records];
+
+    for _ in 0..max_depth {
+        let mut children = Vec::with_capacity(nodes.len() * 2);
+        for node in nodes {
+            let scores = candidates.iter()
+                .map(|(feature, threshold)| split_score(
+                    &columns[*feature], labels, &node, num_classes, *threshold))
+                .collect::<Vec<Float>>();
+
+            // shift the utilities to keep the exponentiated scores finite. This does not change the selection probabilities
+            let max_score = scores.iter().cloned().fold(Float::NEG_INFINITY, Float::max);
+            let (feature, threshold) = exponential_mechanism(
+                epsilon_level, sensitivity, candidates,
+                scores.into_iter().map(|score| score - max_score).collect(),
+                enforce_constant_time)?;
+
+            let (left, right): (Vec<usize>, Vec<usize>) = node.into_iter()
+                .partition(|record| columns[feature][*record] <= threshold);
+            features.push(feature);
+            thresholds.push(threshold);
+            children.push(left);
+            children.push(right);
+        }
+        nodes = children;
+    }
+
+    let leaf_counts = nodes.iter()
+        .map(|node| class_counts(labels, node, num_classes).into_iter()
+            .map(|count| Ok((count + laplace_mechanism(epsilon_leaf, sensitivity, enforce_constant_time)?).max(0.)))
+            .collect::<Result<Vec<Float>>>())
+        .collect::<Result<Vec<Vec<Float>>>>()?;
+
+    Ok(Tree { features, thresholds, leaf_counts })
+}
+
+/// Score of a split, as the number of records of the majority class in each child.
+///
+/// Adding or removing a record changes the score by at most one.
+///
+/// # Arguments
+/// * `column` - Values of the column to split on.
+/// * `labels` - Class index of each record.
+/// * `records` - Indices of the records in the node.
+/// * `num_classes` - Number of classes.
+/// * `threshold` - Records whose value is at most the threshold go to the left child.
+///
+/// # Return
+/// The score of the split.
+pub fn split_score(column: &[Float], labels: &[usize], records: &[usize], num_classes: usize, threshold: Float) -> Float {
+    let mut left = vec![0.; num_classes];
+    let mut right = vec![0.; num_classes];
+    records.iter().for_each(|record| if column[*record] <= threshold {
+        left[labels[*record]] += 1.
+    } else {
+        right[labels[*record]] += 1.
+    });
+    let max = |counts: Vec<Float>| counts.into_iter().fold(0., Float::max);
+    max(left) + max(right)
+}
+
+/// Number of records of each class.
+fn class_counts(labels: &[usize], records: &[usize], num_classes: usize) -> Vec<Float> {
+    let mut counts = vec![0.; num_classes];
+    records.iter().for_each(|record| counts[labels[*record]] += 1.);
+    counts
+}
+
+/// Values of each column of numeric data, as floats.
+pub(crate) fn numeric_columns(data: Array) -> Result<Vec<Vec<Float>>> {
+    Ok(match data {
+        Array::Float(data) => data.gencolumns().into_iter()
+            .map(|column| column.to_vec()).collect(),
+        Array::Int(data) => data.gencolumns().into_iter()
+            .map(|column| column.iter().map(|v| *v as Float).collect()).collect(),
+        _ => return Err("data must be numeric".into())
+    })
+}
+
+/// Index of the leaf that a record reaches in a complete tree.
+///
+/// # Arguments
+/// * `value` - Value of the record in each column.
+/// * `features` - Column of each split, in breadth-first order.
+/// * `thresholds` - Threshold of each split, in breadth-first order.
+///
+/// # Return
+/// The index of the leaf, from left to right.
+pub fn leaf_index(value: impl Fn(usize) -> Float, features: &[usize], thresholds: &[Float]) -> usize {
+    let mut node = 0;
+    while node < features.len() {
+        node = if value(features[node]) <= thresholds[node] { 2 * node + 1 } else { 2 * node + 2 };
+    }
+    node - features.len()
+}
+
+#[cfg(test)]
+mod test_dp_decision_tree {
+    use super::*;
+
+    #[test]
+    fn test_split_score() {
+        let column = vec![1., 2., 3., 4.];
+        let labels = vec![0, 0, 1, 1];
+        let records = vec![0, 1, 2, 3];
+
+        // separates the classes
+        assert_eq!(split_score(&column, &labels, &records, 2, 2.), 4.);
+        // the left child holds every record
+        assert_eq!(split_score(&column, &labels, &records, 2, 4.), 2.);
+    }
+
+    #[test]
+    fn test_leaf_index() {
+        // the root splits column 0 at 0, its children split column 1 at 0
+        let features = vec![0, 1, 1];
+        let thresholds = vec![0., 0., 0.];
+        let record = |values: [Float; 2]| leaf_index(|column| values[column], &features, &thresholds);
+
+        assert_eq!(record([-1., -1.]), 0);
+        assert_eq!(record([-1., 1.]), 1);
+        assert_eq!(record([1., -1.]), 2);
+        assert_eq!(record([1., 1.]), 3);
+    }
+
+    #[test]
+    fn test_grow_tree() {
+        let column = (0..100).map(|v| v as Float).collect::<Vec<Float>>();
+        let labels = column.iter().map(|v| if *v < 50. { 0 } else { 1 }).collect::<Vec<usize>>();
+        let candidates = vec![(0, 24.5), (0, 49.5), (0, 74.5)];
+
+        let tree = grow_tree(
+            &[column], &labels, (0..100).collect(), 2, &candidates,
+            2, 100., 1., false).unwrap();
+
+        assert_eq!(tree.features.len(), 3);
+        assert_eq!(tree.leaf_counts.len(), 4);
+        // with a large budget, the root separates the classes
+        assert_eq!(tree.thresholds[0], 49.5);
+    }
+}
@@ -0,0 +1,120 @@
+use ndarray::arr1;
+
+use smartnoise_validator::{Float, proto};
+use smartnoise_validator::base::{Jagged, ReleaseNode};
+use smartnoise_validator::errors::*;
+use smartnoise_validator::utilities::privacy::get_epsilon;
+use smartnoise_validator::utilities::take_argument;
+
+use crate::components::Evaluable;
+use crate::NodeArguments;
+use crate::utilities::mechanisms::laplace_mechanism;
+
+impl Evaluable for proto::DpEmpiricalCdf {
+    fn evaluate(&self, privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let (noisy_counts, num_records) = privatize_histogram(privacy_definition, &mut arguments, &self.privacy_usage)?;
+
+        // the CDF is zero at the first edge, and accumulates one bin at each following edge
+        let cumulative_counts = noisy_counts.iter()
+            .scan(0., |total, count| {
+                *total += count;
+                Some(*total)
+            })
+            .collect::<Vec<Float>>();
+        let cdf = std::iter::once(0.)
+            .chain(isotonic_regression(&cumulative_counts).into_iter()
+                .map(|count| (count / num_records).max(0.).min(1.)))
+            .collect::<Vec<Float>>();
+
+        Ok(ReleaseNode {
+            value: arr1(&cdf).into_dyn().into(),
+            privacy_usages: Some(self.privacy_usage.clone()),
+            public: true,
+        })
+    }
+}
+
+/// Add Laplace noise to the count of each bin of a histogram over the edges.
+///
+/// The final count of the histogram is of the records outside the edges, and is dropped.
+///
+/// # Return
+/// The noisy count of each bin, and the number of records.
+pub(crate) fn privatize_histogram(
+    privacy_definition: &Option<proto::PrivacyDefinition>,
+    arguments: &mut NodeArguments,
+    privacy_usage: &[proto::PrivacyUsage],
+) -> Result<(Vec<Float>, Float)> {
+    let mut counts = take_argument(arguments, "data")?.array()?.int()?
+        .iter().map(|v| *v as Float).collect::<Vec<Float>>();
+    let num_edges = match take_argument(arguments, "edges")?.jagged()? {
+        Jagged::Float(edges) if edges.len() == 1 => edges[0].len(),
+        Jagged::Int(edges) if edges.len() == 1 => edges[0].len(),
+        _ => return Err("edges must be one column of numeric values".into())
+    };
+    let sensitivity = take_argument(arguments, "sensitivity")?.array()?.float()?
+        .iter().cloned().fold(0., Float::max);
+
+    if privacy_usage.len() != 1 {
+        return Err(Error::from("the histogram is not vectorized, only one privacy parameter may be passed"))
+    }
+    let epsilon = get_epsilon(&privacy_usage[0])?;
+
+    let enforce_constant_time = privacy_definition.as_ref()
+        .ok_or_else(|| Error::from("privacy_definition must be known"))?
+        .protect_elapsed_time;
+
+    if counts.len() != num_edges {
+        return Err("data must contain one count for each bin, followed by the count of records outside the edges".into())
+    }
+    // the validator requires the number of records to be public, so the total count is not private
+    let num_records = counts.iter().sum::<Float>().max(1.);
+    counts.pop();
+
+    let noisy_counts = counts.into_iter()
+        .map(|count| Ok(count + laplace_mechanism(epsilon, sensitivity, enforce_constant_time)?))
+        .collect::<Result<Vec<Float>>>()?;
+    Ok((noisy_counts, num_records))
+}
+
+/// The non-decreasing sequence nearest to the values in squared error, by the pool adjacent violators algorithm.
+///
+/// # Arguments
+/// * `values` - Values to fit.
+///
+/// # Return
+/// The fitted value of each value.
+pub fn isotonic_regression(values: &[Float]) -> Vec<Float> {
+    // each block holds the sum and number of the values that are pooled into it
+    let mut blocks: Vec<(Float, usize)> = Vec::with_capacity(values.len());
+    for value in values {
+        blocks.push((*value, 1));
+        while blocks.len() > 1 {
+            let (sum, count) = blocks[blocks.len() - 1];
+            let (previous_sum, previous_count) = blocks[blocks.len() - 2];
+            if previous_sum / previous_count as Float <= sum / count as Float {
+                break
+            }
+            blocks.pop();
+            *blocks.last_mut().unwrap() = (previous_sum + sum, previous_count + count);
+        }
+    }
+    blocks.into_iter()
+        .flat_map(|(sum, count)| std::iter::repeat(sum / count as Float).take(count))
+        .collect()
+}
+
+#[cfg(test)]
+mod test_dp_empirical_cdf {
+    use super::*;
+
+    #[test]
+    fn test_isotonic_regression() {
+        // already sorted
+        assert_eq!(isotonic_regression(&[1., 2., 3.]), vec![1., 2., 3.]);
+        // violators are pooled to their mean
+        assert_eq!(isotonic_regression(&[1., 3., 2., 4.]), vec![1., 2.5, 2.5, 4.]);
+        assert_eq!(isotonic_regression(&[3., 2., 1.]), vec![2., 2., 2.]);
+        assert!(isotonic_regression(&[]).is_empty());
+    }
+}
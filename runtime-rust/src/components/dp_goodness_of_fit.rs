@@ -0,0 +1,111 @@
+use ndarray::arr1;
+use noisy_float::types::n64;
+
+use smartnoise_validator::{Float, proto};
+use smartnoise_validator::base::{Array, Jagged, ReleaseNode};
+use smartnoise_validator::errors::*;
+use smartnoise_validator::utilities::take_argument;
+
+use crate::components::dp_chi_square_test::{chi_square_statistic, get_privatizer, monte_carlo_p_value, normalize, sample_multinomial};
+use crate::components::Evaluable;
+use crate::components::histogram::histogram;
+use crate::NodeArguments;
+
+impl Evaluable for proto::DpGoodnessOfFit {
+    fn evaluate(&self, privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let counts = match (take_argument(&mut arguments, "data")?.array()?, take_argument(&mut arguments, "categories")?.jagged()?) {
+            (Array::Bool(data), Jagged::Bool(categories)) =>
+                histogram(&data, &arr1(&categories[0]).into_dyn())?,
+            (Array::Float(data), Jagged::Float(categories)) =>
+                histogram(
+                    &data.mapv(|v| n64(v as f64)),
+                    &arr1(&categories[0]).mapv(|v| n64(v as f64)).into_dyn())?,
+            (Array::Int(data), Jagged::Int(categories)) =>
+                histogram(&data, &arr1(&categories[0]).into_dyn())?,
+            (Array::Str(data), Jagged::Str(categories)) =>
+                histogram(&data, &arr1(&categories[0]).into_dyn())?,
+            _ => return Err("data and categories must be homogeneously typed".into())
+        };
+        let expected = take_argument(&mut arguments, "expected")?.array()?.float()?
+            .iter().cloned().collect::<Vec<Float>>();
+
+        let privatize = get_privatizer(&self.mechanism, &self.privacy_usage, privacy_definition, &mut arguments)?;
+        let noisy_counts = counts.iter()
+            .map(|count| privatize(*count as Float))
+            .collect::<Result<Vec<Float>>>()?;
+
+        let (statistic, p_value) = goodness_of_fit_test(
+            &noisy_counts, &expected, self.num_simulations as usize, &privatize)?;
+
+        Ok(ReleaseNode {
+            value: arr1(&[statistic, p_value]).into_dyn().into(),
+            privacy_usages: Some(self.privacy_usage.clone()),
+            public: true,
+        })
+    }
+}
+
+/// Chi-square goodness-of-fit test of a noisy histogram against expected proportions.
+///
+/// Histograms are simulated from the expected proportions and privatized with the same mechanism,
+/// such that the p-value accounts for the noise.
+///
+/// Gaboardi, Lim, Rogers and Vadhan (2016), Differentially Private Chi-Squared Hypothesis Testing:
+/// Goodness of Fit and Independence Testing, Algorithm 1
+///
+/// # Arguments
+/// * `noisy_counts` - Noisy count of each category.
+/// * `expected` - Proportion of each category under the null hypothesis, normalized to sum to one.
+/// * `num_simulations` - Number of histograms to simulate under the null hypothesis.
+/// * `privatize` - Adds noise to one count.
+///
+/// # Return
+/// The chi-square statistic of the noisy histogram, and the p-value.
+pub fn goodness_of_fit_test(
+    noisy_counts: &[Float], expected: &[Float],
+    num_simulations: usize, privatize: impl Fn(Float) -> Result<Float>,
+) -> Result<(Float, Float)> {
+    if noisy_counts.len() != expected.len() {
+        return Err("expected must contain one proportion for each category".into())
+    }
+    let expected = normalize(expected);
+
+    let statistic = goodness_of_fit_statistic(noisy_counts, &expected);
+    let num_records = noisy_counts.iter().sum::<Float>().round().max(0.) as usize;
+
+    let p_value = monte_carlo_p_value(statistic, num_simulations, || {
+        let counts = sample_multinomial(num_records, &expected)?.into_iter()
+            .map(&privatize).collect::<Result<Vec<Float>>>()?;
+        Ok(goodness_of_fit_statistic(&counts, &expected))
+    })?;
+    Ok((statistic, p_value))
+}
+
+/// Chi-square statistic of a histogram against the expected proportions, scaled by the total of the histogram.
+pub fn goodness_of_fit_statistic(counts: &[Float], expected: &[Float]) -> Float {
+    let num_records = counts.iter().sum::<Float>().max(0.);
+    chi_square_statistic(counts, &expected.iter()
+        .map(|proportion| proportion * num_records)
+        .collect::<Vec<Float>>())
+}
+
+#[cfg(test)]
+mod test_dp_goodness_of_fit {
+    use crate::utilities::mechanisms::laplace_mechanism;
+
+    use super::*;
+
+    #[test]
+    fn test_goodness_of_fit_test() {
+        let privatize = |count: Float| Ok(count + laplace_mechanism(1., 1., false)?);
+        let counts = vec![400., 300., 300.].into_iter().map(privatize).collect::<Result<Vec<Float>>>().unwrap();
+
+        let (_, p_value) = goodness_of_fit_test(&counts, &[1., 1., 1.], 200, privatize).unwrap();
+        assert!(p_value < 0.05);
+
+        // the statistic is zero when the counts exactly match the expected proportions
+        let (statistic, p_value) = goodness_of_fit_test(&[400., 300., 300.], &[4., 3., 3.], 200, privatize).unwrap();
+        assert!(statistic < 1e-9);
+        assert!(p_value > 0.9);
+    }
+}
@@ -0,0 +1,160 @@
+use ndarray::{Array, Array2, Ix1, Ix2};
+
+use smartnoise_validator::{Float, proto};
+use smartnoise_validator::base::ReleaseNode;
+use smartnoise_validator::errors::*;
+use smartnoise_validator::proto::privacy_definition::Neighboring;
+use smartnoise_validator::utilities::{standardize_numeric_argument, take_argument};
+use smartnoise_validator::utilities::privacy::{get_epsilon, spread_privacy_usage};
+
+use crate::components::Evaluable;
+use crate::NodeArguments;
+use crate::utilities::{noise, to_nd};
+use crate::utilities::mechanisms::laplace_mechanism;
+
+impl Evaluable for proto::DpkMeans {
+    fn evaluate(&self, privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let data = to_nd(take_argument(&mut arguments, "data")?.array()?.float()?, 2)?
+            .into_dimensionality::<Ix2>()?;
+        let num_columns = data.ncols() as i64;
+
+        let lower = standardize_numeric_argument(
+            take_argument(&mut arguments, "lower")?.array()?.float()?, num_columns)?
+            .into_dimensionality::<Ix1>()?.to_vec();
+        let upper = standardize_numeric_argument(
+            take_argument(&mut arguments, "upper")?.array()?.float()?, num_columns)?
+            .into_dimensionality::<Ix1>()?.to_vec();
+
+        let privacy_definition = privacy_definition.as_ref()
+            .ok_or_else(|| Error::from("privacy_definition must be known"))?;
+        let neighboring = Neighboring::from_i32(privacy_definition.neighboring)
+            .ok_or_else(|| Error::from("neighboring definition must be either \"AddRemove\" or \"Substitute\""))?;
+
+        // the budget is spread evenly over the iterations
+        let epsilons = spread_privacy_usage(&self.privacy_usage, self.num_iterations as usize)?.iter()
+            .map(get_epsilon)
+            .collect::<Result<Vec<Float>>>()?;
+
+        let centroids = dp_k_means(
+            &data, &lower, &upper,
+            self.num_clusters as usize, &epsilons,
+            neighboring, privacy_definition.protect_elapsed_time)?;
+
+        Ok(ReleaseNode {
+            value: centroids.into_dyn().into(),
+            privacy_usages: Some(self.privacy_usage.clone()),
+            public: true,
+        })
+    }
+}
+
+/// Fit k-means centroids with noisy Lloyd iterations.
+///
+/// Data is clamped to the bounding box, and centroids are initialized uniformly at random within the box.
+/// Each iteration assigns every record to its nearest centroid, and spends half of its budget
+/// on the per-cluster counts and half on the per-cluster sums, both privatized with the Laplace mechanism.
+/// A cluster whose noisy count is less than one keeps its previous centroid.
+///
+/// # Arguments
+/// * `data` - Points of shape `(n, d)`.
+/// * `lower` - Lower corner of the bounding box, of length `d`.
+/// * `upper` - Upper corner of the bounding box, of length `d`.
+/// * `num_clusters` - Number of centroids to fit.
+/// * `epsilons` - Privacy usage of each iteration.
+/// * `neighboring` - Definition of neighboring datasets.
+/// * `enforce_constant_time` - Whether to force the noise sampling to run in constant time.
+///
+/// # Return
+/// Centroids of shape `(num_clusters, d)`.
+pub fn dp_k_means(
+    data: &Array2<Float>, lower: &[Float], upper: &[Float],
+    num_clusters: usize, epsilons: &[Float],
+    neighboring: Neighboring, enforce_constant_time: bool,
+) -> Result<Array2<Float>> {
+    let num_columns = data.ncols();
+    if lower.len() != num_columns || upper.len() != num_columns {
+        return Err("lower and upper must have one bound per column".into())
+    }
+    if num_clusters == 0 {
+        return Err("num_clusters must be greater than zero".into())
+    }
+
+    let clamp = |v: Float, column: usize| v.max(lower[column]).min(upper[column]);
+    let data = Array::from_shape_fn(data.dim(), |(i, j)| clamp(data[[i, j]], j));
+
+    // L1 sensitivities of the count and sum vectors.
+    // Under substitution, a record may move from one cluster to another.
+    let magnitude = lower.iter().zip(upper.iter())
+        .map(|(l, u)| l.abs().max(u.abs())).sum::<Float>();
+    let (count_sensitivity, sum_sensitivity) = match neighboring {
+        Neighboring::AddRemove => (1., magnitude),
+        Neighboring::Substitute => (2., 2. * magnitude)
+    };
+
+    let mut centroids = Array2::<Float>::zeros((num_clusters, num_columns));
+    for ((_, column), centroid) in centroids.indexed_iter_mut() {
+        *centroid = noise::sample_uniform(lower[column], upper[column], enforce_constant_time)?;
+    }
+
+    for epsilon in epsilons {
+        let mut counts = vec![0.; num_clusters];
+        let mut sums = Array2::<Float>::zeros((num_clusters, num_columns));
+
+        data.genrows().into_iter().for_each(|row| {
+            let nearest = (0..num_clusters)
+                .map(|cluster| (cluster, centroids.row(cluster).iter().zip(row.iter())
+                    .map(|(c, v)| (c - v).powi(2)).sum::<Float>()))
+                .fold((0, Float::INFINITY), |best, candidate|
+                    if candidate.1 < best.1 { candidate } else { best }).0;
+            counts[nearest] += 1.;
+            let mut sum = sums.row_mut(nearest);
+            sum += &row;
+        });
+
+        for cluster in 0..num_clusters {
+            let count = counts[cluster] + laplace_mechanism(epsilon / 2., count_sensitivity, enforce_constant_time)?;
+            let mut sum = sums.row(cluster).to_owned();
+            for v in sum.iter_mut() {
+                *v += laplace_mechanism(epsilon / 2., sum_sensitivity, enforce_constant_time)?;
+            }
+
+            if count >= 1. {
+                for column in 0..num_columns {
+                    centroids[[cluster, column]] = clamp(sum[column] / count, column);
+                }
+            }
+        }
+    }
+
+    Ok(centroids)
+}
+
+#[cfg(test)]
+mod test_dp_k_means {
+    use ndarray::Array2;
+
+    use super::*;
+
+    #[test]
+    fn test_dp_k_means_shape_and_bounds() {
+        let data = Array2::from_shape_fn((50, 3), |(i, j)| (i * (j + 1)) as Float);
+        let centroids = dp_k_means(
+            &data, &[0., 0., 0.], &[10., 20., 30.], 4, &[0.5, 0.5],
+            Neighboring::AddRemove, false).unwrap();
+
+        assert_eq!(centroids.dim(), (4, 3));
+        assert!(centroids.genrows().into_iter()
+            .all(|row| row[0] <= 10. && row[1] <= 20. && row[2] <= 30. && row.iter().all(|v| *v >= 0.)));
+    }
+
+    #[test]
+    fn test_dp_k_means_single_cluster_is_mean() {
+        // two tight groups, at (1, 1) and (9, 9)
+        let data = Array2::from_shape_fn((2000, 2), |(i, _)| if i % 2 == 0 { 1. } else { 9. });
+        let centroids = dp_k_means(
+            &data, &[0., 0.], &[10., 10.], 1, &[100.; 2],
+            Neighboring::Substitute, false).unwrap();
+
+        assert!(centroids.iter().all(|v| (v - 5.).abs() < 0.1));
+    }
+}
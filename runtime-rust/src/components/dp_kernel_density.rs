@@ -0,0 +1,77 @@
+use ndarray::arr1;
+
+use smartnoise_validator::{Float, proto};
+use smartnoise_validator::base::{Array, IndexKey, Jagged, ReleaseNode};
+use smartnoise_validator::errors::*;
+use smartnoise_validator::utilities::take_argument;
+
+use crate::components::dp_empirical_cdf::privatize_histogram;
+use crate::components::Evaluable;
+use crate::NodeArguments;
+
+impl Evaluable for proto::DpKernelDensity {
+    fn evaluate(&self, privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let edges = match arguments.get::<IndexKey>(&"edges".into())
+            .ok_or_else(|| Error::from("edges: missing"))?.ref_jagged()? {
+            Jagged::Float(edges) if edges.len() == 1 => edges[0].clone(),
+            Jagged::Int(edges) if edges.len() == 1 => edges[0].iter().map(|v| *v as Float).collect(),
+            _ => return Err("edges must be one column of numeric values".into())
+        };
+        let grid = match take_argument(&mut arguments, "grid")?.array()? {
+            Array::Float(grid) => grid.iter().cloned().collect::<Vec<Float>>(),
+            Array::Int(grid) => grid.iter().map(|v| *v as Float).collect(),
+            _ => return Err("grid must be numeric".into())
+        };
+        if self.bandwidth <= 0. {
+            return Err("bandwidth must be greater than zero".into())
+        }
+
+        let (noisy_counts, num_records) = privatize_histogram(privacy_definition, &mut arguments, &self.privacy_usage)?;
+
+        // the counts without noise are non-negative
+        let weights = noisy_counts.into_iter()
+            .map(|count| count.max(0.) / num_records)
+            .collect::<Vec<Float>>();
+
+        Ok(ReleaseNode {
+            value: arr1(&kernel_density(&edges, &weights, &grid, self.bandwidth)).into_dyn().into(),
+            privacy_usages: Some(self.privacy_usage.clone()),
+            public: true,
+        })
+    }
+}
+
+/// Density at each point of the grid, of a mixture of Gaussian kernels centered on the midpoint of each bin.
+///
+/// # Arguments
+/// * `edges` - Edges of the bins.
+/// * `weights` - Weight of the kernel of each bin.
+/// * `grid` - Points at which to evaluate the density.
+/// * `bandwidth` - Standard deviation of each kernel.
+///
+/// # Return
+/// The density at each point of the grid.
+pub fn kernel_density(edges: &[Float], weights: &[Float], grid: &[Float], bandwidth: Float) -> Vec<Float> {
+    let midpoints = edges.windows(2).map(|w| (w[0] + w[1]) / 2.).collect::<Vec<Float>>();
+    let normalization = bandwidth * (2. * std::f64::consts::PI).sqrt();
+    grid.iter()
+        .map(|point| midpoints.iter().zip(weights.iter())
+            .map(|(midpoint, weight)| weight * (-0.5 * ((point - midpoint) / bandwidth).powi(2)).exp())
+            .sum::<Float>() / normalization)
+        .collect()
+}
+
+#[cfg(test)]
+mod test_dp_kernel_density {
+    use super::*;
+
+    #[test]
+    fn test_kernel_density() {
+        // all of the weight in the bin centered on zero
+        let density = kernel_density(&[-1., 1., 3.], &[1., 0.], &[0., 1., -1.], 1.);
+        assert!((density[0] - 1. / (2. * std::f64::consts::PI).sqrt()).abs() < 1e-12);
+        // symmetric about the midpoint
+        assert!((density[1] - density[2]).abs() < 1e-12);
+        assert!(density[1] < density[0]);
+    }
+}
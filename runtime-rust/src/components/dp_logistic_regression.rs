@@ -0,0 +1,296 @@
+use ndarray::{Array, Array1, Array2, ArrayD, Axis};
+
+use smartnoise_validator::{Float, proto};
+use smartnoise_validator::base::ReleaseNode;
+use smartnoise_validator::errors::*;
+use smartnoise_validator::utilities::privacy::get_epsilon;
+use smartnoise_validator::utilities::take_argument;
+
+use crate::components::Evaluable;
+use crate::NodeArguments;
+use crate::utilities::{linalg, noise, to_nd};
+
+/// Upper bound on the second derivative of the logistic loss
+const LOGISTIC_LOSS_CURVATURE: Float = 0.25;
+const NEWTON_MAX_ITERATIONS: usize = 100;
+const NEWTON_TOLERANCE: Float = 1e-10;
+
+impl Evaluable for proto::DpLogisticRegression {
+    fn evaluate(&self, privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let data_x = to_nd(take_argument(&mut arguments, "data_x")?.array()?.float()?, 2)?;
+        let data_y = take_argument(&mut arguments, "data_y")?.array()?.bool()?
+            .iter().map(|v| if *v { 1. } else { -1. }).collect::<Vec<Float>>();
+
+        if self.privacy_usage.len() != 1 {
+            return Err(Error::from("DPLogisticRegression is not vectorized, only one privacy parameter may be passed"))
+        }
+        let epsilon = get_epsilon(&self.privacy_usage[0])?;
+
+        let enforce_constant_time = privacy_definition.as_ref()
+            .ok_or_else(|| Error::from("privacy_definition must be known"))?
+            .protect_elapsed_time;
+
+        let implementation = match self.implementation.to_lowercase().as_str() {
+            "objective-perturbation" => Perturbation::Objective,
+            "output-perturbation" => Perturbation::Output,
+            _ => return Err(format!("`{}` is not recognized as a valid implementation. Must be one of [`objective-perturbation`, `output-perturbation`]", self.implementation).into())
+        };
+
+        let coefficients = dp_logistic_regression(
+            &data_x, &data_y,
+            implementation, self.regularization, self.clip_norm,
+            epsilon, enforce_constant_time)?;
+
+        let num_columns = coefficients.len();
+        Ok(ReleaseNode {
+            value: Array::from_shape_vec((1, num_columns), coefficients.to_vec())?.into_dyn().into(),
+            privacy_usages: Some(self.privacy_usage.clone()),
+            public: true,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Perturbation {
+    Objective,
+    Output,
+}
+
+/// Fit an L2-regularized logistic regression under differential privacy.
+///
+/// Rows of `data_x` are rescaled to lie within an L2 ball of radius `clip_norm`.
+/// The coefficients are then privatized by either objective or output perturbation,
+/// following Algorithms 1 and 2 of Chaudhuri, Monteleoni and Sarwate (2011).
+///
+/// # Arguments
+/// * `data_x` - Predictors, of shape `(n, d)`.
+/// * `data_y` - Labels, each either `-1.` or `1.`.
+/// * `implementation` - Privatization strategy.
+/// * `regularization` - Coefficient on the L2 penalty.
+/// * `clip_norm` - Maximum L2 norm of each row of `data_x`.
+/// * `epsilon` - Privacy loss parameter.
+/// * `enforce_constant_time` - Whether to force noise sampling to run in constant time.
+///
+/// # Return
+/// The `d` privatized coefficients, on the scale of the original predictors.
+pub fn dp_logistic_regression(
+    data_x: &ArrayD<Float>, data_y: &[Float],
+    implementation: Perturbation, regularization: Float, clip_norm: Float,
+    epsilon: Float, enforce_constant_time: bool,
+) -> Result<Array1<Float>> {
+    if regularization <= 0. {
+        return Err("regularization must be greater than zero".into())
+    }
+    if clip_norm <= 0. {
+        return Err("clip_norm must be greater than zero".into())
+    }
+
+    // the analysis assumes predictors in the unit ball
+    let data_x = clip_rows(data_x, clip_norm)? / clip_norm;
+    if data_x.len_of(Axis(0)) != data_y.len() {
+        return Err("data_x and data_y must be the same length".into())
+    }
+    let (num_records, num_columns) = data_x.dim();
+    if num_records == 0 {
+        return Err("data must not be empty".into())
+    }
+    let n = num_records as Float;
+
+    let coefficients = match implementation {
+        Perturbation::Objective => {
+            let c = LOGISTIC_LOSS_CURVATURE;
+            let mut epsilon_prime = epsilon
+                - (1. + 2. * c / (n * regularization) + (c / (n * regularization)).powi(2)).ln();
+            let mut extra_regularization = 0.;
+            if epsilon_prime <= 0. {
+                extra_regularization = c / (n * ((epsilon / 4.).exp() - 1.)) - regularization;
+                epsilon_prime = epsilon / 2.;
+            }
+            let noise = sample_l2_noise(num_columns, 2. / epsilon_prime, enforce_constant_time)?;
+            minimize_logistic_loss(
+                &data_x, data_y,
+                regularization + extra_regularization,
+                &(noise / n))?
+        }
+        Perturbation::Output => {
+            let noise = sample_l2_noise(num_columns, 2. / (n * regularization * epsilon), enforce_constant_time)?;
+            minimize_logistic_loss(
+                &data_x, data_y,
+                regularization,
+                &Array1::zeros(num_columns))? + noise
+        }
+    };
+
+    // undo the rescaling, so that coefficients apply to the original predictors
+    Ok(coefficients / clip_norm)
+}
+
+/// Rescale each row of `data` so that its L2 norm is at most `clip_norm`.
+pub(crate) fn clip_rows(data: &ArrayD<Float>, clip_norm: Float) -> Result<Array2<Float>> {
+    let mut data = data.clone().into_dimensionality::<ndarray::Ix2>()?;
+    data.genrows_mut().into_iter().for_each(|mut row| {
+        let norm = row.iter().map(|v| v.powi(2)).sum::<Float>().sqrt();
+        if norm > clip_norm {
+            row.mapv_inplace(|v| v * clip_norm / norm);
+        }
+    });
+    Ok(data)
+}
+
+/// Sample from the density proportional to `exp(-||b||_2 / scale)` in `dimension` dimensions.
+///
+/// The norm is gamma distributed with shape `dimension`, and the direction is uniform on the sphere.
+pub(crate) fn sample_l2_noise(dimension: usize, scale: Float, enforce_constant_time: bool) -> Result<Array1<Float>> {
+    // a sum of exponentials is gamma distributed
+    let norm = (0..dimension)
+        .map(|_| sample_exponential(scale, enforce_constant_time))
+        .sum::<Result<Float>>()?;
+
+    let direction = (0..dimension)
+        .map(|_| noise::sample_gaussian(0., 1., enforce_constant_time))
+        .collect::<Result<Array1<Float>>>()?;
+    let direction_norm = direction.iter().map(|v| v.powi(2)).sum::<Float>().sqrt();
+    if direction_norm == 0. {
+        return sample_l2_noise(dimension, scale, enforce_constant_time)
+    }
+
+    Ok(direction * (norm / direction_norm))
+}
+
+fn sample_exponential(scale: Float, enforce_constant_time: bool) -> Result<Float> {
+    let uniform = noise::sample_uniform(0., 1., enforce_constant_time)?;
+    if uniform == 0. {
+        return sample_exponential(scale, enforce_constant_time)
+    }
+    Ok(-scale * uniform.ln())
+}
+
+/// Minimize `1/n sum_i log(1 + exp(-y_i w'x_i)) + regularization / 2 ||w||^2 + linear'w`
+/// via Newton's method with a backtracking line search.
+///
+/// The objective is strongly convex when `regularization > 0`, so the minimizer is unique.
+pub fn minimize_logistic_loss(
+    data_x: &Array2<Float>, data_y: &[Float],
+    regularization: Float, linear: &Array1<Float>,
+) -> Result<Array1<Float>> {
+    let (num_records, num_columns) = data_x.dim();
+    let n = num_records as Float;
+
+    let objective = |w: &Array1<Float>| -> Float {
+        data_x.genrows().into_iter().zip(data_y.iter())
+            .map(|(x, y)| log_one_plus_exp(-y * x.dot(w)))
+            .sum::<Float>() / n
+            + regularization / 2. * w.dot(w)
+            + linear.dot(w)
+    };
+
+    let mut coefficients = Array1::<Float>::zeros(num_columns);
+    let mut value = objective(&coefficients);
+
+    for _ in 0..NEWTON_MAX_ITERATIONS {
+        let mut gradient = &coefficients * regularization + linear;
+        let mut hessian = Array2::<Float>::eye(num_columns) * regularization;
+
+        data_x.genrows().into_iter().zip(data_y.iter()).for_each(|(x, y)| {
+            let probability = sigmoid(y * x.dot(&coefficients));
+            gradient.scaled_add(-y * (1. - probability) / n, &x);
+
+            let curvature = probability * (1. - probability) / n;
+            let x = x.to_owned().insert_axis(Axis(1));
+            hessian.scaled_add(curvature, &x.dot(&x.t()));
+        });
+
+        let step = linalg::cholesky_solve(&hessian, &gradient)?;
+
+        // backtrack until the objective decreases sufficiently
+        let decrement = gradient.dot(&step);
+        let mut step_size = 1.;
+        let mut candidate = &coefficients - &(&step * step_size);
+        let mut candidate_value = objective(&candidate);
+        while candidate_value > value - 0.25 * step_size * decrement && step_size > 1e-10 {
+            step_size /= 2.;
+            candidate = &coefficients - &(&step * step_size);
+            candidate_value = objective(&candidate);
+        }
+
+        coefficients = candidate;
+        value = candidate_value;
+
+        if decrement / 2. < NEWTON_TOLERANCE {
+            break
+        }
+    }
+
+    if coefficients.iter().any(|v| !v.is_finite()) {
+        return Err("logistic regression failed to converge".into())
+    }
+    Ok(coefficients)
+}
+
+fn sigmoid(v: Float) -> Float {
+    1. / (1. + (-v).exp())
+}
+
+/// Numerically stable evaluation of `log(1 + exp(v))`.
+fn log_one_plus_exp(v: Float) -> Float {
+    if v > 0. {
+        v + (-v).exp().ln_1p()
+    } else {
+        v.exp().ln_1p()
+    }
+}
+
+
+#[cfg(test)]
+mod test_dp_logistic_regression {
+    use ndarray::{arr1, arr2, Array1};
+
+    use crate::components::dp_logistic_regression::{clip_rows, minimize_logistic_loss, dp_logistic_regression, Perturbation};
+
+    #[test]
+    fn test_clip_rows() {
+        let data = arr2(&[[3., 4.], [0.3, 0.4]]).into_dyn();
+        let clipped = clip_rows(&data, 1.).unwrap();
+        assert_eq!(clipped, arr2(&[[0.6, 0.8], [0.3, 0.4]]));
+    }
+
+    #[test]
+    fn test_minimize_logistic_loss() {
+        // symmetric data, so the optimum of the first coefficient is positive and the second is zero
+        let data_x = arr2(&[[1., 0.5], [1., -0.5], [-1., 0.5], [-1., -0.5], [0.5, 0.], [-0.5, 0.]]);
+        let data_y = [1., 1., -1., -1., -1., 1.];
+        let coefficients = minimize_logistic_loss(&data_x, &data_y, 0.1, &Array1::zeros(2)).unwrap();
+        assert!(coefficients[0] > 0.);
+        assert!(coefficients[1].abs() < 1e-8);
+
+        // the gradient vanishes at the optimum
+        let n = data_y.len() as f64;
+        let mut gradient = &coefficients * 0.1;
+        data_x.genrows().into_iter().zip(data_y.iter()).for_each(|(x, y)| {
+            let probability = 1. / (1. + (-y * x.dot(&coefficients)).exp());
+            gradient.scaled_add(-y * (1. - probability) / n, &x);
+        });
+        assert!(gradient.iter().all(|v| v.abs() < 1e-8));
+    }
+
+    #[test]
+    fn test_linear_term() {
+        let data_x = arr2(&[[1.], [-1.]]);
+        let data_y = [1., -1.];
+        let unperturbed = minimize_logistic_loss(&data_x, &data_y, 1., &arr1(&[0.])).unwrap();
+        let perturbed = minimize_logistic_loss(&data_x, &data_y, 1., &arr1(&[0.5])).unwrap();
+        assert!(perturbed[0] < unperturbed[0]);
+    }
+
+    #[test]
+    fn test_dp_logistic_regression() {
+        let data_x = arr2(&[[1., 0.], [0.9, 0.1], [-1., 0.], [-0.9, -0.1]]).into_dyn();
+        let data_y = [1., 1., -1., -1.];
+        for implementation in &[Perturbation::Objective, Perturbation::Output] {
+            let coefficients = dp_logistic_regression(
+                &data_x, &data_y, *implementation, 1., 1., 1., false).unwrap();
+            assert_eq!(coefficients.len(), 2);
+            assert!(coefficients.iter().all(|v| v.is_finite()));
+        }
+    }
+}
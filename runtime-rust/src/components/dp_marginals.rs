@@ -0,0 +1,274 @@
+use std::collections::BTreeSet;
+use std::hash::Hash;
+
+use indexmap::map::IndexMap;
+use itertools::Itertools;
+use ndarray::{ArrayD, arr1};
+use noisy_float::types::n64;
+
+use smartnoise_validator::{Float, proto};
+use smartnoise_validator::base::{Array, Jagged, ReleaseNode};
+use smartnoise_validator::errors::*;
+use smartnoise_validator::utilities::privacy::{get_delta, get_epsilon};
+use smartnoise_validator::utilities::take_argument;
+
+use crate::components::Evaluable;
+use crate::NodeArguments;
+use crate::utilities::mechanisms::{gaussian_mechanism, laplace_mechanism};
+
+impl Evaluable for proto::DpMarginals {
+    fn evaluate(&self, privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let codes = match (take_argument(&mut arguments, "data")?.array()?, take_argument(&mut arguments, "categories")?.jagged()?) {
+            (Array::Bool(data), Jagged::Bool(categories)) =>
+                encode_categories(&data, &categories)?,
+            (Array::Float(data), Jagged::Float(categories)) =>
+                encode_categories(
+                    &data.mapv(|v| n64(v as f64)),
+                    &categories.into_iter()
+                        .map(|column| column.into_iter().map(|v| n64(v as f64)).collect())
+                        .collect::<Vec<Vec<_>>>())?,
+            (Array::Int(data), Jagged::Int(categories)) =>
+                encode_categories(&data, &categories)?,
+            (Array::Str(data), Jagged::Str(categories)) =>
+                encode_categories(&data, &categories)?,
+            _ => return Err("data and categories must be homogeneously typed".into())
+        };
+        let num_categories = codes.iter().map(|(_, num_categories)| *num_categories).collect::<Vec<usize>>();
+        let codes = codes.into_iter().map(|(codes, _)| codes).collect::<Vec<_>>();
+
+        let marginals = take_argument(&mut arguments, "marginals")?.array()?.int()?
+            .genrows().into_iter()
+            .map(|marginal| marginal.iter()
+                .map(|column| if *column < 0 || *column as usize >= codes.len() {
+                    Err("marginals: column index is out of bounds".into())
+                } else { Ok(*column as usize) })
+                .collect::<Result<Vec<usize>>>())
+            .collect::<Result<Vec<Vec<usize>>>>()?;
+        let sensitivity = take_argument(&mut arguments, "sensitivity")?.array()?.first_float()?;
+
+        if self.privacy_usage.len() != 1 {
+            return Err(Error::from("DPMarginals is not vectorized, only one privacy parameter may be passed"))
+        }
+        let epsilon = get_epsilon(&self.privacy_usage[0])?;
+        let delta = get_delta(&self.privacy_usage[0])?;
+
+        let enforce_constant_time = privacy_definition.as_ref()
+            .ok_or_else(|| Error::from("privacy_definition must be known"))?
+            .protect_elapsed_time;
+
+        let mut tables = contingency_tables(&codes, &num_categories, &marginals);
+
+        // the sensitivity spans every table, so the budget is implicitly split evenly across the tables
+        for cell in tables.iter_mut().flatten() {
+            *cell += match self.mechanism.to_lowercase().as_str() {
+                "laplace" => laplace_mechanism(epsilon, sensitivity, enforce_constant_time)?,
+                "gaussian" => gaussian_mechanism(epsilon, delta, sensitivity, false, enforce_constant_time)?,
+                "analyticgaussian" => gaussian_mechanism(epsilon, delta, sensitivity, true, enforce_constant_time)?,
+                _ => return Err(format!("`{}` is not recognized as a valid mechanism. Must be one of [`Laplace`, `Gaussian`, `AnalyticGaussian`]", self.mechanism).into())
+            };
+        }
+
+        if self.consistent {
+            make_consistent(&mut tables, &marginals, &num_categories);
+        }
+
+        Ok(ReleaseNode {
+            value: arr1(&tables.concat()).into_dyn().into(),
+            privacy_usages: Some(self.privacy_usage.clone()),
+            public: true,
+        })
+    }
+}
+
+/// Replace each value of the data with the index of its category.
+///
+/// # Arguments
+/// * `data` - Data of shape `(n, d)`.
+/// * `categories` - Categories of each of the `d` columns.
+///
+/// # Return
+/// For each column, the category index of each record, or `None` if the value is not a category,
+/// along with the number of categories in the column.
+pub fn encode_categories<T: Eq + Hash>(
+    data: &ArrayD<T>, categories: &[Vec<T>],
+) -> Result<Vec<(Vec<Option<usize>>, usize)>> {
+    let columns = data.gencolumns().into_iter().collect::<Vec<_>>();
+    if columns.len() != categories.len() {
+        return Err("categories must be defined for each column of data".into())
+    }
+
+    Ok(columns.into_iter().zip(categories.iter())
+        .map(|(column, categories)| {
+            let lookup = categories.iter().enumerate()
+                .map(|(index, category)| (category, index))
+                .collect::<IndexMap<&T, usize>>();
+            (column.iter().map(|v| lookup.get(v).cloned()).collect(), categories.len())
+        })
+        .collect())
+}
+
+/// Count the records in each cell of each marginal.
+///
+/// # Arguments
+/// * `codes` - Category index of each record, for each column.
+/// * `num_categories` - Number of categories in each column.
+/// * `marginals` - Column indices of each marginal.
+///
+/// # Return
+/// One table for each marginal, flattened in row-major order over the categories of its columns.
+/// Records outside the categories of any column in a marginal are not counted in that table.
+pub fn contingency_tables(
+    codes: &[Vec<Option<usize>>], num_categories: &[usize], marginals: &[Vec<usize>],
+) -> Vec<Vec<Float>> {
+    let num_records = codes.first().map(|column| column.len()).unwrap_or(0);
+
+    marginals.iter()
+        .map(|marginal| {
+            let mut table = vec![0.; marginal.iter().map(|column| num_categories[*column]).product()];
+            (0..num_records)
+                .filter_map(|record| marginal.iter().try_fold(0, |index, column|
+                    codes[*column][record].map(|code| index * num_categories[*column] + code)))
+                .for_each(|index| table[index] += 1.);
+            table
+        })
+        .collect()
+}
+
+/// Post-process tables such that tables sharing columns agree on the counts over the shared columns.
+///
+/// The sets of columns shared between tables are visited in order of increasing size.
+/// For each set, the projections of the tables onto the set are averaged,
+/// weighted by the inverse of the number of cells that were summed into each projected cell,
+/// and the difference is spread evenly over the summed cells.
+/// Agreement on smaller sets is preserved when reconciling larger sets.
+///
+/// Qardaji, Yang and Li (2014), PriView: Practical Differentially Private Release of Marginal Contingency Tables, Section 4.2
+///
+/// # Arguments
+/// * `tables` - Tables in the layout returned by `contingency_tables`.
+/// * `marginals` - Column indices of each marginal.
+/// * `num_categories` - Number of categories in each column.
+pub fn make_consistent(tables: &mut [Vec<Float>], marginals: &[Vec<usize>], num_categories: &[usize]) {
+    let column_sets = marginals.iter()
+        .map(|marginal| marginal.iter().cloned().collect::<BTreeSet<usize>>())
+        .collect::<Vec<_>>();
+
+    // column sets shared between tables, closed under intersection
+    let mut overlaps = BTreeSet::new();
+    let mut pending = column_sets.iter().tuple_combinations()
+        .map(|(l, r)| l.intersection(r).cloned().collect::<BTreeSet<usize>>())
+        .collect::<Vec<_>>();
+    while let Some(overlap) = pending.pop() {
+        if overlaps.insert(overlap.iter().cloned().collect::<Vec<usize>>()) {
+            pending.extend(column_sets.iter()
+                .map(|columns| overlap.intersection(columns).cloned().collect()));
+        }
+    }
+
+    overlaps.into_iter()
+        .sorted_by_key(|overlap| overlap.len())
+        .for_each(|overlap| {
+            let containing = (0..tables.len())
+                .filter(|table| overlap.iter().all(|column| column_sets[*table].contains(column)))
+                .collect::<Vec<usize>>();
+            if containing.len() < 2 {
+                return
+            }
+
+            let size = overlap.iter().map(|column| num_categories[*column]).product::<usize>();
+
+            let projections = containing.iter()
+                .map(|table| {
+                    let indices = project_indices(&marginals[*table], num_categories, &overlap);
+                    let mut projection = vec![0.; size];
+                    indices.iter().zip(tables[*table].iter())
+                        .for_each(|(index, count)| projection[*index] += count);
+                    let cells_per_projected_cell = (tables[*table].len() / size.max(1)) as Float;
+                    (indices, projection, cells_per_projected_cell)
+                })
+                .collect::<Vec<_>>();
+
+            let total_weight = projections.iter()
+                .map(|(_, _, cells)| 1. / cells).sum::<Float>();
+            let target = (0..size)
+                .map(|index| projections.iter()
+                    .map(|(_, projection, cells)| projection[index] / cells)
+                    .sum::<Float>() / total_weight)
+                .collect::<Vec<Float>>();
+
+            containing.iter().zip(projections.iter())
+                .for_each(|(table, (indices, projection, cells))| tables[*table].iter_mut()
+                    .zip(indices.iter())
+                    .for_each(|(count, index)| *count += (target[*index] - projection[*index]) / cells));
+        })
+}
+
+/// Map each cell of a marginal table to its cell in the table over a subset of its columns.
+pub fn project_indices(marginal: &[usize], num_categories: &[usize], subset: &[usize]) -> Vec<usize> {
+    let sizes = marginal.iter().map(|column| num_categories[*column]).collect::<Vec<usize>>();
+    let positions = subset.iter()
+        .map(|column| marginal.iter().position(|v| v == column).unwrap())
+        .collect::<Vec<usize>>();
+
+    (0..sizes.iter().product())
+        .map(|mut cell| {
+            // decompose the row-major cell index
+            let mut multi_index = vec![0; sizes.len()];
+            sizes.iter().enumerate().rev().for_each(|(position, size)| {
+                multi_index[position] = cell % size;
+                cell /= size;
+            });
+            positions.iter()
+                .fold(0, |index, position| index * sizes[*position] + multi_index[*position])
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test_dp_marginals {
+    use ndarray::arr2;
+
+    use super::*;
+
+    #[test]
+    fn test_contingency_tables() {
+        let data = arr2(&[[0, 1], [1, 1], [1, 0], [2, 1]]).into_dyn();
+        let codes = encode_categories(&data, &[vec![0, 1], vec![0, 1]]).unwrap();
+        let num_categories = codes.iter().map(|(_, n)| *n).collect::<Vec<usize>>();
+        let codes = codes.into_iter().map(|(c, _)| c).collect::<Vec<_>>();
+
+        let tables = contingency_tables(&codes, &num_categories, &[vec![0, 1], vec![1], vec![1, 0]]);
+        // the record with value 2 is not a category of the first column
+        assert_eq!(tables, vec![
+            vec![0., 1., 1., 1.],
+            vec![1., 3.],
+            vec![0., 1., 1., 1.]]);
+    }
+
+    #[test]
+    fn test_make_consistent() {
+        let marginals = vec![vec![0, 1], vec![1, 2], vec![0, 2]];
+        let num_categories = vec![2, 3, 2];
+        let mut tables = vec![
+            vec![1., 2., 3., 4., 5., 6.],
+            vec![7., 1., 2., 0., 3., 5.],
+            vec![4., 4., 9., 2.]];
+
+        make_consistent(&mut tables, &marginals, &num_categories);
+
+        let project = |table: usize, subset: &[usize]| {
+            let indices = project_indices(&marginals[table], &num_categories, subset);
+            let size = subset.iter().map(|c| num_categories[*c]).product::<usize>();
+            let mut projection = vec![0.; size];
+            indices.iter().zip(tables[table].iter()).for_each(|(i, v)| projection[*i] += v);
+            projection
+        };
+        let close = |l: Vec<Float>, r: Vec<Float>| l.iter().zip(r.iter()).all(|(l, r)| (l - r).abs() < 1e-9);
+
+        assert!(close(project(0, &[1]), project(1, &[1])));
+        assert!(close(project(1, &[2]), project(2, &[2])));
+        assert!(close(project(0, &[0]), project(2, &[0])));
+        assert!(close(project(0, &[]), project(1, &[])));
+        assert!(close(project(0, &[]), project(2, &[])));
+    }
+}
@@ -0,0 +1,132 @@
+use ndarray::{arr0, arr1};
+
+use smartnoise_validator::{Float, proto};
+use smartnoise_validator::base::{Array, IndexKey, Jagged, ReleaseNode, Value};
+use smartnoise_validator::errors::*;
+use smartnoise_validator::proto::privacy_definition::Neighboring;
+use smartnoise_validator::utilities::privacy::get_epsilon;
+use smartnoise_validator::utilities::take_argument;
+
+use crate::components::Evaluable;
+use crate::NodeArguments;
+use crate::utilities::mechanisms::{exponential_mechanism, laplace_mechanism};
+
+impl Evaluable for proto::DpMode {
+    fn evaluate(&self, privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let privacy_definition = privacy_definition.as_ref()
+            .ok_or_else(|| Error::from("privacy_definition must be known"))?;
+
+        let mut counts = take_argument(&mut arguments, "data")?.array()?.int()?
+            .iter().map(|v| *v as Float).collect::<Vec<Float>>();
+        let sensitivity = take_argument(&mut arguments, "sensitivity")?.array()?.float()?
+            .iter().cloned().fold(0., Float::max);
+
+        if self.privacy_usage.len() != 1 {
+            return Err(Error::from("DPMode is not vectorized, only one privacy parameter may be passed"))
+        }
+        let epsilon = get_epsilon(&self.privacy_usage[0])?;
+
+        // the candidates are the midpoints of the bins, or the categories
+        let candidates: Value = match arguments.remove::<IndexKey>(&"edges".into()) {
+            Some(edges) => {
+                let edges = match edges.jagged()? {
+                    Jagged::Float(edges) if edges.len() == 1 => edges[0].clone(),
+                    Jagged::Int(edges) if edges.len() == 1 => edges[0].iter().map(|v| *v as Float).collect(),
+                    _ => return Err("edges must be one column of numeric values".into())
+                };
+                arr1(&edges.windows(2).map(|w| (w[0] + w[1]) / 2.).collect::<Vec<Float>>()).into_dyn().into()
+            }
+            None => match take_argument(&mut arguments, "categories")?.jagged()? {
+                Jagged::Bool(categories) if categories.len() == 1 => arr1(&categories[0]).into_dyn().into(),
+                Jagged::Float(categories) if categories.len() == 1 => arr1(&categories[0]).into_dyn().into(),
+                Jagged::Int(categories) if categories.len() == 1 => arr1(&categories[0]).into_dyn().into(),
+                Jagged::Str(categories) if categories.len() == 1 => arr1(&categories[0]).into_dyn().into(),
+                _ => return Err("categories must be one column".into())
+            }
+        };
+
+        // the histogram may end with the count of records outside the candidates, which is never selected
+        let num_candidates = match &candidates {
+            Value::Array(candidates) => candidates.num_records()?,
+            _ => return Err("candidates must be an array".into())
+        };
+        if num_candidates == 0 || counts.len() < num_candidates || counts.len() > num_candidates + 1 {
+            return Err("data must contain one count for each candidate, optionally followed by the count of records outside the candidates".into())
+        }
+        counts.truncate(num_candidates);
+
+        let enforce_constant_time = privacy_definition.protect_elapsed_time;
+
+        let index = match self.mechanism.to_lowercase().as_str() {
+            "automatic" | "exponential" => {
+                // the selection probabilities are unchanged by a shift, which keeps the weights finite
+                let max_count = counts.iter().cloned().fold(Float::MIN, Float::max);
+                let utilities = counts.iter().map(|count| count - max_count).collect::<Vec<Float>>();
+                exponential_mechanism(
+                    epsilon, sensitivity, &(0..num_candidates).collect::<Vec<usize>>(),
+                    utilities, enforce_constant_time)?
+            }
+            "reportnoisymax" => {
+                // counts only increase when records are added, so the noise may be halved
+                let scale = match Neighboring::from_i32(privacy_definition.neighboring) {
+                    Some(Neighboring::AddRemove) => sensitivity,
+                    _ => 2. * sensitivity
+                };
+                report_noisy_max(&counts, epsilon, scale, enforce_constant_time)?
+            }
+            _ => return Err(format!("Unexpected invalid token {:?}", self.mechanism).into())
+        };
+
+        Ok(ReleaseNode {
+            value: match candidates {
+                Value::Array(Array::Bool(candidates)) => arr0(candidates[index]).into_dyn().into(),
+                Value::Array(Array::Float(candidates)) => arr0(candidates[index]).into_dyn().into(),
+                Value::Array(Array::Int(candidates)) => arr0(candidates[index]).into_dyn().into(),
+                Value::Array(Array::Str(candidates)) => arr0(candidates[index].clone()).into_dyn().into(),
+                _ => return Err("candidates must be an array".into())
+            },
+            privacy_usages: Some(self.privacy_usage.clone()),
+            public: true,
+        })
+    }
+}
+
+/// Index of the largest count, after adding Laplace noise to each count.
+///
+/// Dwork and Roth (2014), The Algorithmic Foundations of Differential Privacy, Section 3.3
+///
+/// # Arguments
+/// * `counts` - Count of each candidate.
+/// * `epsilon` - Privacy loss parameter.
+/// * `sensitivity` - Scale of the noise is `sensitivity / epsilon`.
+/// * `enforce_constant_time` - Whether to force noise sampling to run in constant time.
+///
+/// # Return
+/// Index of the candidate with the largest noisy count.
+pub fn report_noisy_max(
+    counts: &[Float], epsilon: Float, sensitivity: Float, enforce_constant_time: bool,
+) -> Result<usize> {
+    let noisy_counts = counts.iter()
+        .map(|count| Ok(count + laplace_mechanism(epsilon, sensitivity, enforce_constant_time)?))
+        .collect::<Result<Vec<Float>>>()?;
+
+    noisy_counts.iter().enumerate()
+        .fold(None, |best: Option<(usize, Float)>, (index, count)| match best {
+            Some((_, best_count)) if best_count >= *count => best,
+            _ => Some((index, *count))
+        })
+        .map(|(index, _)| index)
+        .ok_or_else(|| Error::from("counts must not be empty"))
+}
+
+#[cfg(test)]
+mod test_dp_mode {
+    use super::*;
+
+    #[test]
+    fn test_report_noisy_max() {
+        // the noise is negligible relative to the gap between the counts
+        assert_eq!(report_noisy_max(&[10., 1000., 20.], 100., 1., false).unwrap(), 1);
+        assert!(report_noisy_max(&[], 1., 1., false).is_err());
+    }
+}
@@ -0,0 +1,254 @@
+use indexmap::map::IndexMap;
+use ndarray::{arr1, Array2};
+use noisy_float::types::n64;
+
+use smartnoise_validator::{Float, Integer, proto};
+use smartnoise_validator::base::{Array, IndexKey, Jagged, ReleaseNode, Value};
+use smartnoise_validator::errors::*;
+use smartnoise_validator::utilities::privacy::get_epsilon;
+use smartnoise_validator::utilities::take_argument;
+
+use crate::components::dp_marginals::{contingency_tables, encode_categories};
+use crate::components::Evaluable;
+use crate::NodeArguments;
+use crate::utilities::mechanisms::laplace_mechanism;
+
+/// Variances are floored to this proportion of the squared range of the column,
+/// as the noisy variances may not be positive.
+const MIN_VARIANCE_PROPORTION: Float = 1e-6;
+
+impl Evaluable for proto::DpNaiveBayes {
+    fn evaluate(&self, privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let label = take_argument(&mut arguments, "label")?.array()?;
+        let classes = take_argument(&mut arguments, "classes")?.jagged()?;
+        let data_categorical = arguments.remove::<IndexKey>(&"data_categorical".into());
+        let data_continuous = arguments.remove::<IndexKey>(&"data_continuous".into());
+        let sensitivity = take_argument(&mut arguments, "sensitivity")?.array()?.float()?
+            .iter().cloned().collect::<Vec<Float>>();
+
+        if self.privacy_usage.len() != 1 {
+            return Err(Error::from("DPNaiveBayes is not vectorized, only one privacy parameter may be passed"))
+        }
+        let epsilon = get_epsilon(&self.privacy_usage[0])?;
+
+        let enforce_constant_time = privacy_definition.as_ref()
+            .ok_or_else(|| Error::from("privacy_definition must be known"))?
+            .protect_elapsed_time;
+
+        let mut label_codes = encode(label, classes.clone())?;
+        if label_codes.len() != 1 {
+            return Err("label must contain one column".into())
+        }
+        let (label_codes, num_classes) = label_codes.remove(0);
+
+        // the first column of the tables is the label, followed by the categorical features
+        let mut codes = vec![label_codes];
+        let mut num_categories = vec![num_classes];
+        let categories = match data_categorical {
+            Some(data) => {
+                let categories = take_argument(&mut arguments, "categories")?.jagged()?;
+                encode(data.array()?, categories.clone())?.into_iter()
+                    .for_each(|(column_codes, column_num_categories)| {
+                        codes.push(column_codes);
+                        num_categories.push(column_num_categories);
+                    });
+                Some(categories)
+            }
+            None => None
+        };
+        let num_features_categorical = codes.len() - 1;
+
+        let continuous = match data_continuous {
+            Some(data) => {
+                let data = data.array()?.float()?;
+                let lower = take_argument(&mut arguments, "lower")?.array()?.float()?;
+                let upper = take_argument(&mut arguments, "upper")?.array()?.float()?;
+                let columns = data.gencolumns().into_iter()
+                    .map(|column| column.to_vec())
+                    .collect::<Vec<Vec<Float>>>();
+                if columns.len() != lower.len() || lower.len() != upper.len() {
+                    return Err("data_continuous must have one lower and upper bound for each column".into())
+                }
+                Some((columns, lower.iter().cloned().collect::<Vec<Float>>(), upper.iter().cloned().collect::<Vec<Float>>()))
+            }
+            None => None
+        };
+        let num_features_continuous = continuous.as_ref().map(|(columns, _, _)| columns.len()).unwrap_or(0);
+
+        // one sensitivity for the counts and histograms, and two for each continuous column
+        if sensitivity.len() != 1 + 2 * num_features_continuous {
+            return Err("sensitivity must be defined for the counts, and the sum and sum of squares of each continuous column".into())
+        }
+
+        // the budget is split evenly over the class counts, each histogram, and each sum and sum of squares
+        let epsilon = epsilon / (1 + num_features_categorical + 2 * num_features_continuous) as Float;
+        let privatize = |value: Float, sensitivity: Float| -> Result<Float> {
+            Ok(value + laplace_mechanism(epsilon, sensitivity, enforce_constant_time)?)
+        };
+
+        let marginals = (0..codes.len())
+            .map(|column| if column == 0 { vec![0] } else { vec![0, column] })
+            .collect::<Vec<Vec<usize>>>();
+        let tables = contingency_tables(&codes, &num_categories, &marginals).into_iter()
+            .map(|table| table.into_iter()
+                .map(|count| privatize(count, sensitivity[0]))
+                .collect::<Result<Vec<Float>>>())
+            .collect::<Result<Vec<Vec<Float>>>>()?;
+
+        let mut model = vec![
+            (IndexKey::from("classes"), Value::Array(flatten_categories(classes))),
+            (IndexKey::from("log_prior"), arr1(&log_probabilities(&tables[0])).into_dyn().into()),
+        ];
+
+        if let Some(categories) = categories {
+            // row c holds the log probability of each category of each feature, given class c
+            let log_likelihoods = (0..num_classes)
+                .map(|class| tables.iter().zip(num_categories.iter()).skip(1)
+                    .flat_map(|(table, num_categories)| log_probabilities(
+                        &table[class * num_categories..(class + 1) * num_categories]))
+                    .collect::<Vec<Float>>())
+                .collect::<Vec<Vec<Float>>>();
+            let total_categories = num_categories.iter().skip(1).sum();
+
+            model.push((IndexKey::from("categories"), Value::Array(flatten_categories(categories))));
+            model.push((IndexKey::from("num_categories"), arr1(&num_categories.iter().skip(1)
+                .map(|v| *v as Integer).collect::<Vec<Integer>>()).into_dyn().into()));
+            model.push((IndexKey::from("log_likelihoods"), Array2::from_shape_vec(
+                (num_classes, total_categories), log_likelihoods.concat())?.into_dyn().into()));
+        }
+
+        if let Some((columns, lower, upper)) = continuous {
+            let label_codes = &codes[0];
+            let mut means = Array2::<Float>::zeros((num_classes, columns.len()));
+            let mut variances = Array2::<Float>::zeros((num_classes, columns.len()));
+
+            for (index, column) in columns.iter().enumerate() {
+                let mut sums = vec![0.; num_classes];
+                let mut square_sums = vec![0.; num_classes];
+                label_codes.iter().zip(column.iter())
+                    .filter_map(|(class, value)| class.map(|class| (class, value)))
+                    .for_each(|(class, value)| {
+                        sums[class] += value;
+                        square_sums[class] += value.powi(2);
+                    });
+
+                for class in 0..num_classes {
+                    let (mean, variance) = gaussian_parameters(
+                        tables[0][class],
+                        privatize(sums[class], sensitivity[1 + 2 * index])?,
+                        privatize(square_sums[class], sensitivity[2 + 2 * index])?,
+                        lower[index], upper[index]);
+                    means[[class, index]] = mean;
+                    variances[[class, index]] = variance;
+                }
+            }
+
+            model.push((IndexKey::from("means"), means.into_dyn().into()));
+            model.push((IndexKey::from("variances"), variances.into_dyn().into()));
+        }
+
+        Ok(ReleaseNode {
+            value: Value::Dataframe(model.into_iter().collect::<IndexMap<IndexKey, Value>>()),
+            privacy_usages: Some(self.privacy_usage.clone()),
+            public: true,
+        })
+    }
+}
+
+/// Replace each value of the data with the index of its category, for any type of data.
+///
+/// See `encode_categories`.
+pub(crate) fn encode(data: Array, categories: Jagged) -> Result<Vec<(Vec<Option<usize>>, usize)>> {
+    match (data, categories) {
+        (Array::Bool(data), Jagged::Bool(categories)) =>
+            encode_categories(&data, &categories),
+        (Array::Float(data), Jagged::Float(categories)) =>
+            encode_categories(
+                &data.mapv(|v| n64(v as f64)),
+                &categories.into_iter()
+                    .map(|column| column.into_iter().map(|v| n64(v as f64)).collect())
+                    .collect::<Vec<Vec<_>>>()),
+        (Array::Int(data), Jagged::Int(categories)) =>
+            encode_categories(&data, &categories),
+        (Array::Str(data), Jagged::Str(categories)) =>
+            encode_categories(&data, &categories),
+        _ => Err("data and categories must be homogeneously typed".into())
+    }
+}
+
+/// Concatenate the categories of each column into one array.
+pub(crate) fn flatten_categories(categories: Jagged) -> Array {
+    match categories {
+        Jagged::Bool(categories) => Array::Bool(arr1(&categories.concat()).into_dyn()),
+        Jagged::Int(categories) => Array::Int(arr1(&categories.concat()).into_dyn()),
+        Jagged::Float(categories) => Array::Float(arr1(&categories.concat()).into_dyn()),
+        Jagged::Str(categories) => Array::Str(arr1(&categories.concat()).into_dyn()),
+    }
+}
+
+/// Log probabilities of each category from noisy counts, with add-one smoothing.
+///
+/// Negative counts are treated as zero, so every category has a positive probability.
+///
+/// # Arguments
+/// * `counts` - Noisy count of each category.
+///
+/// # Return
+/// The log probability of each category.
+pub fn log_probabilities(counts: &[Float]) -> Vec<Float> {
+    let total = counts.iter().map(|count| count.max(0.)).sum::<Float>() + counts.len() as Float;
+    counts.iter()
+        .map(|count| ((count.max(0.) + 1.) / total).ln())
+        .collect()
+}
+
+/// Mean and variance of a column within one class, from its noisy count, sum and sum of squares.
+///
+/// The mean is clamped to the bounds of the column, and the variance is clamped between
+/// a small proportion of the squared range and the largest variance possible within the bounds.
+///
+/// # Arguments
+/// * `count` - Noisy number of records in the class.
+/// * `sum` - Noisy sum of the column over the records in the class.
+/// * `square_sum` - Noisy sum of squares of the column over the records in the class.
+/// * `lower` - Lower bound of the column.
+/// * `upper` - Upper bound of the column.
+///
+/// # Return
+/// The mean and variance of the column within the class.
+pub fn gaussian_parameters(count: Float, sum: Float, square_sum: Float, lower: Float, upper: Float) -> (Float, Float) {
+    let count = count.max(1.);
+    let mean = (sum / count).max(lower).min(upper);
+    let range = upper - lower;
+    let variance = (square_sum / count - mean.powi(2))
+        .max(MIN_VARIANCE_PROPORTION * range.powi(2))
+        .min((range / 2.).powi(2));
+    (mean, variance)
+}
+
+#[cfg(test)]
+mod test_dp_naive_bayes {
+    use super::*;
+
+    #[test]
+    fn test_log_probabilities() {
+        let probabilities = log_probabilities(&[3., -2., 5.]).into_iter()
+            .map(Float::exp).collect::<Vec<Float>>();
+        assert!((probabilities.iter().sum::<Float>() - 1.).abs() < 1e-12);
+        assert!((probabilities[0] - 4. / 11.).abs() < 1e-12);
+        assert!((probabilities[1] - 1. / 11.).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_gaussian_parameters() {
+        // records [1, 2, 3]
+        let (mean, variance) = gaussian_parameters(3., 6., 14., 0., 10.);
+        assert!((mean - 2.).abs() < 1e-12);
+        assert!((variance - 2. / 3.).abs() < 1e-12);
+
+        // noise may drive the mean outside the bounds and the variance negative
+        let (mean, variance) = gaussian_parameters(-1., 20., 0., 0., 10.);
+        assert!((mean - 10.).abs() < 1e-12);
+        assert!(variance > 0.);
+    }
+}
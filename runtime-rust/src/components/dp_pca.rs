@@ -0,0 +1,286 @@
+use ndarray::{Array, Array1, Array2, Axis, s};
+
+use smartnoise_validator::{Float, proto};
+use smartnoise_validator::base::ReleaseNode;
+use smartnoise_validator::errors::*;
+use smartnoise_validator::utilities::privacy::{get_delta, get_epsilon};
+use smartnoise_validator::utilities::take_argument;
+
+use crate::components::Evaluable;
+use crate::NodeArguments;
+use crate::utilities::{linalg, noise};
+use crate::utilities::mechanisms::{gaussian_mechanism, laplace_mechanism};
+
+/// Upper bound on the number of rejections when sampling a direction
+const BINGHAM_MAX_PROPOSALS: usize = 100_000;
+
+impl Evaluable for proto::Dppca {
+    fn evaluate(&self, privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let upper_triangle = take_argument(&mut arguments, "data")?.array()?.float()?
+            .iter().cloned().collect::<Vec<Float>>();
+        let sensitivity = take_argument(&mut arguments, "sensitivity")?.array()?.first_float()?;
+
+        if self.privacy_usage.len() != 1 {
+            return Err(Error::from("DPPCA is not vectorized, only one privacy parameter may be passed"))
+        }
+        let epsilon = get_epsilon(&self.privacy_usage[0])?;
+        let delta = get_delta(&self.privacy_usage[0])?;
+
+        let enforce_constant_time = privacy_definition.as_ref()
+            .ok_or_else(|| Error::from("privacy_definition must be known"))?
+            .protect_elapsed_time;
+
+        let covariance = upper_triangle_to_symmetric(&upper_triangle)?;
+        let num_components = self.num_components as usize;
+
+        let components = match self.implementation.to_lowercase().as_str() {
+            "analyze-gauss" => analyze_gauss(
+                &covariance, num_components,
+                epsilon, delta, sensitivity, enforce_constant_time)?,
+            "iterative-exponential" => iterative_eigenvector_sampling(
+                &covariance, num_components,
+                epsilon, sensitivity, enforce_constant_time)?,
+            _ => return Err(format!("`{}` is not recognized as a valid implementation. Must be one of [`analyze-gauss`, `iterative-exponential`]", self.implementation).into())
+        };
+
+        Ok(ReleaseNode {
+            value: components.into_dyn().into(),
+            privacy_usages: Some(self.privacy_usage.clone()),
+            public: true,
+        })
+    }
+}
+
+/// Release the top principal components by perturbing the covariance matrix with Gaussian noise.
+///
+/// Noise is added to the upper triangle of the covariance matrix and mirrored into the lower triangle,
+/// so that the noisy matrix remains symmetric. The eigendecomposition of the noisy matrix is post-processing.
+///
+/// # Arguments
+/// * `covariance` - Symmetric covariance matrix of shape `(d, d)`.
+/// * `num_components` - Number of principal components to release.
+/// * `epsilon` - Privacy parameter.
+/// * `delta` - Privacy parameter.
+/// * `sensitivity` - L2 sensitivity of the upper triangle of the covariance matrix.
+/// * `enforce_constant_time` - Whether to force the noise sampling to run in constant time.
+///
+/// # Return
+/// One row per component. The first column is the variance, and the remaining columns are the direction.
+pub fn analyze_gauss(
+    covariance: &Array2<Float>, num_components: usize,
+    epsilon: Float, delta: Float, sensitivity: Float,
+    enforce_constant_time: bool,
+) -> Result<Array2<Float>> {
+    let d = covariance.nrows();
+    check_num_components(num_components, d)?;
+
+    let mut noisy_covariance = covariance.clone();
+    for i in 0..d {
+        for j in i..d {
+            noisy_covariance[[i, j]] += gaussian_mechanism(epsilon, delta, sensitivity, false, enforce_constant_time)?;
+            noisy_covariance[[j, i]] = noisy_covariance[[i, j]];
+        }
+    }
+
+    let (values, vectors) = linalg::symmetric_eigen(&noisy_covariance)?;
+
+    let mut components = Array2::<Float>::zeros((num_components, d + 1));
+    for i in 0..num_components {
+        components[[i, 0]] = values[i].max(0.);
+        components.row_mut(i).slice_mut(s![1..]).assign(&orient(vectors.column(i).to_owned()));
+    }
+    Ok(components)
+}
+
+/// Release the top principal components by iteratively sampling eigenvectors with the exponential mechanism.
+///
+/// Half of the budget is spread evenly over the directions. Each direction is sampled
+/// from the orthogonal complement of the previous directions, with density proportional to
+/// `exp(epsilon_i u^T C u / (2 Delta))`, where `Delta` bounds the change of `u^T C u` for any unit vector `u`.
+/// The other half of the budget releases the variances along the chosen directions with the Laplace mechanism.
+///
+/// # Arguments
+/// * `covariance` - Symmetric covariance matrix of shape `(d, d)`.
+/// * `num_components` - Number of principal components to release.
+/// * `epsilon` - Privacy parameter.
+/// * `sensitivity` - L2 sensitivity of the upper triangle of the covariance matrix.
+/// * `enforce_constant_time` - Whether to force the noise sampling to run in constant time.
+///
+/// # Return
+/// One row per component. The first column is the variance, and the remaining columns are the direction.
+pub fn iterative_eigenvector_sampling(
+    covariance: &Array2<Float>, num_components: usize,
+    epsilon: Float, sensitivity: Float,
+    enforce_constant_time: bool,
+) -> Result<Array2<Float>> {
+    let d = covariance.nrows();
+    check_num_components(num_components, d)?;
+
+    // the Frobenius norm of a symmetric matrix is at most sqrt(2) times the L2 norm of its upper triangle,
+    //     and bounds the change in u^T C u for unit vectors u
+    let frobenius_sensitivity = Float::sqrt(2.) * sensitivity;
+    let epsilon_direction = epsilon / (2. * num_components as Float);
+    let epsilon_variance = epsilon / 2.;
+
+    let mut directions: Vec<Array1<Float>> = Vec::new();
+    for _ in 0..num_components {
+        let basis = orthogonal_complement(&directions, d)?;
+        let projected = basis.t().dot(covariance).dot(&basis);
+
+        let scale = epsilon_direction / (2. * frobenius_sensitivity);
+        let sample = sample_bingham(&(projected * scale), enforce_constant_time)?;
+        directions.push(orient(basis.dot(&sample)));
+    }
+
+    // by Ky Fan, the L1 norm of the change in the k variances is at most sqrt(k) times the Frobenius norm
+    let variance_sensitivity = (num_components as Float).sqrt() * frobenius_sensitivity;
+
+    let mut components = Array2::<Float>::zeros((num_components, d + 1));
+    for (i, direction) in directions.iter().enumerate() {
+        let variance = direction.dot(&covariance.dot(direction))
+            + laplace_mechanism(epsilon_variance, variance_sensitivity, enforce_constant_time)?;
+        components[[i, 0]] = variance.max(0.);
+        components.row_mut(i).slice_mut(s![1..]).assign(direction);
+    }
+    Ok(components)
+}
+
+/// Sample a unit vector `x` with density proportional to `exp(x^T b x)` on the sphere.
+///
+/// Uses rejection sampling from an angular central Gaussian envelope,
+/// as described in Kent, Ganeiber and Mardia (2018).
+fn sample_bingham(b: &Array2<Float>, enforce_constant_time: bool) -> Result<Array1<Float>> {
+    let (values, vectors) = linalg::symmetric_eigen(b)?;
+    let dimension = values.len();
+
+    // in the eigenbasis, the density is proportional to exp(-sum_j a_j z_j^2), where a_j >= 0
+    let a = values.mapv(|value| values[0] - value);
+
+    // the envelope parameter solves sum_j 1 / (b + 2 a_j) = 1
+    let mut lower = 0.;
+    let mut upper = dimension as Float;
+    for _ in 0..100 {
+        let midpoint = (lower + upper) / 2.;
+        if a.iter().map(|a_j| 1. / (midpoint + 2. * a_j)).sum::<Float>() > 1. {
+            lower = midpoint;
+        } else {
+            upper = midpoint;
+        }
+    }
+    let envelope = upper;
+    let omega = a.mapv(|a_j| 1. + 2. * a_j / envelope);
+    let m = dimension as Float;
+
+    for _ in 0..BINGHAM_MAX_PROPOSALS {
+        let proposal = omega.iter()
+            .map(|omega_j| noise::sample_gaussian(0., 1. / omega_j.sqrt(), enforce_constant_time))
+            .collect::<Result<Array1<Float>>>()?;
+        let norm = proposal.dot(&proposal).sqrt();
+        if norm == 0. {
+            continue
+        }
+        let z = proposal / norm;
+
+        let log_acceptance = -a.iter().zip(z.iter()).map(|(a_j, z_j)| a_j * z_j.powi(2)).sum::<Float>()
+            + m / 2. * omega.iter().zip(z.iter()).map(|(omega_j, z_j)| omega_j * z_j.powi(2)).sum::<Float>().ln()
+            + (m - envelope) / 2. + m / 2. * (envelope / m).ln();
+
+        if noise::sample_uniform(0., 1., enforce_constant_time)?.ln() < log_acceptance {
+            return Ok(vectors.dot(&z))
+        }
+    }
+    Err("failed to sample a direction, the privacy parameters may be too large".into())
+}
+
+/// Orthonormal basis of the orthogonal complement of a set of orthonormal vectors.
+///
+/// # Return
+/// A matrix of shape `(d, d - k)` whose columns span the complement.
+fn orthogonal_complement(directions: &[Array1<Float>], d: usize) -> Result<Array2<Float>> {
+    let mut projection = Array2::<Float>::eye(d);
+    directions.iter().for_each(|direction| {
+        let direction = direction.view().insert_axis(Axis(1));
+        projection -= &direction.dot(&direction.t());
+    });
+
+    // the projection has eigenvalue one on the complement, and zero on the span of the directions
+    let (_, vectors) = linalg::symmetric_eigen(&projection)?;
+    Ok(vectors.slice(s![.., ..d - directions.len()]).to_owned())
+}
+
+/// Flip the sign of a direction, so that its largest-magnitude entry is positive.
+fn orient(direction: Array1<Float>) -> Array1<Float> {
+    let largest = direction.iter().cloned()
+        .fold(0., |largest: Float, v| if v.abs() > largest.abs() { v } else { largest });
+    if largest < 0. { -direction } else { direction }
+}
+
+fn check_num_components(num_components: usize, d: usize) -> Result<()> {
+    if num_components == 0 || num_components > d {
+        return Err(format!("num_components: must be between one and the number of columns ({})", d).into())
+    }
+    Ok(())
+}
+
+/// Reconstruct a symmetric matrix from its row-major upper triangle, as released by Covariance.
+pub(crate) fn upper_triangle_to_symmetric(upper_triangle: &[Float]) -> Result<Array2<Float>> {
+    let d = (((8 * upper_triangle.len() + 1) as Float).sqrt() as usize - 1) / 2;
+    if d * (d + 1) / 2 != upper_triangle.len() {
+        return Err("data: must be the upper triangle of a symmetric matrix".into())
+    }
+
+    let mut matrix = Array::zeros((d, d));
+    let mut entries = upper_triangle.iter();
+    for i in 0..d {
+        for j in i..d {
+            let entry = *entries.next().ok_or("data: too few entries")?;
+            matrix[[i, j]] = entry;
+            matrix[[j, i]] = entry;
+        }
+    }
+    Ok(matrix)
+}
+
+#[cfg(test)]
+mod test_dp_pca {
+    use ndarray::arr2;
+
+    use super::*;
+
+    #[test]
+    fn test_upper_triangle_to_symmetric() {
+        let matrix = upper_triangle_to_symmetric(&[1., 2., 3., 4., 5., 6.]).unwrap();
+        assert_eq!(matrix, arr2(&[[1., 2., 3.], [2., 4., 5.], [3., 5., 6.]]));
+        assert!(upper_triangle_to_symmetric(&[1., 2.]).is_err());
+    }
+
+    #[test]
+    fn test_analyze_gauss_recovers_direction() {
+        let covariance = arr2(&[[4., 0., 0.], [0., 1., 0.], [0., 0., 0.25]]);
+        let components = analyze_gauss(&covariance, 2, 1e6, 1e-6, 1., false).unwrap();
+
+        assert_eq!(components.dim(), (2, 4));
+        assert!((components[[0, 0]] - 4.).abs() < 1e-3);
+        assert!((components[[0, 1]] - 1.).abs() < 1e-3);
+        assert!((components[[1, 0]] - 1.).abs() < 1e-3);
+        assert!((components[[1, 2]] - 1.).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_iterative_eigenvector_sampling_is_orthonormal() {
+        let covariance = arr2(&[[4., 1., 0.], [1., 2., 0.], [0., 0., 1.]]);
+        let components = iterative_eigenvector_sampling(&covariance, 3, 1., 0.1, false).unwrap();
+
+        let directions = components.slice(s![.., 1..]).to_owned();
+        let gram = directions.dot(&directions.t());
+        assert!((gram - Array2::<Float>::eye(3)).iter().all(|v| v.abs() < 1e-8));
+        assert!(components.column(0).iter().all(|v| *v >= 0.));
+    }
+
+    #[test]
+    fn test_iterative_eigenvector_sampling_recovers_direction() {
+        let covariance = arr2(&[[10., 0.], [0., 1.]]);
+        let components = iterative_eigenvector_sampling(&covariance, 1, 100., 0.01, false).unwrap();
+        assert!(components[[0, 1]] > 0.99);
+    }
+}
@@ -0,0 +1,164 @@
+use ndarray::{Array, Array1, Array2, ArrayD};
+
+use smartnoise_validator::{Float, proto};
+use smartnoise_validator::base::ReleaseNode;
+use smartnoise_validator::errors::*;
+use smartnoise_validator::utilities::privacy::get_epsilon;
+use smartnoise_validator::utilities::take_argument;
+
+use crate::components::dp_logistic_regression::{clip_rows, sample_l2_noise};
+use crate::components::Evaluable;
+use crate::NodeArguments;
+use crate::utilities::to_nd;
+
+impl Evaluable for proto::DpQuantileRegression {
+    fn evaluate(&self, privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let data_x = to_nd(take_argument(&mut arguments, "data_x")?.array()?.float()?, 2)?;
+        let data_y = take_argument(&mut arguments, "data_y")?.array()?.float()?
+            .iter().cloned().collect::<Vec<Float>>();
+
+        if self.privacy_usage.len() != 1 {
+            return Err(Error::from("DPQuantileRegression is not vectorized, only one privacy parameter may be passed"))
+        }
+        let epsilon = get_epsilon(&self.privacy_usage[0])?;
+
+        let enforce_constant_time = privacy_definition.as_ref()
+            .ok_or_else(|| Error::from("privacy_definition must be known"))?
+            .protect_elapsed_time;
+
+        let coefficients = dp_quantile_regression(
+            &data_x, &data_y,
+            self.tau, self.clip_norm, self.num_iterations as usize, self.learning_rate,
+            epsilon, enforce_constant_time)?;
+
+        let num_columns = coefficients.len();
+        Ok(ReleaseNode {
+            value: Array::from_shape_vec((1, num_columns), coefficients.to_vec())?.into_dyn().into(),
+            privacy_usages: Some(self.privacy_usage.clone()),
+            public: true,
+        })
+    }
+}
+
+/// Fit a linear quantile regression under differential privacy.
+///
+/// Rows of `data_x` are rescaled to lie within an L2 ball of radius `clip_norm`.
+/// The check loss is then minimized by subgradient descent, where each step perturbs the mean subgradient.
+/// The subgradient of each record has L2 norm at most `max(tau, 1 - tau)`, regardless of `data_y`,
+/// so the targets need not be bounded.
+/// The budget is split evenly over the steps, and the average of the last half of the iterates is released.
+///
+/// Bassily, Smith and Thakurta (2014), Private Empirical Risk Minimization: Efficient Algorithms and Tight Error Bounds
+///
+/// # Arguments
+/// * `data_x` - Predictors, of shape `(n, d)`.
+/// * `data_y` - Targets.
+/// * `tau` - Quantile to model, within `(0, 1)`.
+/// * `clip_norm` - Maximum L2 norm of each row of `data_x`.
+/// * `num_iterations` - Number of gradient steps.
+/// * `learning_rate` - Size of the first step. Step `t` is scaled by `1 / sqrt(t)`.
+/// * `epsilon` - Privacy loss parameter.
+/// * `enforce_constant_time` - Whether to force noise sampling to run in constant time.
+///
+/// # Return
+/// The `d` privatized coefficients, on the scale of the original predictors.
+#[allow(clippy::too_many_arguments)]
+pub fn dp_quantile_regression(
+    data_x: &ArrayD<Float>, data_y: &[Float],
+    tau: Float, clip_norm: Float, num_iterations: usize, learning_rate: Float,
+    epsilon: Float, enforce_constant_time: bool,
+) -> Result<Array1<Float>> {
+    if tau <= 0. || tau >= 1. {
+        return Err("tau must be within (0, 1)".into())
+    }
+    if clip_norm <= 0. {
+        return Err("clip_norm must be greater than zero".into())
+    }
+    if num_iterations == 0 {
+        return Err("num_iterations must be greater than zero".into())
+    }
+
+    // the analysis assumes predictors in the unit ball
+    let data_x = clip_rows(data_x, clip_norm)? / clip_norm;
+    if data_x.nrows() != data_y.len() {
+        return Err("data_x and data_y must be the same length".into())
+    }
+    let (num_records, num_columns) = data_x.dim();
+    if num_records == 0 {
+        return Err("data must not be empty".into())
+    }
+
+    // substituting one record changes the mean subgradient by at most twice the bound on each subgradient
+    let sensitivity = 2. * tau.max(1. - tau) / num_records as Float;
+    let epsilon_step = epsilon / num_iterations as Float;
+
+    let mut coefficients = Array1::<Float>::zeros(num_columns);
+    let mut average = Array1::<Float>::zeros(num_columns);
+    // the early iterates are far from the optimum, so only the last half are averaged
+    let num_averaged = num_iterations - num_iterations / 2;
+    for step in 0..num_iterations {
+        let gradient = check_loss_gradient(&data_x, data_y, &coefficients, tau)
+            + sample_l2_noise(num_columns, sensitivity / epsilon_step, enforce_constant_time)?;
+        coefficients.scaled_add(-learning_rate / ((step + 1) as Float).sqrt(), &gradient);
+        if step >= num_iterations / 2 {
+            average.scaled_add(1. / num_averaged as Float, &coefficients);
+        }
+    }
+
+    // undo the rescaling, so that coefficients apply to the original predictors
+    Ok(average / clip_norm)
+}
+
+/// Mean subgradient of the check loss `1/n sum_i rho_tau(y_i - w'x_i)`,
+/// where `rho_tau(u) = u (tau - 1[u < 0])`.
+pub fn check_loss_gradient(
+    data_x: &Array2<Float>, data_y: &[Float], coefficients: &Array1<Float>, tau: Float,
+) -> Array1<Float> {
+    let mut gradient = Array1::<Float>::zeros(data_x.ncols());
+    data_x.genrows().into_iter().zip(data_y.iter()).for_each(|(x, y)| {
+        let below = if *y < x.dot(coefficients) { 1. } else { 0. };
+        gradient.scaled_add(below - tau, &x);
+    });
+    gradient / data_x.nrows() as Float
+}
+
+#[cfg(test)]
+mod test_dp_quantile_regression {
+    use ndarray::{arr1, arr2, Array2};
+
+    use crate::components::dp_quantile_regression::{check_loss_gradient, dp_quantile_regression};
+
+    #[test]
+    fn test_check_loss_gradient() {
+        let data_x = arr2(&[[1.], [1.], [1.], [1.]]);
+        let data_y = [1., 2., 3., 4.];
+
+        // below every target, so only the tau term contributes
+        assert_eq!(check_loss_gradient(&data_x, &data_y, &arr1(&[0.]), 0.25), arr1(&[-0.25]));
+        // above every target
+        assert_eq!(check_loss_gradient(&data_x, &data_y, &arr1(&[5.]), 0.25), arr1(&[0.75]));
+        // at the median, the subgradient vanishes
+        assert_eq!(check_loss_gradient(&data_x, &data_y, &arr1(&[2.5]), 0.5), arr1(&[0.]));
+    }
+
+    #[test]
+    fn test_dp_quantile_regression() {
+        // a constant column, and targets spread evenly around 2x
+        let num_records = 1000;
+        let data_x = Array2::from_shape_fn((num_records, 2), |(i, j)|
+            if j == 0 { 0.5 } else { (i % 10) as f64 / 10. - 0.45 }).into_dyn();
+        let data_y = (0..num_records)
+            .map(|i| 2. * data_x[[i, 1]] + (i / 10) as f64 / 100. - 0.5)
+            .collect::<Vec<f64>>();
+
+        let fit = |tau: f64| dp_quantile_regression(
+            &data_x, &data_y, tau, 1., 500, 5., 1000., false).unwrap();
+
+        let median = fit(0.5);
+        assert!(median.iter().all(|v| v.is_finite()));
+        assert!((median[1] - 2.).abs() < 0.2);
+
+        // the intercept increases with the quantile
+        assert!(fit(0.9)[0] > fit(0.1)[0]);
+    }
+}
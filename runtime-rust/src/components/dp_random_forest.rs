@@ -0,0 +1,17 @@
+use smartnoise_validator::proto;
+use smartnoise_validator::base::ReleaseNode;
+use smartnoise_validator::errors::*;
+
+use crate::components::dp_decision_tree::evaluate_forest;
+use crate::components::Evaluable;
+use crate::NodeArguments;
+
+impl Evaluable for proto::DpRandomForest {
+    fn evaluate(&self, privacy_definition: &Option<proto::PrivacyDefinition>, arguments: NodeArguments) -> Result<ReleaseNode> {
+        if self.num_trees == 0 {
+            return Err("num_trees must be greater than zero".into())
+        }
+        // each tree is grown on a disjoint partition of the records, with the entire budget
+        evaluate_forest(privacy_definition, arguments, &self.privacy_usage, self.max_depth as usize, self.num_trees as usize)
+    }
+}
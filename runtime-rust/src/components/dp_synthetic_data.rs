@@ -0,0 +1,316 @@
+use std::hash::Hash;
+
+use indexmap::map::IndexMap;
+use itertools::Itertools;
+use ndarray::{ArrayD, arr1};
+
+use smartnoise_validator::{Float, proto};
+use smartnoise_validator::base::{Array, IndexKey, Jagged, ReleaseNode, Value};
+use smartnoise_validator::errors::*;
+use smartnoise_validator::proto::privacy_definition::Neighboring;
+use smartnoise_validator::utilities::privacy::{get_delta, get_epsilon};
+use smartnoise_validator::utilities::take_argument;
+
+use crate::components::dp_marginals::{contingency_tables, encode_categories, project_indices};
+use crate::components::Evaluable;
+use crate::NodeArguments;
+use crate::utilities::noise;
+use crate::utilities::mechanisms::{exponential_mechanism, gaussian_mechanism};
+
+/// Number of passes of multiplicative weights updates over the measurements, after each measurement.
+const NUM_UPDATE_PASSES: usize = 20;
+
+impl Evaluable for proto::DpSyntheticData {
+    fn evaluate(&self, privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let names: Vec<IndexKey> = match take_argument(&mut arguments, "names")?.array()? {
+            Array::Str(names) => names.iter().map(|v| IndexKey::from(v.to_string())).collect(),
+            Array::Bool(names) => names.iter().map(|v| IndexKey::from(*v)).collect(),
+            Array::Int(names) => names.iter().map(|v| IndexKey::from(*v)).collect(),
+            _ => return Err("floats may not be used for column names".into())
+        };
+        let num_records = match arguments.remove::<IndexKey>(&"num_records".into()) {
+            Some(num_records) => Some(num_records.array()?.first_int()?),
+            None => None
+        };
+
+        if self.privacy_usage.len() != 1 {
+            return Err(Error::from("DPSyntheticData is not vectorized, only one privacy parameter may be passed"))
+        }
+        let epsilon = get_epsilon(&self.privacy_usage[0])?;
+        let delta = get_delta(&self.privacy_usage[0])?;
+
+        let privacy_definition = privacy_definition.as_ref()
+            .ok_or_else(|| Error::from("privacy_definition must be known"))?;
+        let neighboring = Neighboring::from_i32(privacy_definition.neighboring)
+            .ok_or_else(|| Error::from("neighboring definition must be either \"AddRemove\" or \"Substitute\""))?;
+        let enforce_constant_time = privacy_definition.protect_elapsed_time;
+
+        let synthesize = |codes: Vec<(Vec<Option<usize>>, usize)>| -> Result<Vec<Vec<usize>>> {
+            let num_categories = codes.iter().map(|(_, num_categories)| *num_categories).collect::<Vec<usize>>();
+            let codes = codes.into_iter().map(|(codes, _)| codes).collect::<Vec<_>>();
+
+            let (distribution, estimated_num_records) = mwem(
+                &codes, &num_categories,
+                self.k as usize, self.num_iterations as usize,
+                epsilon, delta, neighboring, enforce_constant_time)?;
+
+            let num_records = match num_records {
+                Some(num_records) => num_records as usize,
+                None => estimated_num_records.round().max(0.) as usize
+            };
+            sample_records(&distribution, &num_categories, num_records, enforce_constant_time)
+        };
+
+        let columns = match (take_argument(&mut arguments, "data")?.array()?, take_argument(&mut arguments, "categories")?.jagged()?) {
+            (Array::Bool(data), Jagged::Bool(categories)) =>
+                decode_categories(synthesize(encode_categories(&data, &categories)?)?, &categories),
+            (Array::Int(data), Jagged::Int(categories)) =>
+                decode_categories(synthesize(encode_categories(&data, &categories)?)?, &categories),
+            (Array::Str(data), Jagged::Str(categories)) =>
+                decode_categories(synthesize(encode_categories(&data, &categories)?)?, &categories),
+            (Array::Float(_), Jagged::Float(_)) =>
+                return Err("float data may not be categorical".into()),
+            _ => return Err("data and categories must be homogeneously typed".into())
+        };
+
+        Ok(ReleaseNode {
+            value: Value::Dataframe(names.into_iter().zip(columns)
+                .collect::<IndexMap<IndexKey, Value>>()),
+            privacy_usages: Some(self.privacy_usage.clone()),
+            public: true,
+        })
+    }
+}
+
+/// Fit a distribution over the joint domain with the Multiplicative Weights Exponential Mechanism.
+///
+/// The candidate queries are every marginal over at most `k` columns.
+/// Each iteration spends half of its budget to select the candidate whose table is worst approximated
+/// by the current distribution with the exponential mechanism,
+/// and the other half to measure the table with the Gaussian mechanism.
+/// The distribution is then updated with multiplicative weights towards all measurements so far.
+///
+/// Hardt, Ligett and McSherry (2012), A Simple and Practical Algorithm for Differentially Private Data Release
+///
+/// # Arguments
+/// * `codes` - Category index of each record, for each column.
+/// * `num_categories` - Number of categories in each column.
+/// * `k` - Maximum number of columns in each candidate marginal.
+/// * `num_iterations` - Number of marginals to select and measure.
+/// * `epsilon` - Multiplicative privacy loss parameter, spent evenly over the iterations.
+/// * `delta` - Additive privacy loss parameter, spent evenly over the measurements.
+/// * `neighboring` - Definition of neighboring datasets.
+/// * `enforce_constant_time` - Whether to force the noise sampling to run in constant time.
+///
+/// # Return
+/// The probability of each cell of the joint domain, in row-major order over the categories of the columns,
+/// and the number of records estimated from the measurements.
+#[allow(clippy::too_many_arguments)]
+pub fn mwem(
+    codes: &[Vec<Option<usize>>], num_categories: &[usize],
+    k: usize, num_iterations: usize,
+    epsilon: Float, delta: Float,
+    neighboring: Neighboring, enforce_constant_time: bool,
+) -> Result<(Vec<Float>, Float)> {
+    let num_columns = codes.len();
+    if k == 0 || k > num_columns {
+        return Err("k must be between one and the number of columns".into())
+    }
+    if num_iterations == 0 {
+        return Err("num_iterations must be greater than zero".into())
+    }
+
+    let candidates = (1..=k)
+        .flat_map(|size| (0..num_columns).combinations(size))
+        .collect::<Vec<Vec<usize>>>();
+    let tables = contingency_tables(codes, num_categories, &candidates);
+
+    let columns = (0..num_columns).collect::<Vec<usize>>();
+    let domain_size = num_categories.iter().product::<usize>();
+    let mut distribution = vec![1. / domain_size as Float; domain_size];
+
+    // the L1 error of a table changes by at most two when a record is changed
+    let selection_sensitivity = 2.;
+    let measurement_sensitivity = match neighboring {
+        Neighboring::AddRemove => 1.,
+        Neighboring::Substitute => Float::sqrt(2.)
+    };
+    let epsilon_iteration = epsilon / num_iterations as Float;
+    let delta_iteration = delta / num_iterations as Float;
+
+    // projections from the joint domain onto each measured table, and the noisy counts of each table
+    let mut measurements: Vec<(Vec<usize>, Vec<Float>)> = Vec::new();
+
+    for _ in 0..num_iterations {
+        let errors = candidates.iter().zip(tables.iter())
+            .map(|(candidate, table)| {
+                let num_records = table.iter().sum::<Float>();
+                let estimate = project(&distribution, &project_indices(&columns, num_categories, candidate), table.len());
+                table.iter().zip(estimate.iter())
+                    .map(|(count, probability)| (count - num_records * probability).abs())
+                    .sum::<Float>()
+            })
+            .collect::<Vec<Float>>();
+
+        // shift the utilities to keep the exponentiated scores finite. This does not change the selection probabilities
+        let max_error = errors.iter().cloned().fold(Float::NEG_INFINITY, Float::max);
+        let selected = exponential_mechanism(
+            epsilon_iteration / 2., selection_sensitivity,
+            &(0..candidates.len()).collect::<Vec<usize>>(),
+            errors.into_iter().map(|error| error - max_error).collect(),
+            enforce_constant_time)?;
+
+        let measurement = tables[selected].iter()
+            .map(|count| Ok(count + gaussian_mechanism(
+                epsilon_iteration / 2., delta_iteration, measurement_sensitivity,
+                false, enforce_constant_time)?))
+            .collect::<Result<Vec<Float>>>()?;
+        measurements.push((project_indices(&columns, num_categories, &candidates[selected]), measurement));
+
+        let estimated_num_records = estimate_num_records(&measurements);
+        for _ in 0..NUM_UPDATE_PASSES {
+            measurements.iter().try_for_each(|(indices, measurement)| {
+                let estimate = project(&distribution, indices, measurement.len());
+                distribution.iter_mut().zip(indices.iter())
+                    .for_each(|(probability, index)| *probability *= update_weight(
+                        measurement[*index] / estimated_num_records, estimate[*index]));
+                let total = distribution.iter().sum::<Float>();
+                if !total.is_finite() || total <= 0. {
+                    return Err(Error::from("multiplicative weights update failed to produce a distribution"))
+                }
+                distribution.iter_mut().for_each(|probability| *probability /= total);
+                Ok(())
+            })?
+        }
+    }
+
+    Ok((distribution, estimate_num_records(&measurements)))
+}
+
+/// Multiplicative weight of a cell, given the measured and estimated proportion of records in its cell of a table.
+///
+/// A noisy proportion may lie far outside [0, 1] when the noise dominates the counts,
+/// so it is clamped to keep the normalised error, and therefore the exponent, within [-1, 1].
+fn update_weight(measured: Float, estimated: Float) -> Float {
+    ((measured.clamp(0., 1.) - estimated) / 2.).exp()
+}
+
+/// Average of the noisy totals of the measured tables, at least one.
+fn estimate_num_records(measurements: &[(Vec<usize>, Vec<Float>)]) -> Float {
+    (measurements.iter().map(|(_, measurement)| measurement.iter().sum::<Float>()).sum::<Float>()
+        / measurements.len() as Float).max(1.)
+}
+
+/// Sum the distribution into the cells of a table.
+fn project(distribution: &[Float], indices: &[usize], size: usize) -> Vec<Float> {
+    let mut projection = vec![0.; size];
+    distribution.iter().zip(indices.iter())
+        .for_each(|(probability, index)| projection[*index] += probability);
+    projection
+}
+
+/// Sample records from a distribution over the joint domain.
+///
+/// # Arguments
+/// * `distribution` - Probability of each cell of the joint domain, in row-major order over the categories of the columns.
+/// * `num_categories` - Number of categories in each column.
+/// * `num_records` - Number of records to sample.
+/// * `enforce_constant_time` - Whether to force the uniform sampling to run in constant time.
+///
+/// # Return
+/// For each column, the category index of each sampled record.
+pub fn sample_records(
+    distribution: &[Float], num_categories: &[usize],
+    num_records: usize, enforce_constant_time: bool,
+) -> Result<Vec<Vec<usize>>> {
+    let cumulative = distribution.iter()
+        .scan(0., |total, probability| {
+            *total += probability;
+            Some(*total)
+        })
+        .collect::<Vec<Float>>();
+    let total = *cumulative.last().ok_or_else(|| Error::from("the joint domain may not be empty"))?;
+    if distribution.iter().any(|probability| !probability.is_finite() || *probability < 0.) || total <= 0. {
+        return Err("distribution must be non-negative and finite, with a positive total".into())
+    }
+
+    let mut columns = vec![Vec::with_capacity(num_records); num_categories.len()];
+    for _ in 0..num_records {
+        let target = noise::sample_uniform(0., total, enforce_constant_time)?;
+        if target.is_nan() {
+            return Err("sampled target must be comparable to the cumulative distribution".into())
+        }
+        let mut cell = cumulative.partition_point(|v| *v < target).min(cumulative.len() - 1);
+        // decompose the row-major cell index
+        for (column, size) in num_categories.iter().enumerate().rev() {
+            columns[column].push(cell % size);
+            cell /= size;
+        }
+    }
+    Ok(columns)
+}
+
+/// Replace each category index with its category.
+fn decode_categories<T: Clone + Eq + Hash>(codes: Vec<Vec<usize>>, categories: &[Vec<T>]) -> Vec<Value>
+    where Value: From<ArrayD<T>> {
+    codes.into_iter().zip(categories.iter())
+        .map(|(codes, categories)| arr1(&codes.into_iter()
+            .map(|code| categories[code].clone())
+            .collect::<Vec<T>>()).into_dyn().into())
+        .collect()
+}
+
+#[cfg(test)]
+mod test_dp_synthetic_data {
+    use super::*;
+
+    #[test]
+    fn test_sample_records() {
+        // all mass on the cell (1, 2)
+        let distribution = vec![0., 0., 0., 0., 0., 1.];
+        let columns = sample_records(&distribution, &[2, 3], 10, false).unwrap();
+
+        assert_eq!(columns, vec![vec![1; 10], vec![2; 10]]);
+    }
+
+    #[test]
+    fn test_mwem_fits_marginals() {
+        // the first column is always 0, the second column is uniform
+        let codes = vec![
+            vec![Some(0); 1000],
+            (0..1000).map(|i| Some(i % 2)).collect::<Vec<_>>()];
+
+        let (distribution, num_records) = mwem(
+            &codes, &[2, 2], 1, 4, 100., 1e-6,
+            Neighboring::AddRemove, false).unwrap();
+
+        assert_eq!(distribution.len(), 4);
+        assert!((distribution.iter().sum::<Float>() - 1.).abs() < 1e-9);
+        assert!((num_records - 1000.).abs() < 10.);
+        assert!(distribution[0] + distribution[1] > 0.9);
+    }
+
+    #[test]
+    fn test_mwem_low_epsilon() {
+        // the noise dominates the counts, so the noisy proportions lie far outside [0, 1]
+        let codes = vec![
+            (0..20).map(|i| Some(i % 3)).collect::<Vec<_>>(),
+            (0..20).map(|i| Some(i % 2)).collect::<Vec<_>>()];
+
+        let (distribution, _) = mwem(
+            &codes, &[3, 2], 2, 10, 1e-4, 1e-6,
+            Neighboring::Substitute, false).unwrap();
+
+        assert!(distribution.iter().all(|probability| probability.is_finite() && *probability >= 0.));
+        assert!((distribution.iter().sum::<Float>() - 1.).abs() < 1e-9);
+        assert!(sample_records(&distribution, &[3, 2], 10, false).is_ok());
+    }
+
+    #[test]
+    fn test_update_weight_bounded() {
+        assert_eq!(update_weight(1e12, 0.), Float::exp(0.5));
+        assert_eq!(update_weight(-1e12, 1.), Float::exp(-0.5));
+        assert!(sample_records(&[Float::NAN, 1.], &[2], 1, false).is_err());
+        assert!(sample_records(&[0., 0.], &[2], 1, false).is_err());
+    }
+}
@@ -0,0 +1,183 @@
+use std::hash::Hash;
+
+use indexmap::map::IndexMap;
+use ndarray::{ArrayD, Axis};
+
+use smartnoise_validator::{Integer, proto};
+use smartnoise_validator::base::{Array, Jagged, ReleaseNode};
+use smartnoise_validator::errors::*;
+use smartnoise_validator::utilities::{standardize_categorical_argument, standardize_null_target_argument, take_argument};
+
+use crate::components::Evaluable;
+use crate::NodeArguments;
+use crate::utilities::{get_num_columns, to_nd};
+
+impl Evaluable for proto::OneHot {
+    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let indicators = match (
+            take_argument(&mut arguments, "data")?.array()?,
+            take_argument(&mut arguments, "categories")?.jagged()?) {
+            (Array::Int(data), Jagged::Int(categories)) => one_hot(data, categories)?,
+            (Array::Str(data), Jagged::Str(categories)) => one_hot(data, categories)?,
+            (Array::Bool(data), Jagged::Bool(categories)) => one_hot(data, categories)?,
+            _ => return Err("OneHot: data and categories must share the same data type, and may not be float".into())
+        };
+
+        Ok(ReleaseNode::new(match self.atomic_type.to_lowercase().as_str() {
+            "int" | "integer" => indicators.mapv(|v| v as Integer).into(),
+            "bool" => indicators.into(),
+            _ => return Err("atomic_type: must be one of \"int\" or \"bool\"".into())
+        }))
+    }
+}
+
+impl Evaluable for proto::Recode {
+    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let indices = match (
+            take_argument(&mut arguments, "data")?.array()?,
+            take_argument(&mut arguments, "categories")?.jagged()?) {
+            (Array::Int(data), Jagged::Int(categories)) => category_indices(data, categories)?,
+            (Array::Str(data), Jagged::Str(categories)) => category_indices(data, categories)?,
+            (Array::Bool(data), Jagged::Bool(categories)) => category_indices(data, categories)?,
+            _ => return Err("Recode: data and categories must share the same data type, and may not be float".into())
+        };
+
+        Ok(ReleaseNode::new(match (
+            take_argument(&mut arguments, "recoded")?.jagged()?,
+            take_argument(&mut arguments, "null_value")?.array()?) {
+            (Jagged::Float(recoded), Array::Float(null)) => recode_indices(indices, recoded, null)?.into(),
+            (Jagged::Int(recoded), Array::Int(null)) => recode_indices(indices, recoded, null)?.into(),
+            (Jagged::Str(recoded), Array::Str(null)) => recode_indices(indices, recoded, null)?.into(),
+            (Jagged::Bool(recoded), Array::Bool(null)) => recode_indices(indices, recoded, null)?.into(),
+            _ => return Err("Recode: recoded and null_value must share the same data type".into())
+        }))
+    }
+}
+
+/// Expand each column of categorical data into indicator columns.
+///
+/// # Arguments
+/// * `data` - Categorical data to be encoded.
+/// * `categories` - For each column, the categories that are given an indicator column.
+///
+/// # Return
+/// One indicator column for each category of each column, in order.
+/// Values outside of the categories are false in every indicator column of their column.
+///
+/// # Example
+/// ```
+/// use ndarray::{arr1, arr2};
+/// use smartnoise_runtime::components::encoding::one_hot;
+///
+/// let data = arr1(&["a".to_string(), "c".to_string(), "d".to_string()]).into_dyn();
+/// let categories = vec![vec!["a".to_string(), "b".to_string(), "c".to_string()]];
+/// let indicators = one_hot(data, categories).unwrap();
+/// assert_eq!(indicators, arr2(&[
+///     [true, false, false],
+///     [false, false, true],
+///     [false, false, false]]).into_dyn());
+/// ```
+pub fn one_hot<T: Clone + Eq + Hash + Ord>(
+    data: ArrayD<T>, categories: Vec<Vec<T>>
+) -> Result<ArrayD<bool>> {
+    let num_columns = get_num_columns(&data)?;
+    let categories = standardize_categorical_argument(categories, num_columns)?;
+    if categories.len() as i64 != num_columns {
+        return Err("categories must contain one set of categories, or one set for each column of data".into())
+    }
+    let num_indicators = categories.iter().map(Vec::len).sum::<usize>();
+
+    let data = to_nd(data, 2)?;
+    let indicators = data.outer_iter()
+        .flat_map(|row| row.iter().zip(categories.iter())
+            .flat_map(|(value, categories)| categories.iter()
+                .map(move |category| category == value))
+            .collect::<Vec<bool>>())
+        .collect::<Vec<bool>>();
+
+    Ok(ndarray::Array::from_shape_vec(
+        vec![data.len_of(Axis(0)), num_indicators], indicators)?.into_dyn())
+}
+
+/// Map each column of categorical data to new categories through a lookup table.
+///
+/// # Arguments
+/// * `data` - Categorical data to be recoded.
+/// * `categories` - For each column, the categories to be recoded.
+/// * `recoded` - For each column, the recoded category of each category.
+/// * `null_value` - For each column, the value to which elements not included in `categories` will be mapped.
+///
+/// # Return
+/// Recoded data.
+///
+/// # Example
+/// ```
+/// use ndarray::{arr0, arr1};
+/// use smartnoise_runtime::components::encoding::recode;
+///
+/// let data = arr1(&["cat".to_string(), "dog".to_string(), "fish".to_string(), "frog".to_string()]).into_dyn();
+/// let categories = vec![vec!["cat".to_string(), "dog".to_string(), "fish".to_string()]];
+/// let recoded = vec![vec![1, 1, 0]];
+/// let recoded_data = recode(data, categories, recoded, arr0(-1).into_dyn()).unwrap();
+/// assert_eq!(recoded_data, arr1(&[1, 1, 0, -1]).into_dyn());
+/// ```
+pub fn recode<T: Clone + Eq + Hash, U: Clone>(
+    data: ArrayD<T>, categories: Vec<Vec<T>>, recoded: Vec<Vec<U>>, null_value: ArrayD<U>
+) -> Result<ArrayD<U>> {
+    recode_indices(category_indices(data, categories)?, recoded, null_value)
+}
+
+/// Position of each value within the categories of its column, if it is a category.
+fn category_indices<T: Clone + Eq + Hash>(
+    data: ArrayD<T>, categories: Vec<Vec<T>>
+) -> Result<ArrayD<Option<usize>>> {
+    let lookups = standardize_columns(categories, get_num_columns(&data)?)?.into_iter()
+        .map(|categories| categories.into_iter().enumerate()
+            .map(|(index, category)| (category, index))
+            .collect::<IndexMap<T, usize>>())
+        .collect::<Vec<_>>();
+
+    let mut indices = ArrayD::<Option<usize>>::default(data.shape());
+    indices.gencolumns_mut().into_iter()
+        .zip(data.gencolumns())
+        .zip(lookups.iter())
+        .for_each(|((mut indices, data), lookup)| indices.iter_mut().zip(data.iter())
+            .for_each(|(index, value)| *index = lookup.get(value).cloned()));
+    Ok(indices)
+}
+
+/// Replace the position of each category with its recoded category.
+fn recode_indices<U: Clone>(
+    indices: ArrayD<Option<usize>>, recoded: Vec<Vec<U>>, null_value: ArrayD<U>
+) -> Result<ArrayD<U>> {
+    let num_columns = get_num_columns(&indices)?;
+    let recoded = standardize_columns(recoded, num_columns)?;
+    let null_value = standardize_null_target_argument(null_value, num_columns)?;
+
+    let mut output = indices.mapv(|_| null_value[0].clone());
+    output.gencolumns_mut().into_iter()
+        .zip(indices.gencolumns())
+        .zip(recoded.iter().zip(null_value.iter()))
+        .try_for_each(|((mut output, indices), (recoded, null))| output.iter_mut().zip(indices.iter())
+            .try_for_each(|(output, index)| {
+                *output = match index {
+                    Some(index) => recoded.get(*index)
+                        .ok_or_else(|| Error::from("recoded: must contain one recoded category for each category"))?
+                        .clone(),
+                    None => null.clone()
+                };
+                Ok::<_, Error>(())
+            }))?;
+    Ok(output)
+}
+
+/// Broadcast a set of values for one column across all columns, without deduplicating.
+fn standardize_columns<T: Clone>(values: Vec<T>, num_columns: i64) -> Result<Vec<T>> {
+    if values.len() as i64 == num_columns {
+        return Ok(values)
+    }
+    if values.len() == 1 {
+        return Ok((0..num_columns).map(|_| values[0].clone()).collect())
+    }
+    Err("must contain one set of values, or one set for each column of data".into())
+}
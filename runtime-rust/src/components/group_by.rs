@@ -0,0 +1,115 @@
+use indexmap::map::IndexMap;
+use ndarray::{arr1, ArrayD};
+
+use smartnoise_validator::proto;
+use smartnoise_validator::base::{Array, IndexKey, ReleaseNode, Value};
+use smartnoise_validator::components::group_by::{get_column_names, split_key};
+use smartnoise_validator::errors::*;
+use smartnoise_validator::utilities::take_argument;
+
+use crate::components::Evaluable;
+use crate::NodeArguments;
+
+impl Evaluable for proto::GroupBy {
+    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let groups = take_argument(&mut arguments, "data")?.partitions()?;
+        let mut names = get_column_names(&take_argument(&mut arguments, "names")?)?;
+
+        // restore the value of each key column from the key of each group
+        let num_keys = split_key(groups.keys().next()
+            .ok_or_else(|| Error::from("data: must have at least one group"))?).len();
+        let mut key_columns = vec![Vec::with_capacity(groups.len()); num_keys];
+        let mut aggregates = Vec::with_capacity(groups.len());
+        for (key, aggregate) in groups.into_iter() {
+            let key = split_key(&key);
+            if key.len() != num_keys {
+                return Err("data: each group must have the same number of keys".into())
+            }
+            key.into_iter().zip(key_columns.iter_mut())
+                .for_each(|(key, column)| column.push(key));
+            aggregates.push(aggregate.array()?);
+        }
+
+        if names.len() < num_keys {
+            return Err("names: must name each key column, followed by each value the aggregator releases".into())
+        }
+        let aggregate_names = names.split_off(num_keys);
+        let aggregate_columns = transpose_aggregates(aggregates, aggregate_names.len())
+            .chain_err(|| "names: must name each key column, followed by each value the aggregator releases")?;
+
+        let key_columns = key_columns.into_iter()
+            .map(keys_to_column).collect::<Result<Vec<Value>>>()?;
+
+        let mut grouped = IndexMap::new();
+        for (name, column) in names.into_iter().chain(aggregate_names)
+            .zip(key_columns.into_iter().chain(aggregate_columns)) {
+            if grouped.contains_key(&name) {
+                return Err("names: must be unique".into())
+            }
+            grouped.insert(name, column);
+        }
+
+        Ok(ReleaseNode::new(Value::Dataframe(grouped)))
+    }
+}
+
+/// Collect the keys of one key column into an array.
+fn keys_to_column(keys: Vec<IndexKey>) -> Result<Value> {
+    Ok(match keys.first() {
+        Some(IndexKey::Str(_)) => arr1(&keys.into_iter().map(|key| match key {
+            IndexKey::Str(key) => Ok(key),
+            _ => Err("keys must share the same data type".into())
+        }).collect::<Result<Vec<String>>>()?).into_dyn().into(),
+        Some(IndexKey::Int(_)) => arr1(&keys.into_iter().map(|key| match key {
+            IndexKey::Int(key) => Ok(key),
+            _ => Err("keys must share the same data type".into())
+        }).collect::<Result<Vec<_>>>()?).into_dyn().into(),
+        Some(IndexKey::Bool(_)) => arr1(&keys.into_iter().map(|key| match key {
+            IndexKey::Bool(key) => Ok(key),
+            _ => Err("keys must share the same data type".into())
+        }).collect::<Result<Vec<bool>>>()?).into_dyn().into(),
+        Some(IndexKey::Tuple(_)) => return Err("keys may not be nested".into()),
+        None => return Err("data: must have at least one group".into())
+    })
+}
+
+/// Split the aggregate of each group into one column for each aggregated column.
+fn transpose_aggregates(aggregates: Vec<Array>, num_columns: usize) -> Result<Vec<Value>> {
+    Ok(match aggregates.first().ok_or_else(|| Error::from("data: must have at least one group"))? {
+        Array::Float(_) => transpose(aggregates.into_iter().map(|v| v.float()).collect::<Result<_>>()?, num_columns)?
+            .into_iter().map(Value::from).collect(),
+        Array::Int(_) => transpose(aggregates.into_iter().map(|v| v.int()).collect::<Result<_>>()?, num_columns)?
+            .into_iter().map(Value::from).collect(),
+        Array::Bool(_) => transpose(aggregates.into_iter().map(|v| v.bool()).collect::<Result<_>>()?, num_columns)?
+            .into_iter().map(Value::from).collect(),
+        Array::Str(_) => transpose(aggregates.into_iter().map(|v| v.string()).collect::<Result<_>>()?, num_columns)?
+            .into_iter().map(Value::from).collect(),
+    })
+}
+
+/// Each aggregate is one row, regardless of its shape.
+fn transpose<T: Clone>(rows: Vec<ArrayD<T>>, num_columns: usize) -> Result<Vec<ArrayD<T>>> {
+    if rows.iter().any(|row| row.len() != num_columns) {
+        return Err("each aggregate must have one value for each column".into())
+    }
+    Ok((0..num_columns)
+        .map(|column| arr1(&rows.iter()
+            .map(|row| row.iter().nth(column).cloned().unwrap())
+            .collect::<Vec<T>>()).into_dyn())
+        .collect())
+}
+
+#[cfg(test)]
+mod test_group_by {
+    use ndarray::arr2;
+
+    use super::*;
+
+    #[test]
+    fn test_transpose() {
+        let rows = vec![arr1(&[1, 2]).into_dyn(), arr2(&[[3, 4]]).into_dyn()];
+        assert_eq!(transpose(rows.clone(), 2).unwrap(),
+                   vec![arr1(&[1, 3]).into_dyn(), arr1(&[2, 4]).into_dyn()]);
+        assert!(transpose(rows, 1).is_err());
+    }
+}
@@ -143,7 +143,7 @@ fn column_stack(
     };
 
     match data_type {
-        DataType::Unknown => unreachable!(),
+        DataType::Unknown | DataType::Timestamp => unreachable!(),
         DataType::Float => {
             let chunks = column_names.iter()
                 .map(|column_name| dataframe.remove(column_name)
@@ -0,0 +1,168 @@
+use std::collections::HashMap;
+
+use indexmap::map::IndexMap;
+use ndarray::{ArrayD, Axis};
+
+use smartnoise_validator::{Float, proto};
+use smartnoise_validator::base::{Array, IndexKey, ReleaseNode, Value};
+use smartnoise_validator::errors::*;
+use smartnoise_validator::utilities::take_argument;
+
+use crate::components::Evaluable;
+use crate::NodeArguments;
+
+impl Evaluable for proto::Join {
+    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let left = take_argument(&mut arguments, "left")?.dataframe()?;
+        let right = take_argument(&mut arguments, "right")?.dataframe()?;
+        let left_key = IndexKey::new(take_argument(&mut arguments, "left_key")?.array()?)?;
+        let right_key = IndexKey::new(take_argument(&mut arguments, "right_key")?.array()?)?;
+        let bound_left = take_argument(&mut arguments, "bound_left")?.array()?.first_bool()?;
+        let bound_right = take_argument(&mut arguments, "bound_right")?.array()?.first_bool()?;
+
+        let how = self.how.to_lowercase();
+        if how != "inner" && how != "left" {
+            return Err(format!("how: unrecognized join {:?}, must be one of [inner, left]", self.how).into())
+        }
+        if self.max_matches == 0 {
+            return Err("max_matches must be greater than zero".into())
+        }
+        let max_matches = self.max_matches as usize;
+
+        let left_keys = get_keys(left.get(&left_key)
+            .ok_or_else(|| Error::from("left_key: column is not in the left table"))?)?;
+        let right_keys = get_keys(right.get(&right_key)
+            .ok_or_else(|| Error::from("right_key: column is not in the right table"))?)?;
+
+        let rows = join_rows(
+            &left_keys, &right_keys,
+            if bound_left { Some(max_matches) } else { None },
+            if bound_right { Some(max_matches) } else { None },
+            how == "left");
+
+        let left_rows = rows.iter().map(|(left_row, _)| Some(*left_row)).collect::<Vec<Option<usize>>>();
+        let right_rows = rows.iter().map(|(_, right_row)| *right_row).collect::<Vec<Option<usize>>>();
+
+        let mut joined = IndexMap::new();
+        for (name, column) in left.into_iter() {
+            joined.insert(name, select_rows(column, &left_rows)?);
+        }
+        for (name, column) in right.into_iter().filter(|(name, _)| name != &right_key) {
+            if joined.contains_key(&name) {
+                return Err(format!("{}: column names must be unique across the joined tables", name.to_string()).into())
+            }
+            let column = select_rows(column, &right_rows)
+                .chain_err(|| format!("{}: unmatched rows may not be represented", name.to_string()))?;
+            joined.insert(name, column);
+        }
+
+        Ok(ReleaseNode::new(Value::Dataframe(joined)))
+    }
+}
+
+/// Pair the rows of two tables with equal keys.
+///
+/// # Arguments
+/// * `left_keys` - Key of each row of the left table.
+/// * `right_keys` - Key of each row of the right table.
+/// * `left_bound` - If set, only the first rows of each key of the left table are matched. Later rows are treated as unmatched.
+/// * `right_bound` - If set, only the first rows of each key of the right table are joined.
+/// * `keep_unmatched` - Whether rows of the left table without a match are kept, paired with `None`.
+///
+/// # Return
+/// Index of the left row and right row of each row of the result, in the order of the left table.
+pub fn join_rows(
+    left_keys: &[IndexKey], right_keys: &[IndexKey],
+    left_bound: Option<usize>, right_bound: Option<usize>,
+    keep_unmatched: bool,
+) -> Vec<(usize, Option<usize>)> {
+    let mut right_index = HashMap::<&IndexKey, Vec<usize>>::new();
+    right_keys.iter().enumerate().for_each(|(right_row, key)| {
+        let matches = right_index.entry(key).or_default();
+        if right_bound.map(|bound| matches.len() < bound).unwrap_or(true) {
+            matches.push(right_row)
+        }
+    });
+
+    let mut left_counts = HashMap::<&IndexKey, usize>::new();
+    let mut rows = Vec::new();
+    left_keys.iter().enumerate().for_each(|(left_row, key)| {
+        let count = left_counts.entry(key).or_insert(0);
+        *count += 1;
+        // rows beyond the bound are not matched, but are still kept in a left join
+        let within_bound = left_bound.map(|bound| *count <= bound).unwrap_or(true);
+        match right_index.get(key).filter(|_| within_bound) {
+            Some(matches) => rows.extend(matches.iter().map(|right_row| (left_row, Some(*right_row)))),
+            None => if keep_unmatched { rows.push((left_row, None)) }
+        }
+    });
+    rows
+}
+
+/// Keys of each row of a key column.
+fn get_keys(column: &Value) -> Result<Vec<IndexKey>> {
+    Ok(match column.ref_array()? {
+        Array::Int(column) => column.iter().map(|v| IndexKey::from(*v)).collect(),
+        Array::Str(column) => column.iter().map(|v| IndexKey::from(v.clone())).collect(),
+        Array::Bool(column) => column.iter().map(|v| IndexKey::from(*v)).collect(),
+        Array::Float(_) => return Err("keys may not be floats, because they are not comparable".into())
+    })
+}
+
+/// Select the given rows of a column, where None is a null row.
+fn select_rows(column: Value, rows: &[Option<usize>]) -> Result<Value> {
+    Ok(match column.array()? {
+        Array::Float(column) => select_rows_of(&column, rows, Some(Float::NAN))?.into(),
+        Array::Str(column) => select_rows_of(&column, rows, Some("".to_string()))?.into(),
+        Array::Int(column) => select_rows_of(&column, rows, None)?.into(),
+        Array::Bool(column) => select_rows_of(&column, rows, None)?.into(),
+    })
+}
+
+fn select_rows_of<T: Clone>(column: &ArrayD<T>, rows: &[Option<usize>], null: Option<T>) -> Result<ArrayD<T>> {
+    let mut shape = column.shape().to_vec();
+    if shape.is_empty() {
+        return Err("columns must have at least one dimension".into())
+    }
+    shape[0] = rows.len();
+    let row_size = shape[1..].iter().product::<usize>();
+
+    let mut values = Vec::with_capacity(rows.len() * row_size);
+    for row in rows {
+        match row {
+            Some(row) => values.extend(column.index_axis(Axis(0), *row).iter().cloned()),
+            None => {
+                let null = null.clone()
+                    .ok_or_else(|| Error::from("only float and string columns may contain nulls"))?;
+                values.extend(std::iter::repeat(null).take(row_size))
+            }
+        }
+    }
+    Ok(ArrayD::from_shape_vec(shape, values)?)
+}
+
+#[cfg(test)]
+mod test_join {
+    use super::*;
+
+    #[test]
+    fn test_join_rows() {
+        let keys = |keys: &[i64]| keys.iter().map(|v| IndexKey::from(*v)).collect::<Vec<IndexKey>>();
+        let left = keys(&[1, 2, 1, 3]);
+        let right = keys(&[1, 1, 2]);
+
+        assert_eq!(join_rows(&left, &right, None, None, false),
+                   vec![(0, Some(0)), (0, Some(1)), (1, Some(2)), (2, Some(0)), (2, Some(1))]);
+        // each key contributes at most one row from each table
+        assert_eq!(join_rows(&left, &right, Some(1), Some(1), false),
+                   vec![(0, Some(0)), (1, Some(2))]);
+        // unmatched rows are kept in a left join
+        assert_eq!(join_rows(&left, &right, None, Some(1), true),
+                   vec![(0, Some(0)), (1, Some(2)), (2, Some(0)), (3, None)]);
+        // left rows beyond the bound are kept unmatched in a left join, and dropped in an inner join
+        assert_eq!(join_rows(&left, &right, Some(1), None, true),
+                   vec![(0, Some(0)), (0, Some(1)), (1, Some(2)), (2, None), (3, None)]);
+        assert_eq!(join_rows(&left, &right, Some(1), None, false),
+                   vec![(0, Some(0)), (0, Some(1)), (1, Some(2))]);
+    }
+}
@@ -1,12 +1,102 @@
 use core::iter::Iterator;
 
-/// Beginnings of DP Linear Regression
-/// Borrowing heavily from the crate 'linreg'
+use ndarray::{Array, Array1, Array2, Ix2};
 
+use smartnoise_validator::{Float, proto};
+use smartnoise_validator::base::ReleaseNode;
 use smartnoise_validator::errors::*;
-use smartnoise_validator::Float;
+use smartnoise_validator::utilities::privacy::{get_delta, get_epsilon};
+use smartnoise_validator::utilities::take_argument;
+
+use crate::components::Evaluable;
+use crate::NodeArguments;
+use crate::utilities::{linalg, to_nd};
+use crate::utilities::mechanisms::{gaussian_mechanism, laplace_mechanism};
+
+impl Evaluable for proto::LinregNoisyStats {
+    fn evaluate(&self, privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let data_x = to_nd(take_argument(&mut arguments, "data_x")?.array()?.float()?, 2)?
+            .into_dimensionality::<Ix2>()?;
+        let data_y = take_argument(&mut arguments, "data_y")?.array()?.float()?
+            .iter().cloned().collect::<Vec<Float>>();
+        let sensitivity = take_argument(&mut arguments, "sensitivity")?.array()?.first_float()?;
+
+        if self.privacy_usage.len() != 1 {
+            return Err(Error::from("LinregNoisyStats is not vectorized, only one privacy parameter may be passed"))
+        }
+        let epsilon = get_epsilon(&self.privacy_usage[0])?;
+        let delta = get_delta(&self.privacy_usage[0])?;
+
+        let enforce_constant_time = privacy_definition.as_ref()
+            .ok_or_else(|| Error::from("privacy_definition must be known"))?
+            .protect_elapsed_time;
+
+        let mechanism = self.mechanism.to_lowercase();
+        let sample_noise = || match mechanism.as_str() {
+            "laplace" => laplace_mechanism(epsilon, sensitivity, enforce_constant_time),
+            "gaussian" => gaussian_mechanism(epsilon, delta, sensitivity, false, enforce_constant_time),
+            "analyticgaussian" => gaussian_mechanism(epsilon, delta, sensitivity, true, enforce_constant_time),
+            _ => Err(format!("`{}` is not recognized as a valid mechanism. Must be one of [`Laplace`, `Gaussian`, `AnalyticGaussian`]", self.mechanism).into())
+        };
+
+        let coefficients = noisy_stats_ridge(
+            &data_x, &data_y, self.regularization, sample_noise)?;
+
+        let num_columns = coefficients.len();
+        Ok(ReleaseNode {
+            value: Array::from_shape_vec((1, num_columns), coefficients.to_vec())?.into_dyn().into(),
+            privacy_usages: Some(self.privacy_usage.clone()),
+            public: true,
+        })
+    }
+}
+
+/// Fit a ridge regression from noisy sufficient statistics.
+///
+/// The predictors are augmented with a constant column, for the intercept.
+/// Noise is added to each entry of the upper triangle of XᵀX and of Xᵀy,
+/// the noisy XᵀX is mirrored into a symmetric matrix,
+/// and the penalized normal equations are solved.
+/// The penalty is not applied to the intercept.
+///
+/// # Arguments
+/// * `data_x` - Predictors of shape `(n, d)`, within the bounds used to compute the sensitivity.
+/// * `data_y` - Targets of length `n`, within the bounds used to compute the sensitivity.
+/// * `regularization` - Coefficient on the ridge penalty.
+/// * `sample_noise` - Returns one noise sample, scaled to the sensitivity of the statistics.
+///
+/// # Return
+/// The `d` coefficients, followed by the intercept.
+pub fn noisy_stats_ridge(
+    data_x: &Array2<Float>, data_y: &[Float],
+    regularization: Float,
+    mut sample_noise: impl FnMut() -> Result<Float>,
+) -> Result<Array1<Float>> {
+    let (num_records, num_columns) = data_x.dim();
+    if num_records != data_y.len() {
+        return Err("predictors and targets must share same length".into())
+    }
+    let d = num_columns + 1;
+
+    // value of column j of the augmented predictors, in row i
+    let augmented = |i: usize, j: usize| if j == num_columns { 1. } else { data_x[[i, j]] };
+
+    let mut gram = Array2::<Float>::zeros((d, d));
+    let mut moment = Array1::<Float>::zeros(d);
+    for j in 0..d {
+        for k in j..d {
+            gram[[j, k]] = (0..num_records).map(|i| augmented(i, j) * augmented(i, k)).sum::<Float>()
+                + sample_noise()?;
+            gram[[k, j]] = gram[[j, k]];
+        }
+        moment[j] = (0..num_records).map(|i| augmented(i, j) * data_y[i]).sum::<Float>()
+            + sample_noise()?;
+    }
 
-use crate::utilities::mechanisms::laplace_mechanism;
+    (0..num_columns).for_each(|j| gram[[j, j]] += regularization);
+
+    linalg::solve(&gram, &moment)
+}
 
 /// Calculates "NoisyStat", which adds Laplace noise to the OLS sufficient statistics
 ///
@@ -91,7 +181,7 @@ mod tests {
 
         // This is, admittedly, not the greatest test, but it does ensure that noisy_stats
         // is returning values without panicking.
-        assert!(!result.is_err());
+        assert!(result.is_ok());
     }
 
     #[test]
@@ -134,4 +224,32 @@ mod tests {
         assert!((base_p25 - p_25).abs() < 1.0 / epsilon);
         assert!((base_p75 - p_75).abs() < 1.0 / epsilon);
     }
+
+    #[test]
+    fn noisy_stats_ridge_multivariate_test() {
+        // y = 2 x_0 - x_1 + 3
+        let data_x = Array2::from_shape_fn((100, 2), |(i, j)| ((i * (j + 3)) % 17) as Float);
+        let data_y = data_x.genrows().into_iter()
+            .map(|row| 2. * row[0] - row[1] + 3.)
+            .collect::<Vec<Float>>();
+
+        let coefficients = noisy_stats_ridge(&data_x, &data_y, 0., || Ok(0.)).unwrap();
+
+        assert_eq!(coefficients.len(), 3);
+        assert!((coefficients[0] - 2.).abs() < 1e-8);
+        assert!((coefficients[1] + 1.).abs() < 1e-8);
+        assert!((coefficients[2] - 3.).abs() < 1e-8);
+    }
+
+    #[test]
+    fn noisy_stats_ridge_laplace_completes_test() {
+        let data_x = Array2::from_shape_fn((1000, 1), |(i, _)| (i % 10) as Float / 10.);
+        let data_y = data_x.iter().map(|x| 0.5 * x).collect::<Vec<Float>>();
+
+        let coefficients = noisy_stats_ridge(
+            &data_x, &data_y, 1.,
+            || laplace_mechanism(1., 4., false)).unwrap();
+
+        assert!((coefficients[0] - 0.5).abs() < 1.);
+    }
 }
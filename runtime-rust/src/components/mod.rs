@@ -14,27 +14,52 @@ use smartnoise_validator::base::ReleaseNode;
 
 use smartnoise_validator::proto;
 
-//pub mod bin;
+pub mod bin;
 pub mod cast;
 pub mod clamp;
 pub mod count;
 pub mod covariance;
 pub mod column_bind;
+pub mod conditional;
+pub mod datetime;
 pub mod digitize;
+pub mod dp_chi_square_test;
+pub mod dp_confidence_interval;
+pub mod dp_correlation;
+pub mod dp_decision_tree;
+pub mod dp_empirical_cdf;
+pub mod dp_goodness_of_fit;
 pub mod dp_gumbel_median;
+pub mod dp_k_means;
+pub mod dp_kernel_density;
+pub mod dp_marginals;
+pub mod dp_mode;
+pub mod dp_naive_bayes;
+pub mod dp_logistic_regression;
+pub mod dp_pca;
+pub mod dp_quantile_regression;
+pub mod dp_random_forest;
+pub mod dp_synthetic_data;
+pub mod encoding;
 pub mod filter;
+pub mod group_by;
 pub mod histogram;
 pub mod impute;
 pub mod index;
-// pub mod linreg_noisy_stats;
+pub mod join;
+pub mod linreg_noisy_stats;
 pub mod materialize;
 pub mod mean;
 pub mod mechanisms;
+pub mod nulls;
 pub mod partition;
+pub mod predict;
+pub mod projection;
 pub mod quantile;
 pub mod raw_moment;
 pub mod reshape;
 pub mod resize;
+pub mod sort;
 pub mod sum;
 pub mod theil_sen;
 pub mod to_dataframe;
@@ -85,16 +110,22 @@ impl Evaluable for proto::component::Variant {
 
         evaluate!(
             // INSERT COMPONENT LIST
-            Cast, Clamp, ColumnBind, Count, Covariance, Digitize, Filter, Histogram, Impute, Index,
-            Materialize, Mean, Partition,
-            Quantile, RawMoment, Reshape, Resize, Sum, ToDataframe, Union, Variance,
+            Bin, CaseWhen, Cast, Clamp, Coalesce, ColumnBind, Count, Covariance, DatetimeExtract, DatetimeTrunc, Digitize,
+            DropNull, FillNull, Filter, GroupBy, Histogram, IfElse, Impute, Index, IsNull, Join, Materialize, Mean,
+            OneHot, ParseDatetime, Partition,
+            Quantile, RawMoment, Recode, Reshape, Resize, Sort, Sum, ToDataframe, Union, Variance,
 
             ExponentialMechanism, GaussianMechanism,
             LaplaceMechanism, SnappingMechanism,
             SimpleGeometricMechanism,
 
-            Abs, Add, LogicalAnd, Divide, Equal, GreaterThan, LessThan, Log, Modulo, Multiply,
-            Negate, Negative, LogicalOr, Power, RowMax, RowMin, Subtract, TheilSen, DpGumbelMedian
+            Abs, Add, LogicalAnd, Ceil, Concat, Cos, Divide, Equal, Exp, Floor, GreaterThan, LessThan, Log, Lower,
+            Modulo, Multiply, Negate, Negative, LogicalOr, Power, RegexExtract, RegexMatch, Round, RowMax, RowMin,
+            Sign, Sin, Sqrt, Subtract, Substring, Tan, Trim, TheilSen, DpGumbelMedian,
+            DpLogisticRegression, LinregNoisyStats, DpPca, Projection, DpKMeans, DpMarginals,
+            DpSyntheticData, DpChiSquareTest, DpGoodnessOfFit, DpConfidenceInterval, DpCorrelation,
+            DpNaiveBayes, Predict, DpDecisionTree, DpRandomForest, DpQuantileRegression, DpEmpiricalCdf,
+            DpKernelDensity, DpMode
         );
 
         Err(format!("Component type not implemented: {:?}", self).into())
@@ -0,0 +1,163 @@
+use ndarray::{ArrayD, Axis, Zip};
+
+use smartnoise_validator::{Float, Integer, proto};
+use smartnoise_validator::base::{Array, ReleaseNode};
+use smartnoise_validator::errors::*;
+use smartnoise_validator::utilities::{standardize_null_target_argument, take_argument};
+
+use crate::components::Evaluable;
+use crate::components::filter::filter;
+use crate::NodeArguments;
+use crate::utilities::{broadcast_map, get_num_columns};
+
+impl Evaluable for proto::IsNull {
+    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        Ok(ReleaseNode::new(match take_argument(&mut arguments, "data")?.array()? {
+            Array::Float(data) => data.mapv(|v| v.is_null()),
+            Array::Int(data) => data.mapv(|v| v.is_null()),
+            Array::Str(data) => data.map(Nullable::is_null),
+            Array::Bool(data) => data.mapv(|v| v.is_null()),
+        }.into()))
+    }
+}
+
+impl Evaluable for proto::Coalesce {
+    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        Ok(ReleaseNode::new(match (
+            take_argument(&mut arguments, "left")?.array()?,
+            take_argument(&mut arguments, "right")?.array()?) {
+            (Array::Float(left), Array::Float(right)) => coalesce(left, right)?.into(),
+            (Array::Int(left), Array::Int(right)) => coalesce(left, right)?.into(),
+            (Array::Str(left), Array::Str(right)) => coalesce(left, right)?.into(),
+            (Array::Bool(left), Array::Bool(right)) => coalesce(left, right)?.into(),
+            _ => return Err("Coalesce: left and right must share the same data type".into())
+        }))
+    }
+}
+
+impl Evaluable for proto::FillNull {
+    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        Ok(ReleaseNode::new(match (
+            take_argument(&mut arguments, "data")?.array()?,
+            take_argument(&mut arguments, "fill_value")?.array()?) {
+            (Array::Float(data), Array::Float(value)) => fill_null(data, value)?.into(),
+            (Array::Int(data), Array::Int(value)) => fill_null(data, value)?.into(),
+            (Array::Str(data), Array::Str(value)) => fill_null(data, value)?.into(),
+            (Array::Bool(data), Array::Bool(value)) => fill_null(data, value)?.into(),
+            _ => return Err("FillNull: data and fill_value must share the same data type".into())
+        }))
+    }
+}
+
+impl Evaluable for proto::DropNull {
+    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        Ok(ReleaseNode::new(match take_argument(&mut arguments, "data")?.array()? {
+            Array::Float(data) => drop_null(data)?.into(),
+            Array::Int(data) => drop_null(data)?.into(),
+            Array::Str(data) => drop_null(data)?.into(),
+            Array::Bool(data) => drop_null(data)?.into(),
+        }))
+    }
+}
+
+/// Types that may represent a null value.
+pub trait Nullable {
+    fn is_null(&self) -> bool;
+}
+
+impl Nullable for Float {
+    fn is_null(&self) -> bool { self.is_nan() }
+}
+
+impl Nullable for String {
+    fn is_null(&self) -> bool { self.is_empty() }
+}
+
+impl Nullable for Integer {
+    fn is_null(&self) -> bool { false }
+}
+
+impl Nullable for bool {
+    fn is_null(&self) -> bool { false }
+}
+
+/// Take values from the right argument wherever the left argument is null.
+///
+/// # Arguments
+/// * `left` - Data whose non-null values are preferred.
+/// * `right` - Data to take values from wherever `left` is null.
+///
+/// # Return
+/// Data that is only null where both arguments are null.
+///
+/// # Example
+/// ```
+/// use ndarray::arr1;
+/// use smartnoise_runtime::components::nulls::coalesce;
+///
+/// let left = arr1(&[1., f64::NAN, f64::NAN]).into_dyn();
+/// let right = arr1(&[4., 5., f64::NAN]).into_dyn();
+/// let coalesced = coalesce(left, right).unwrap();
+/// assert_eq!(coalesced.iter().take(2).cloned().collect::<Vec<_>>(), vec![1., 5.]);
+/// assert!(coalesced[2].is_nan());
+/// ```
+pub fn coalesce<T: Nullable + Clone + Default>(left: ArrayD<T>, right: ArrayD<T>) -> Result<ArrayD<T>> {
+    broadcast_map(left, right, &|l: &T, r: &T| if l.is_null() { r.clone() } else { l.clone() })
+}
+
+/// Replace nulls in each column with the corresponding value.
+///
+/// # Arguments
+/// * `data` - Data in which to replace nulls.
+/// * `value` - Value for each column, with which to replace nulls.
+///
+/// # Return
+/// Data without nulls.
+///
+/// # Example
+/// ```
+/// use ndarray::{arr1, arr2};
+/// use smartnoise_runtime::components::nulls::fill_null;
+///
+/// let data = arr2(&[["a".to_string(), "".to_string()], ["".to_string(), "d".to_string()]]).into_dyn();
+/// let value = arr1(&["x".to_string(), "y".to_string()]).into_dyn();
+/// let filled = fill_null(data, value).unwrap();
+/// assert_eq!(filled, arr2(&[["a".to_string(), "y".to_string()], ["x".to_string(), "d".to_string()]]).into_dyn());
+/// ```
+pub fn fill_null<T: Nullable + Clone>(mut data: ArrayD<T>, value: ArrayD<T>) -> Result<ArrayD<T>> {
+    let num_columns = get_num_columns(&data)?;
+
+    data.gencolumns_mut().into_iter()
+        .zip(standardize_null_target_argument(value, num_columns)?)
+        .for_each(|(column, value)| Zip::from(column)
+            .apply(|v| if v.is_null() { *v = value.clone() }));
+
+    Ok(data)
+}
+
+/// Drop the rows that contain a null in any column.
+///
+/// # Arguments
+/// * `data` - Data from which to drop rows.
+///
+/// # Return
+/// Data with only the rows that contain no nulls.
+///
+/// # Example
+/// ```
+/// use ndarray::arr2;
+/// use smartnoise_runtime::components::nulls::drop_null;
+///
+/// let data = arr2(&[ [1., 2.], [f64::NAN, 4.], [5., 6.] ]).into_dyn();
+/// let dropped = drop_null(data).unwrap();
+/// assert_eq!(dropped, arr2(&[ [1., 2.], [5., 6.] ]).into_dyn());
+/// ```
+pub fn drop_null<T: Nullable + Clone + Default>(data: ArrayD<T>) -> Result<ArrayD<T>> {
+    if data.ndim() == 0 {
+        return Err("data: must have at least one dimension".into())
+    }
+    let mask = data.axis_iter(Axis(0))
+        .map(|row| !row.iter().any(Nullable::is_null))
+        .collect::<Vec<bool>>();
+    filter(data, ArrayD::from_shape_vec(vec![mask.len()], mask)?)
+}
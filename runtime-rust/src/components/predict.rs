@@ -0,0 +1,275 @@
+use indexmap::map::IndexMap;
+use ndarray::{arr1, ArrayD};
+
+use smartnoise_validator::{Float, proto};
+use smartnoise_validator::base::{Array, IndexKey, Jagged, ReleaseNode, Value};
+use smartnoise_validator::errors::*;
+use smartnoise_validator::utilities::take_argument;
+
+use crate::components::dp_decision_tree::{leaf_index, numeric_columns};
+use crate::components::dp_naive_bayes::encode;
+use crate::components::Evaluable;
+use crate::NodeArguments;
+
+impl Evaluable for proto::Predict {
+    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let mut model = take_argument(&mut arguments, "model")?.dataframe()?;
+        let classes = model.remove::<IndexKey>(&"classes".into())
+            .ok_or_else(|| Error::from("model: classes is missing"))?.array()?;
+
+        let scores = if model.contains_key::<IndexKey>(&"thresholds".into()) {
+            tree_scores(model, arguments)?
+        } else {
+            naive_bayes_scores(model, arguments)?
+        };
+
+        // the most likely class of each record
+        let indices = scores.iter()
+            .map(|record_scores| record_scores.iter()
+                .enumerate()
+                .fold((0, Float::NEG_INFINITY), |(argmax, max), (index, score)|
+                    if *score > max { (index, *score) } else { (argmax, max) }).0)
+            .collect::<Vec<usize>>();
+
+        fn select<T: Clone>(classes: ArrayD<T>, indices: &[usize]) -> ArrayD<T> {
+            let classes = classes.iter().cloned().collect::<Vec<T>>();
+            arr1(&indices.iter().map(|index| classes[*index].clone()).collect::<Vec<T>>()).into_dyn()
+        }
+        Ok(ReleaseNode::new(match classes {
+            Array::Bool(classes) => select(classes, &indices).into(),
+            Array::Int(classes) => select(classes, &indices).into(),
+            Array::Float(classes) => select(classes, &indices).into(),
+            Array::Str(classes) => select(classes, &indices).into(),
+        }))
+    }
+}
+
+/// Log posterior of each class for each record, up to a constant, under a naive Bayes model.
+fn naive_bayes_scores(mut model: IndexMap<IndexKey, Value>, mut arguments: NodeArguments) -> Result<Vec<Vec<Float>>> {
+    let mut take_model = |name: &str| model.remove::<IndexKey>(&name.into())
+        .ok_or_else(|| Error::from(format!("model: {} is missing", name)));
+
+    let log_prior = take_model("log_prior")?.array()?.float()?;
+    let num_classes = log_prior.len();
+
+    let mut scores: Option<Vec<Vec<Float>>> = None;
+    let mut add_scores = |feature_scores: Vec<Vec<Float>>| -> Result<()> {
+        if let Some(scores) = &scores {
+            if scores.len() != feature_scores.len() {
+                return Err("data_categorical and data_continuous must be same length".into())
+            }
+        }
+        scores = Some(match scores.take() {
+            Some(scores) => scores.into_iter().zip(feature_scores)
+                .map(|(l, r)| l.iter().zip(r.iter()).map(|(l, r)| l + r).collect())
+                .collect(),
+            None => feature_scores
+        });
+        Ok(())
+    };
+
+    if let Some(data) = arguments.remove::<IndexKey>(&"data_categorical".into()) {
+        let categories = take_model("categories")?.array()?;
+        let num_categories = take_model("num_categories")?.array()?.int()?.iter()
+            .map(|v| *v as usize).collect::<Vec<usize>>();
+        let log_likelihoods = take_model("log_likelihoods")?.array()?.float()?;
+
+        let codes = encode(data.array()?, split_categories(categories, &num_categories)?)?.into_iter()
+            .map(|(codes, _)| codes)
+            .collect::<Vec<Vec<Option<usize>>>>();
+        add_scores(categorical_log_likelihoods(&codes, &num_categories, &log_likelihoods, num_classes)?)?;
+    }
+
+    if let Some(data) = arguments.remove::<IndexKey>(&"data_continuous".into()) {
+        let columns = data.array()?.float()?.gencolumns().into_iter()
+            .map(|column| column.to_vec())
+            .collect::<Vec<Vec<Float>>>();
+        let means = take_model("means")?.array()?.float()?;
+        let variances = take_model("variances")?.array()?.float()?;
+        add_scores(continuous_log_likelihoods(&columns, &means, &variances, num_classes)?)?;
+    }
+
+    Ok(scores
+        .ok_or_else(|| Error::from("at least one of data_categorical and data_continuous must be supplied"))?
+        .into_iter()
+        .map(|record_scores| record_scores.iter().zip(log_prior.iter())
+            .map(|(score, log_prior)| score + log_prior)
+            .collect())
+        .collect())
+}
+
+/// Sum of the class proportions of the leaves that each record reaches in each tree.
+fn tree_scores(mut model: IndexMap<IndexKey, Value>, mut arguments: NodeArguments) -> Result<Vec<Vec<Float>>> {
+    let mut take_model = |name: &str| model.remove::<IndexKey>(&name.into())
+        .ok_or_else(|| Error::from(format!("model: {} is missing", name)));
+
+    let features = take_model("features")?.array()?.int()?;
+    let thresholds = take_model("thresholds")?.array()?.float()?;
+    let leaf_counts = take_model("leaf_counts")?.array()?.float()?;
+    let columns = numeric_columns(take_argument(&mut arguments, "data")?.array()?)?;
+
+    if features.ndim() != 2 || features.shape() != thresholds.shape() || leaf_counts.ndim() != 2 {
+        return Err("model: features, thresholds and leaf_counts must be matrices".into())
+    }
+    let num_trees = features.shape()[0];
+    let num_splits = features.shape()[1];
+    let num_classes = leaf_counts.shape()[1];
+    if leaf_counts.shape()[0] != num_trees * (num_splits + 1) {
+        return Err("model: leaf_counts must have one row for each leaf of each tree".into())
+    }
+    if features.iter().any(|feature| *feature < 0 || *feature as usize >= columns.len()) {
+        return Err("data must contain each column that the model splits on".into())
+    }
+
+    let features = features.genrows().into_iter()
+        .map(|row| row.iter().map(|v| *v as usize).collect())
+        .collect::<Vec<Vec<usize>>>();
+    let thresholds = thresholds.genrows().into_iter()
+        .map(|row| row.to_vec())
+        .collect::<Vec<Vec<Float>>>();
+    let leaf_proportions = leaf_counts.genrows().into_iter()
+        .map(|counts| {
+            let total = counts.iter().sum::<Float>();
+            counts.iter().map(|count| if total > 0. { count / total } else { 0. }).collect()
+        })
+        .collect::<Vec<Vec<Float>>>();
+
+    let num_records = columns.first().map(|column| column.len()).unwrap_or(0);
+    Ok((0..num_records)
+        .map(|record| (0..num_trees)
+            .map(|tree| &leaf_proportions[tree * (num_splits + 1)
+                + leaf_index(|column| columns[column][record], &features[tree], &thresholds[tree])])
+            .fold(vec![0.; num_classes], |scores, proportions| scores.iter().zip(proportions.iter())
+                .map(|(l, r)| l + r).collect()))
+        .collect())
+}
+
+/// Split the concatenated categories of the model into the categories of each column.
+fn split_categories(categories: Array, num_categories: &[usize]) -> Result<Jagged> {
+    if categories.num_records()? != num_categories.iter().sum::<usize>() {
+        return Err("model: the number of categories does not match the categories".into())
+    }
+    fn split<T: Clone>(categories: ArrayD<T>, num_categories: &[usize]) -> Vec<Vec<T>> {
+        let categories = categories.iter().cloned().collect::<Vec<T>>();
+        num_categories.iter()
+            .scan(0, |offset, num_categories| {
+                *offset += num_categories;
+                Some(categories[*offset - num_categories..*offset].to_vec())
+            })
+            .collect()
+    }
+    Ok(match categories {
+        Array::Bool(categories) => Jagged::Bool(split(categories, num_categories)),
+        Array::Int(categories) => Jagged::Int(split(categories, num_categories)),
+        Array::Float(categories) => Jagged::Float(split(categories, num_categories)),
+        Array::Str(categories) => Jagged::Str(split(categories, num_categories)),
+    })
+}
+
+/// Sum of the log likelihoods of the categorical features of each record, for each class.
+///
+/// Values that are not among the categories of the model are ignored.
+///
+/// # Arguments
+/// * `codes` - Category index of each record, for each column.
+/// * `num_categories` - Number of categories in each column.
+/// * `log_likelihoods` - Log probability of each category given each class, of shape `(num_classes, sum(num_categories))`.
+/// * `num_classes` - Number of classes.
+///
+/// # Return
+/// The log likelihood of each class, for each record.
+pub fn categorical_log_likelihoods(
+    codes: &[Vec<Option<usize>>], num_categories: &[usize],
+    log_likelihoods: &ArrayD<Float>, num_classes: usize,
+) -> Result<Vec<Vec<Float>>> {
+    if log_likelihoods.shape() != [num_classes, num_categories.iter().sum()] {
+        return Err("model: log_likelihoods must have one row per class and one column per category".into())
+    }
+    let num_records = codes.first().map(|column| column.len()).unwrap_or(0);
+    let offsets = num_categories.iter()
+        .scan(0, |offset, num_categories| {
+            *offset += num_categories;
+            Some(*offset - num_categories)
+        })
+        .collect::<Vec<usize>>();
+
+    Ok((0..num_records)
+        .map(|record| (0..num_classes)
+            .map(|class| codes.iter().zip(offsets.iter())
+                .filter_map(|(column, offset)| column[record]
+                    .map(|code| log_likelihoods[[class, offset + code]]))
+                .sum())
+            .collect())
+        .collect())
+}
+
+/// Sum of the normal log densities of the continuous features of each record, for each class.
+///
+/// # Arguments
+/// * `columns` - Values of each column.
+/// * `means` - Mean of each column within each class, of shape `(num_classes, num_columns)`.
+/// * `variances` - Variance of each column within each class, of shape `(num_classes, num_columns)`.
+/// * `num_classes` - Number of classes.
+///
+/// # Return
+/// The log likelihood of each class, for each record.
+pub fn continuous_log_likelihoods(
+    columns: &[Vec<Float>], means: &ArrayD<Float>, variances: &ArrayD<Float>, num_classes: usize,
+) -> Result<Vec<Vec<Float>>> {
+    if means.shape() != [num_classes, columns.len()] || variances.shape() != means.shape() {
+        return Err("model: means and variances must have one row per class and one column per feature".into())
+    }
+    let num_records = columns.first().map(|column| column.len()).unwrap_or(0);
+
+    Ok((0..num_records)
+        .map(|record| (0..num_classes)
+            .map(|class| columns.iter().enumerate()
+                .map(|(index, column)| {
+                    let variance = variances[[class, index]];
+                    -0.5 * (2. * std::f64::consts::PI * variance).ln()
+                        - (column[record] - means[[class, index]]).powi(2) / (2. * variance)
+                })
+                .sum())
+            .collect())
+        .collect())
+}
+
+#[cfg(test)]
+mod test_predict {
+    use ndarray::arr2;
+
+    use super::*;
+
+    #[test]
+    fn test_categorical_log_likelihoods() {
+        // two classes, one feature with two categories
+        let log_likelihoods = arr2(&[[0.9_f64.ln(), 0.1_f64.ln()], [0.2_f64.ln(), 0.8_f64.ln()]]).into_dyn();
+        let scores = categorical_log_likelihoods(
+            &[vec![Some(0), Some(1), None]], &[2], &log_likelihoods, 2).unwrap();
+
+        assert!(scores[0][0] > scores[0][1]);
+        assert!(scores[1][0] < scores[1][1]);
+        // unknown categories do not contribute
+        assert_eq!(scores[2], vec![0., 0.]);
+    }
+
+    #[test]
+    fn test_continuous_log_likelihoods() {
+        let means = arr2(&[[0.], [10.]]).into_dyn();
+        let variances = arr2(&[[1.], [1.]]).into_dyn();
+        let scores = continuous_log_likelihoods(&[vec![1., 9.]], &means, &variances, 2).unwrap();
+
+        assert!(scores[0][0] > scores[0][1]);
+        assert!(scores[1][0] < scores[1][1]);
+    }
+
+    #[test]
+    fn test_split_categories() {
+        let categories = Array::Str(ndarray::arr1(&["a", "b", "x", "y", "z"])
+            .mapv(|v| v.to_string()).into_dyn());
+        match split_categories(categories, &[2, 3]).unwrap() {
+            Jagged::Str(categories) => assert_eq!(categories[1], vec!["x", "y", "z"]),
+            _ => panic!("categories must be strings")
+        }
+    }
+}
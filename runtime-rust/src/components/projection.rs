@@ -0,0 +1,48 @@
+use ndarray::{Array2, ArrayD, Ix2, s};
+
+use smartnoise_validator::{Float, proto};
+use smartnoise_validator::base::ReleaseNode;
+use smartnoise_validator::errors::*;
+use smartnoise_validator::utilities::take_argument;
+
+use crate::components::Evaluable;
+use crate::NodeArguments;
+use crate::utilities::to_nd;
+
+impl Evaluable for proto::Projection {
+    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let data = to_nd(take_argument(&mut arguments, "data")?.array()?.float()?, 2)?;
+        let components = take_argument(&mut arguments, "components")?.array()?.float()?;
+
+        Ok(ReleaseNode::new(projection(&data, &components)?.into_dyn().into()))
+    }
+}
+
+/// Project each record onto the principal directions.
+///
+/// # Arguments
+/// * `data` - Data of shape `(n, d)`.
+/// * `components` - Principal components of shape `(k, d + 1)`, as released by DPPCA. The first column of variances is ignored.
+///
+/// # Return
+/// Coordinates of shape `(n, k)`.
+///
+/// # Example
+/// ```
+/// use ndarray::arr2;
+/// use smartnoise_runtime::components::projection::projection;
+/// let data = arr2(&[[1., 2.], [3., 4.]]).into_dyn();
+/// let components = arr2(&[[5., 0., 1.]]).into_dyn();
+/// let projected = projection(&data, &components).unwrap();
+/// assert_eq!(projected, arr2(&[[2.], [4.]]));
+/// ```
+pub fn projection(data: &ArrayD<Float>, components: &ArrayD<Float>) -> Result<Array2<Float>> {
+    let data = data.clone().into_dimensionality::<Ix2>()?;
+    let components = components.clone().into_dimensionality::<Ix2>()?;
+
+    if components.ncols() != data.ncols() + 1 {
+        return Err("components: must have one more column than data".into())
+    }
+
+    Ok(data.dot(&components.slice(s![.., 1..]).t()))
+}
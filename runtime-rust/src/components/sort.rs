@@ -0,0 +1,110 @@
+use std::cmp::Ordering;
+
+use ndarray::{ArrayD, Axis};
+
+use smartnoise_validator::proto;
+use smartnoise_validator::base::{Array, ReleaseNode, Value};
+use smartnoise_validator::components::sort::get_key_names;
+use smartnoise_validator::errors::*;
+use smartnoise_validator::utilities::{standardize_null_target_argument, take_argument};
+use smartnoise_validator::utilities::array::slow_select;
+
+use crate::components::Evaluable;
+use crate::NodeArguments;
+use crate::utilities::to_nd;
+
+impl Evaluable for proto::Sort {
+    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let data = take_argument(&mut arguments, "data")?.dataframe()?;
+        let keys = get_key_names(take_argument(&mut arguments, "keys")?.array()?)?;
+        let descending = standardize_null_target_argument(
+            take_argument(&mut arguments, "descending")?.array()?.bool()?, keys.len() as i64)?;
+
+        let key_columns = keys.iter()
+            .map(|key| data.get(key)
+                .ok_or_else(|| Error::from(format!("{}: key is not a column of data", key.to_string())))?
+                .ref_array())
+            .collect::<Result<Vec<&Array>>>()?;
+        let order = sort_rows(&key_columns, &descending)?;
+
+        Ok(ReleaseNode::new(Value::Dataframe(data.into_iter()
+            .map(|(name, column)| Ok((name, match column.array()? {
+                Array::Float(column) => slow_select(&column, Axis(0), &order).into(),
+                Array::Int(column) => slow_select(&column, Axis(0), &order).into(),
+                Array::Str(column) => slow_select(&column, Axis(0), &order).into(),
+                Array::Bool(column) => slow_select(&column, Axis(0), &order).into(),
+            })))
+            .collect::<Result<_>>()?)))
+    }
+}
+
+/// Order of the rows, sorted by one or more key columns.
+///
+/// The sort is stable, so rows with equal keys keep their relative order.
+/// Float nulls sort after all other values in ascending order.
+///
+/// # Arguments
+/// * `keys` - Key columns, in order of precedence.
+/// * `descending` - Whether to sort each key in descending order.
+///
+/// # Return
+/// Index of the row in each position of the sorted data.
+///
+/// # Example
+/// ```
+/// use ndarray::arr1;
+/// use smartnoise_validator::base::Array;
+/// use smartnoise_runtime::components::sort::sort_rows;
+///
+/// let users = Array::Str(arr1(&["b", "a", "b", "a"]).mapv(String::from).into_dyn());
+/// let times = Array::Int(arr1(&[1, 5, 0, 3]).into_dyn());
+/// let order = sort_rows(&[&users, &times], &[false, false]).unwrap();
+/// assert_eq!(order, vec![3, 1, 2, 0]);
+/// ```
+pub fn sort_rows(keys: &[&Array], descending: &[bool]) -> Result<Vec<usize>> {
+    if keys.len() != descending.len() {
+        return Err("descending must contain one value for each key".into())
+    }
+
+    let ranks = keys.iter()
+        .map(|key| Ok(match key {
+            Array::Float(key) => rank_rows(key, |l, r| l.partial_cmp(r)
+                .unwrap_or_else(|| l.is_nan().cmp(&r.is_nan())))?,
+            Array::Int(key) => rank_rows(key, Ord::cmp)?,
+            Array::Str(key) => rank_rows(key, Ord::cmp)?,
+            Array::Bool(key) => rank_rows(key, Ord::cmp)?,
+        }))
+        .collect::<Result<Vec<_>>>()?;
+
+    let num_records = ranks.iter().map(Vec::len).max().unwrap_or(0);
+    if ranks.iter().any(|ranks| ranks.len() != num_records) {
+        return Err("key columns must share the same number of records".into())
+    }
+
+    let mut order = (0..num_records).collect::<Vec<usize>>();
+    order.sort_by(|l, r| ranks.iter().zip(descending.iter())
+        .map(|(ranks, descending)| {
+            let ordering = ranks[*l].cmp(&ranks[*r]);
+            if *descending { ordering.reverse() } else { ordering }
+        })
+        .find(|ordering| *ordering != Ordering::Equal)
+        .unwrap_or(Ordering::Equal));
+    Ok(order)
+}
+
+/// Rank of each row of a key column, where equal values share the same rank.
+fn rank_rows<T: Clone>(key: &ArrayD<T>, compare: impl Fn(&T, &T) -> Ordering) -> Result<Vec<usize>> {
+    let key = to_nd(key.clone(), 1)?;
+    let mut order = (0..key.len()).collect::<Vec<usize>>();
+    order.sort_by(|l, r| compare(&key[*l], &key[*r]));
+
+    let mut ranks = vec![0; key.len()];
+    let mut rank = 0;
+    for (position, row) in order.iter().enumerate() {
+        if position > 0 && compare(&key[order[position - 1]], &key[*row]) != Ordering::Equal {
+            rank += 1;
+        }
+        ranks[*row] = rank;
+    }
+    Ok(ranks)
+}
@@ -3,9 +3,10 @@ use smartnoise_validator::errors::*;
 use crate::components::Evaluable;
 use crate::NodeArguments;
 use smartnoise_validator::base::{Value, Array, ReleaseNode};
-use smartnoise_validator::utilities::take_argument;
+use smartnoise_validator::components::transforms::{compile_pattern, regex_extract, sign, substring};
+use smartnoise_validator::utilities::{standardize_null_target_argument, take_argument};
 use smartnoise_validator::{proto, Integer, Float};
-use crate::utilities::broadcast_map;
+use crate::utilities::{broadcast_map, get_num_columns};
 
 
 impl Evaluable for proto::Abs {
@@ -53,6 +54,33 @@ impl Evaluable for proto::And {
     }
 }
 
+impl Evaluable for proto::Ceil {
+    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let data = take_argument(&mut arguments, "data")?.array()?.float()?;
+        Ok(ReleaseNode::new(data.mapv(|v| v.ceil()).into()))
+    }
+}
+
+impl Evaluable for proto::Concat {
+    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        match (take_argument(&mut arguments, "left")?, take_argument(&mut arguments, "right")?) {
+            (Value::Array(left), Value::Array(right)) => match (left, right) {
+                (Array::Str(x), Array::Str(y)) =>
+                    Ok(broadcast_map(x, y, &|l, r| format!("{}{}{}", l, self.separator, r))?.into()),
+                _ => Err("Concat: Both arguments must be strings.".into())
+            },
+            _ => Err("Concat: Both arguments must be arrays.".into())
+        }.map(ReleaseNode::new)
+    }
+}
+
+impl Evaluable for proto::Cos {
+    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let data = take_argument(&mut arguments, "data")?.array()?.float()?;
+        Ok(ReleaseNode::new(data.mapv(|v| v.cos()).into()))
+    }
+}
+
 impl Evaluable for proto::Divide {
     fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
         match (take_argument(&mut arguments, "left")?, take_argument(&mut arguments, "right")?) {
@@ -88,6 +116,20 @@ impl Evaluable for proto::Equal {
     }
 }
 
+impl Evaluable for proto::Exp {
+    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let data = take_argument(&mut arguments, "data")?.array()?.float()?;
+        Ok(ReleaseNode::new(data.mapv(|v| v.exp()).into()))
+    }
+}
+
+impl Evaluable for proto::Floor {
+    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let data = take_argument(&mut arguments, "data")?.array()?.float()?;
+        Ok(ReleaseNode::new(data.mapv(|v| v.floor()).into()))
+    }
+}
+
 impl Evaluable for proto::GreaterThan {
     fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
         match (take_argument(&mut arguments, "left")?, take_argument(&mut arguments, "right")?) {
@@ -127,6 +169,13 @@ impl Evaluable for proto::Log {
 }
 
 
+impl Evaluable for proto::Lower {
+    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let data = take_argument(&mut arguments, "data")?.array()?.string()?;
+        Ok(ReleaseNode::new(data.mapv(|v| v.to_lowercase()).into()))
+    }
+}
+
 impl Evaluable for proto::Modulo {
     fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
         match (take_argument(&mut arguments, "left")?, take_argument(&mut arguments, "right")?) {
@@ -212,6 +261,38 @@ impl Evaluable for proto::Power {
     }
 }
 
+impl Evaluable for proto::RegexExtract {
+    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let pattern = compile_pattern(&self.pattern)?;
+        let mut data = take_argument(&mut arguments, "data")?.array()?.string()?;
+        let null = take_argument(&mut arguments, "null_value")?.array()?.string()?;
+
+        let num_columns = get_num_columns(&data)?;
+        data.gencolumns_mut().into_iter()
+            .zip(standardize_null_target_argument(null, num_columns)?)
+            .for_each(|(mut column, null)| column.iter_mut()
+                .for_each(|v| *v = regex_extract(&pattern, v, self.group as usize)
+                    .unwrap_or_else(|| null.clone())));
+
+        Ok(ReleaseNode::new(data.into()))
+    }
+}
+
+impl Evaluable for proto::RegexMatch {
+    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let pattern = compile_pattern(&self.pattern)?;
+        let data = take_argument(&mut arguments, "data")?.array()?.string()?;
+        Ok(ReleaseNode::new(data.mapv(|v| pattern.is_match(&v)).into()))
+    }
+}
+
+impl Evaluable for proto::Round {
+    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let data = take_argument(&mut arguments, "data")?.array()?.float()?;
+        Ok(ReleaseNode::new(data.mapv(|v| v.round()).into()))
+    }
+}
+
 impl Evaluable for proto::RowMax {
     fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
         match (take_argument(&mut arguments, "left")?, take_argument(&mut arguments, "right")?) {
@@ -242,6 +323,37 @@ impl Evaluable for proto::RowMin {
     }
 }
 
+impl Evaluable for proto::Sign {
+    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        match take_argument(&mut arguments, "data")?.array()? {
+            Array::Float(data) => Ok(data.mapv(sign).into()),
+            Array::Int(data) => Ok(data.mapv(|v| v.signum()).into()),
+            _ => Err("Sign: Argument must be numeric.".into())
+        }.map(ReleaseNode::new)
+    }
+}
+
+impl Evaluable for proto::Sin {
+    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let data = take_argument(&mut arguments, "data")?.array()?.float()?;
+        Ok(ReleaseNode::new(data.mapv(|v| v.sin()).into()))
+    }
+}
+
+impl Evaluable for proto::Sqrt {
+    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let data = take_argument(&mut arguments, "data")?.array()?.float()?;
+        Ok(ReleaseNode::new(data.mapv(|v| v.sqrt()).into()))
+    }
+}
+
+impl Evaluable for proto::Substring {
+    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let data = take_argument(&mut arguments, "data")?.array()?.string()?;
+        Ok(ReleaseNode::new(data.mapv(|v| substring(&v, self.start as usize, self.length as usize)).into()))
+    }
+}
+
 impl Evaluable for proto::Subtract {
     fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
         match (take_argument(&mut arguments, "left")?, take_argument(&mut arguments, "right")?) {
@@ -256,3 +368,17 @@ impl Evaluable for proto::Subtract {
         }.map(ReleaseNode::new)
     }
 }
+
+impl Evaluable for proto::Tan {
+    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let data = take_argument(&mut arguments, "data")?.array()?.float()?;
+        Ok(ReleaseNode::new(data.mapv(|v| v.tan()).into()))
+    }
+}
+
+impl Evaluable for proto::Trim {
+    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let data = take_argument(&mut arguments, "data")?.array()?.string()?;
+        Ok(ReleaseNode::new(data.mapv(|v| v.trim().to_string()).into()))
+    }
+}
@@ -0,0 +1,277 @@
+use ndarray::{Array1, Array2};
+
+use smartnoise_validator::errors::*;
+use smartnoise_validator::Float;
+
+/// Solve the linear system `a x = b` for a symmetric positive definite matrix `a`.
+///
+/// The system is solved via a Cholesky decomposition of `a`.
+///
+/// # Arguments
+/// * `a` - Symmetric positive definite matrix of shape `(d, d)`.
+/// * `b` - Right-hand side of length `d`.
+///
+/// # Return
+/// The solution `x` of length `d`.
+///
+/// # Example
+/// ```
+/// use ndarray::{arr1, arr2};
+/// use smartnoise_runtime::utilities::linalg::cholesky_solve;
+/// let a = arr2(&[[4., 2.], [2., 3.]]);
+/// let b = arr1(&[2., 1.]);
+/// let x = cholesky_solve(&a, &b).unwrap();
+/// assert!((x[0] - 0.5).abs() < 1e-12 && x[1].abs() < 1e-12);
+/// ```
+pub fn cholesky_solve(a: &Array2<Float>, b: &Array1<Float>) -> Result<Array1<Float>> {
+    let d = b.len();
+    if a.shape() != [d, d] {
+        return Err("matrix must be square and conformable with the right-hand side".into())
+    }
+
+    // lower-triangular factor, such that a = l l^T
+    let mut l = Array2::<Float>::zeros((d, d));
+    for i in 0..d {
+        for j in 0..=i {
+            let partial = (0..j).map(|k| l[[i, k]] * l[[j, k]]).sum::<Float>();
+            if i == j {
+                let pivot = a[[i, i]] - partial;
+                if pivot.is_nan() || pivot <= 0. {
+                    return Err("matrix is not positive definite".into())
+                }
+                l[[i, j]] = pivot.sqrt();
+            } else {
+                l[[i, j]] = (a[[i, j]] - partial) / l[[j, j]];
+            }
+        }
+    }
+
+    // forward substitution: l z = b
+    let mut z = Array1::<Float>::zeros(d);
+    for i in 0..d {
+        z[i] = (b[i] - (0..i).map(|k| l[[i, k]] * z[k]).sum::<Float>()) / l[[i, i]];
+    }
+
+    // backward substitution: l^T x = z
+    let mut x = Array1::<Float>::zeros(d);
+    for i in (0..d).rev() {
+        x[i] = (z[i] - (i + 1..d).map(|k| l[[k, i]] * x[k]).sum::<Float>()) / l[[i, i]];
+    }
+
+    Ok(x)
+}
+
+/// Solve the linear system `a x = b` for a square, non-singular matrix `a`.
+///
+/// The system is solved via an LU decomposition of `a` with partial pivoting.
+///
+/// # Arguments
+/// * `a` - Square matrix of shape `(d, d)`.
+/// * `b` - Right-hand side of length `d`.
+///
+/// # Return
+/// The solution `x` of length `d`.
+///
+/// # Example
+/// ```
+/// use ndarray::{arr1, arr2};
+/// use smartnoise_runtime::utilities::linalg::solve;
+/// let a = arr2(&[[0., 2.], [1., 1.]]);
+/// let b = arr1(&[2., 3.]);
+/// let x = solve(&a, &b).unwrap();
+/// assert!((x[0] - 2.).abs() < 1e-12 && (x[1] - 1.).abs() < 1e-12);
+/// ```
+pub fn solve(a: &Array2<Float>, b: &Array1<Float>) -> Result<Array1<Float>> {
+    let d = b.len();
+    if a.shape() != [d, d] {
+        return Err("matrix must be square and conformable with the right-hand side".into())
+    }
+
+    let mut lu = a.clone();
+    let mut x = b.clone();
+
+    // forward elimination, swapping in the largest remaining pivot of each column
+    for j in 0..d {
+        let pivot_row = (j..d)
+            .max_by(|l, r| lu[[*l, j]].abs().partial_cmp(&lu[[*r, j]].abs()).unwrap_or(std::cmp::Ordering::Equal))
+            .unwrap_or(j);
+        let pivot = lu[[pivot_row, j]];
+        if pivot.is_nan() || pivot == 0. {
+            return Err("matrix is singular".into())
+        }
+        if pivot_row != j {
+            for k in 0..d {
+                lu.swap([j, k], [pivot_row, k]);
+            }
+            x.swap(j, pivot_row);
+        }
+        for i in j + 1..d {
+            let factor = lu[[i, j]] / pivot;
+            for k in j..d {
+                lu[[i, k]] -= factor * lu[[j, k]];
+            }
+            x[i] -= factor * x[j];
+        }
+    }
+
+    // backward substitution on the upper-triangular factor
+    for i in (0..d).rev() {
+        x[i] = (x[i] - (i + 1..d).map(|k| lu[[i, k]] * x[k]).sum::<Float>()) / lu[[i, i]];
+    }
+
+    Ok(x)
+}
+
+/// Compute the eigendecomposition of a symmetric matrix.
+///
+/// Eigenpairs are computed with cyclic Jacobi rotations.
+/// Only the upper triangle of `a` is read.
+///
+/// # Arguments
+/// * `a` - Symmetric matrix of shape `(d, d)`.
+///
+/// # Return
+/// The `d` eigenvalues in decreasing order,
+/// and a matrix whose columns are the corresponding unit-norm eigenvectors.
+///
+/// # Example
+/// ```
+/// use ndarray::arr2;
+/// use smartnoise_runtime::utilities::linalg::symmetric_eigen;
+/// let a = arr2(&[[2., 1.], [1., 2.]]);
+/// let (values, vectors) = symmetric_eigen(&a).unwrap();
+/// assert!((values[0] - 3.).abs() < 1e-12 && (values[1] - 1.).abs() < 1e-12);
+/// assert!((vectors[[0, 0]].abs() - 0.5_f64.sqrt()).abs() < 1e-12);
+/// ```
+pub fn symmetric_eigen(a: &Array2<Float>) -> Result<(Array1<Float>, Array2<Float>)> {
+    const MAX_SWEEPS: usize = 100;
+
+    let d = a.nrows();
+    if a.ncols() != d {
+        return Err("matrix must be square".into())
+    }
+    if a.iter().any(|v| !v.is_finite()) {
+        return Err("matrix must be finite".into())
+    }
+
+    // symmetrize from the upper triangle
+    let mut m = Array2::from_shape_fn((d, d), |(i, j)| a[[i.min(j), i.max(j)]]);
+    let mut vectors = Array2::<Float>::eye(d);
+
+    for _ in 0..MAX_SWEEPS {
+        let off_diagonal = (0..d)
+            .map(|p| (p + 1..d).map(|q| m[[p, q]].powi(2)).sum::<Float>())
+            .sum::<Float>();
+        if off_diagonal <= Float::EPSILON.powi(2) * m.iter().map(|v| v.powi(2)).sum::<Float>() {
+            break
+        }
+
+        for p in 0..d {
+            for q in p + 1..d {
+                if m[[p, q]] == 0. {
+                    continue
+                }
+                // rotation angle that zeroes out the (p, q) entry
+                let theta = (m[[q, q]] - m[[p, p]]) / (2. * m[[p, q]]);
+                let t = theta.signum() / (theta.abs() + (theta.powi(2) + 1.).sqrt());
+                let c = 1. / (t.powi(2) + 1.).sqrt();
+                let s = t * c;
+
+                for k in 0..d {
+                    let (m_kp, m_kq) = (m[[k, p]], m[[k, q]]);
+                    m[[k, p]] = c * m_kp - s * m_kq;
+                    m[[k, q]] = s * m_kp + c * m_kq;
+                }
+                for k in 0..d {
+                    let (m_pk, m_qk) = (m[[p, k]], m[[q, k]]);
+                    m[[p, k]] = c * m_pk - s * m_qk;
+                    m[[q, k]] = s * m_pk + c * m_qk;
+                }
+                for k in 0..d {
+                    let (v_kp, v_kq) = (vectors[[k, p]], vectors[[k, q]]);
+                    vectors[[k, p]] = c * v_kp - s * v_kq;
+                    vectors[[k, q]] = s * v_kp + c * v_kq;
+                }
+            }
+        }
+    }
+
+    let mut order = (0..d).collect::<Vec<usize>>();
+    order.sort_by(|l, r| m[[*r, *r]].partial_cmp(&m[[*l, *l]]).unwrap_or(std::cmp::Ordering::Equal));
+
+    Ok((
+        order.iter().map(|i| m[[*i, *i]]).collect(),
+        Array2::from_shape_fn((d, d), |(i, j)| vectors[[i, order[j]]])
+    ))
+}
+
+/// Project a symmetric matrix onto the positive semi-definite cone, by clamping its negative eigenvalues to zero.
+///
+/// # Arguments
+/// * `a` - Symmetric matrix of shape `(d, d)`.
+///
+/// # Return
+/// The nearest positive semi-definite matrix in Frobenius norm.
+///
+/// # Example
+/// ```
+/// use ndarray::arr2;
+/// use smartnoise_runtime::utilities::linalg::project_positive_semidefinite;
+/// let a = arr2(&[[1., 2.], [2., 1.]]);
+/// let projected = project_positive_semidefinite(&a).unwrap();
+/// assert!((projected[[0, 0]] - 1.5).abs() < 1e-12 && (projected[[0, 1]] - 1.5).abs() < 1e-12);
+/// ```
+pub fn project_positive_semidefinite(a: &Array2<Float>) -> Result<Array2<Float>> {
+    let (values, vectors) = symmetric_eigen(a)?;
+    let scaled = &vectors * &values.mapv(|v| v.max(0.));
+    Ok(scaled.dot(&vectors.t()))
+}
+
+/// Find the nearest correlation matrix to a symmetric matrix.
+///
+/// Alternates projections onto the positive semi-definite matrices and the matrices with unit diagonal,
+/// with Dykstra's correction for the positive semi-definite projection.
+///
+/// Higham (2002), Computing the Nearest Correlation Matrix - a Problem from Finance
+///
+/// # Arguments
+/// * `a` - Symmetric matrix of shape `(d, d)`.
+/// * `tolerance` - Stop once an iteration changes the matrix by less than this amount in Frobenius norm.
+/// * `max_iterations` - Maximum number of alternating projections.
+///
+/// # Return
+/// A matrix with unit diagonal that is positive semi-definite, up to the tolerance.
+///
+/// # Example
+/// ```
+/// use ndarray::arr2;
+/// use smartnoise_runtime::utilities::linalg::{nearest_correlation, symmetric_eigen};
+/// // not positive semi-definite
+/// let a = arr2(&[[1., 1., 0.], [1., 1., 1.], [0., 1., 1.]]);
+/// let correlation = nearest_correlation(&a, 1e-10, 1000).unwrap();
+/// assert!((0..3).all(|i| (correlation[[i, i]] - 1.).abs() < 1e-12));
+/// assert!(symmetric_eigen(&correlation).unwrap().0.iter().all(|v| *v > -1e-8));
+/// ```
+pub fn nearest_correlation(a: &Array2<Float>, tolerance: Float, max_iterations: usize) -> Result<Array2<Float>> {
+    let d = a.nrows();
+    if a.ncols() != d {
+        return Err("matrix must be square".into())
+    }
+
+    let mut y = a.clone();
+    let mut correction = Array2::<Float>::zeros((d, d));
+    for _ in 0..max_iterations {
+        let r = &y - &correction;
+        let x = project_positive_semidefinite(&r)?;
+        correction = &x - &r;
+
+        let mut next = x;
+        next.diag_mut().fill(1.);
+        let change = (&next - &y).iter().map(|v| v.powi(2)).sum::<Float>().sqrt();
+        y = next;
+        if change < tolerance {
+            break
+        }
+    }
+    Ok(y)
+}
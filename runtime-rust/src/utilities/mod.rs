@@ -8,6 +8,7 @@ use openssl::rand::rand_bytes;
 
 use smartnoise_validator::utilities::array::{slow_select, slow_stack};
 
+pub mod linalg;
 pub mod mechanisms;
 pub mod noise;
 
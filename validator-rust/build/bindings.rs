@@ -49,6 +49,12 @@ pub fn build_bindings(
             })
             .collect::<Vec<String>>().join(",\n                ");
 
+        // prost boxes the variants of components that contain components
+        let (box_open, box_close) = if component.options.values()
+            .any(|opt| opt.type_proto.as_deref() == Some("Component")) {
+            ("Box::new(", ")")
+        } else { ("", "") };
+
         bindings_analysis.push(format!(r#"
     #[allow(clippy::wrong_self_convention)]
     pub fn {name}({signature}) -> builders::{id}Builder {{
@@ -56,9 +62,9 @@ pub fn build_bindings(
         let mut arguments = IndexMap::<base::IndexKey, u32>::new();
         {argument_insertion}
         let component = proto::Component {{
-            variant: Some(proto::component::Variant::{variant}(proto::{id} {{
+            variant: Some(proto::component::Variant::{variant}({box_open}proto::{id} {{
                 {option_insertion}
-            }})),
+            }}{box_close})),
             omit: false,
             submission: self.submission_count,
             arguments: Some(proto::ArgumentNodeIds::new(arguments)),
@@ -78,7 +84,9 @@ pub fn build_bindings(
             id=component.id.to_camel_case(),
             signature=signature,
             argument_insertion=argument_insertion,
-            option_insertion=option_insertion
+            option_insertion=option_insertion,
+            box_open=box_open,
+            box_close=box_close
         ));
 
         // GENERATE BUILDER BINDINGS
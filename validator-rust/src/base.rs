@@ -872,6 +872,8 @@ pub enum DataType {
     Str,
     Float,
     Int,
+    /// Seconds since the Unix epoch in UTC, stored as integers.
+    Timestamp,
 }
 
 
@@ -1,105 +1,154 @@
 use crate::errors::*;
 
-use std::collections::HashMap;
-use crate::base::{Nature, NodeProperties, NatureCategorical, Jagged, ValueProperties, Array, DataType};
+use crate::base::{Array, DataType, IndexKey, Jagged, Nature, NatureContinuous, NodeProperties, Value, ValueProperties, Vector1DNull};
 
-use crate::proto;
-use crate::utilities::{prepend, standardize_categorical_argument, standardize_null_target_argument, standardize_float_argument, deduplicate};
-use crate::components::Component;
+use crate::{base, proto, Warnable};
+use crate::utilities::{get_literal, prepend, standardize_categorical_argument, standardize_float_argument, standardize_null_target_argument};
+use crate::components::{Component, Expandable};
 
-use crate::base::Value;
-use std::iter::Sum;
-use std::ops::Div;
-use noisy_float::prelude::n64;
+use ndarray::arr0;
+use indexmap::map::IndexMap;
+use std::ops::{Add, Div, Sub};
+use crate::utilities::inference::infer_property;
 
 impl Component for proto::Bin {
     fn propagate_property(
         &self,
         _privacy_definition: &Option<proto::PrivacyDefinition>,
-        public_arguments: &IndexMap<String, Value>,
-        properties: &NodeProperties,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: NodeProperties,
         _node_id: u32
     ) -> Result<Warnable<ValueProperties>> {
-        let mut data_property = properties.get("data")
-            .ok_or_else(|| Error::from("data: missing"))?.array()
+        let mut data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
             .map_err(prepend("data:"))?.clone();
 
         let num_columns = data_property.num_columns()
             .map_err(prepend("data:"))?;
 
-        let null_value = public_arguments.get("null_value")
-            .ok_or_else(|| Error::from("null_value: missing, must be public"))?.array()?;
-
-        data_property.assert_is_not_aggregated()?;
-        if data_property.data_type != DataType::F64 && data_property.data_type != DataType::I64 {
-            return Err("data: atomic type must be numeric".into())
+        if !data_property.releasable {
+            data_property.assert_is_not_aggregated()?;
         }
 
-        public_arguments.get("edges")
-            .ok_or_else(|| Error::from("edges: missing, must be public"))
-            .and_then(|v| v.jagged())
-            .and_then(|v| match (v, null_value) {
-                (Jagged::F64(jagged), Array::F64(null)) => {
-                    let null = standardize_null_target_argument(null, &num_columns)?;
-                    let edges = standardize_float_argument(jagged, &num_columns)?;
-
-                    if null.iter().any(|v| !v.is_finite()) {
-                        return Err("the replacement for null values must be finite".into())
-                    }
-                    data_property.nature = Some(Nature::Categorical(NatureCategorical {
-                        categories: Jagged::F64(edges.into_iter().zip(null.into_iter())
-                            .map(|(mut col, null)| {
-                                // mandate that edges be sorted
-                                if !col.windows(2).all(|w| w[0] <= w[1]) {
-                                    return Err("edges must be sorted".into());
-                                }
-                                col = nature_from_edges(&self.side, col)?;
-                                col.push(null);
-
-                                Ok(Some(deduplicate(col.into_iter().map(|v| n64(v as f64)).collect())
-                                    .into_iter().map(|v| v.raw()).collect()))
-                            }).collect::<Result<_>>()?),
-                    }));
-                    Ok(())
-                }
-                (Jagged::I64(jagged), Array::I64(null)) => {
-                    let null = standardize_null_target_argument(null, &num_columns)?;
-                    let edges = standardize_categorical_argument(jagged, &num_columns)?;
-                    data_property.nature = Some(Nature::Categorical(NatureCategorical {
-                        categories: Jagged::I64(edges.into_iter().zip(null.into_iter())
-                            .map(|(mut col, null)| {
-                                // mandate that edges be sorted
-                                if !col.windows(2).all(|w| w[0] <= w[1]) {
-                                    return Err("edges must be sorted".into());
-                                }
-                                col = nature_from_edges(&self.side, col)?;
-                                col.push(null);
-
-                                Ok(Some(deduplicate(col)))
-                            }).collect::<Result<_>>()?),
-                    }));
-                    Ok(())
+        let side = BinSide::new(&self.side)?;
+
+        let edges = public_arguments.get::<IndexKey>(&"edges".into())
+            .ok_or_else(|| Error::from("edges: missing, must be public"))?.ref_jagged()?;
+        let null_value = public_arguments.get::<IndexKey>(&"null_value".into())
+            .ok_or_else(|| Error::from("null_value: missing, must be public"))?.ref_array()?;
+
+        // every value is mapped to the representative of a bin, or to the null value
+        let (lower, upper) = match (&data_property.data_type, edges, null_value) {
+            (DataType::Float, Jagged::Float(edges), Array::Float(null)) => {
+                let null = standardize_null_target_argument(null.clone(), num_columns)?;
+                if !null.iter().all(|v| v.is_finite()) {
+                    return Err("null_value: must be finite".into())
                 }
-                _ => Err("edges: must be numeric".into())
-            })?;
+                let bounds = get_bounds(
+                    standardize_float_argument(edges.clone(), num_columns)?, null, &side)?;
+                (Vector1DNull::Float(bounds.iter().map(|(lower, _)| Some(*lower)).collect()),
+                 Vector1DNull::Float(bounds.iter().map(|(_, upper)| Some(*upper)).collect()))
+            }
+            (DataType::Int, Jagged::Int(edges), Array::Int(null)) => {
+                let null = standardize_null_target_argument(null.clone(), num_columns)?;
+                let bounds = get_bounds(
+                    standardize_categorical_argument(edges.clone(), num_columns)?, null, &side)?;
+                (Vector1DNull::Int(bounds.iter().map(|(lower, _)| Some(*lower)).collect()),
+                 Vector1DNull::Int(bounds.iter().map(|(_, upper)| Some(*upper)).collect()))
+            }
+            _ => return Err("data, edges and null_value must all be float, or all be integer".into())
+        };
 
-        Ok(data_property.into())
+        data_property.nature = Some(Nature::Continuous(NatureContinuous { lower, upper }));
+        data_property.nullity = false;
+        Ok(ValueProperties::Array(data_property).into())
     }
 }
 
+impl Expandable for proto::Bin {
+    fn expand_component(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        component: &proto::Component,
+        _public_arguments: &IndexMap<IndexKey, &Value>,
+        properties: &base::NodeProperties,
+        component_id: u32,
+        mut maximum_id: u32,
+    ) -> Result<base::ComponentExpansion> {
+        let mut component = component.clone();
+
+        let mut expansion = base::ComponentExpansion::default();
+
+        if !properties.contains_key::<IndexKey>(&"inclusive_left".into()) {
+            maximum_id += 1;
+            let id_inclusive_left = maximum_id;
+            let value = Value::Array(Array::Bool(arr0(true).into_dyn()));
+            expansion.properties.insert(id_inclusive_left, infer_property(&value, None, id_inclusive_left)?);
+            let (patch_node, release) = get_literal(value, component.submission)?;
+            expansion.computation_graph.insert(id_inclusive_left, patch_node);
+            expansion.releases.insert(id_inclusive_left, release);
+            component.insert_argument(&"inclusive_left".into(), id_inclusive_left);
+        }
+
+        expansion.computation_graph.insert(component_id, component);
 
-fn nature_from_edges<T: Clone + Sum + Div<Output=T> + From<i32>>(side: &str, mut edges: Vec<T>) -> Result<Vec<T>> {
-    Ok(match side {
-        "lower" => {
-            edges.pop();
-            edges
-        },
-        "midpoint" => edges.windows(2)
-            .map(|slice| slice.iter().cloned().sum::<T>() / T::from(2)).collect(),
-        "upper" => {
-            edges.remove(0);
-            edges
-        },
-        _ => bail!("side: must be lower, midpoint or upper")
-    })
-}
\ No newline at end of file
+        Ok(expansion)
+    }
+}
+
+/// How to refer to each bin.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BinSide {
+    Lower,
+    Midpoint,
+    Upper,
+}
+
+impl BinSide {
+    pub fn new(side: &str) -> Result<BinSide> {
+        Ok(match side.to_lowercase().as_str() {
+            "lower" => BinSide::Lower,
+            "midpoint" => BinSide::Midpoint,
+            "upper" => BinSide::Upper,
+            _ => bail!("side: must be lower, midpoint or upper")
+        })
+    }
+}
+
+/// The value each bin is mapped to.
+///
+/// The midpoint of integer bins is rounded towards the lower edge.
+///
+/// # Arguments
+/// * `edges` - Sorted edges of the bins.
+/// * `side` - Whether to refer to a bin by its lower edge, midpoint or upper edge.
+///
+/// # Return
+/// One representative for each bin.
+pub fn bin_representatives<T>(edges: &[T], side: &BinSide) -> Result<Vec<T>>
+    where T: Copy + PartialOrd + Add<Output=T> + Sub<Output=T> + Div<Output=T> + From<i32> {
+    if edges.len() < 2 {
+        return Err("edges: must define at least one bin".into())
+    }
+    if !edges.windows(2).all(|w| w[0] < w[1]) {
+        return Err("edges: must be sorted".into())
+    }
+    Ok(edges.windows(2).map(|w| match side {
+        BinSide::Lower => w[0],
+        BinSide::Midpoint => w[0] + (w[1] - w[0]) / T::from(2),
+        BinSide::Upper => w[1],
+    }).collect())
+}
+
+/// Bounds of each column, over the representatives of the bins and the null value.
+fn get_bounds<T>(edges: Vec<Vec<T>>, null: Vec<T>, side: &BinSide) -> Result<Vec<(T, T)>>
+    where T: Copy + PartialOrd + Add<Output=T> + Sub<Output=T> + Div<Output=T> + From<i32> {
+    edges.iter().zip(null)
+        .map(|(edges, null)| {
+            let representatives = bin_representatives(edges, side)?;
+            let lower = representatives.iter().fold(null, |l, r| if *r < l { *r } else { l });
+            let upper = representatives.iter().fold(null, |l, r| if *r > l { *r } else { l });
+            Ok((lower, upper))
+        })
+        .collect()
+}
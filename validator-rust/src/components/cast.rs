@@ -39,7 +39,7 @@ impl Component for proto::Cast {
         };
 
         match data_property.data_type {
-            DataType::Unknown => unreachable!(),
+            DataType::Unknown | DataType::Timestamp => unreachable!(),
             DataType::Bool => {
                 // true label must be defined
                 let true_label = get_argument(&public_arguments, "true_label")?.clone().array()?;
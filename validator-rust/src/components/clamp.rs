@@ -131,7 +131,7 @@ impl Component for proto::Clamp {
 
             }
 
-            DataType::Int => {
+            DataType::Int | DataType::Timestamp => {
                 // 1. check public arguments (constant n)
                 let mut clamp_lower = match public_arguments.get::<IndexKey>(&"lower".into()) {
                     Some(&lower) => lower.ref_array()?.clone().vec_int(Some(num_columns))
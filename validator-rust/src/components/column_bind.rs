@@ -67,4 +67,52 @@ impl Component for proto::ColumnBind {
                 .ok_or_else(|| Error::from("sample proportions must be shared among arguments"))?.and_then(|v| v.to_f64()),
         })))
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod test_column_bind {
+    use crate::bindings::Analysis;
+    use crate::components::sort::test_sort::utilities::*;
+
+    use super::*;
+
+    /// Properties of a column of events, of known length.
+    fn column_property(analysis: &mut Analysis, data: u32, name: &str) -> ValueProperties {
+        let column = column(analysis, data, name);
+        let mut property = analysis.properties(column).unwrap().array().unwrap().clone();
+        property.num_records = Some(10);
+        ValueProperties::Array(property)
+    }
+
+    fn bind(properties: Vec<ValueProperties>) -> Result<ArrayProperties> {
+        Ok(proto::ColumnBind {}.propagate_property(
+            &None, IndexMap::new(),
+            properties.into_iter().enumerate()
+                .map(|(idx, property)| ((idx as i64).into(), property))
+                .collect(),
+            0)?.0.array()?.clone())
+    }
+
+    #[test]
+    fn test_column_bind_naturally_ordered() {
+        let (mut analysis, data) = analysis_events();
+        let sorted = sort_by(&mut analysis, data, &["time"]);
+
+        let property = bind(vec![
+            column_property(&mut analysis, data, "user"),
+            column_property(&mut analysis, data, "time")]).unwrap();
+        assert!(property.naturally_ordered);
+        assert_eq!(property.num_columns, Some(2));
+
+        // the order of sorted columns is kept
+        let property = bind(vec![
+            column_property(&mut analysis, sorted, "user"),
+            column_property(&mut analysis, sorted, "time")]).unwrap();
+        assert!(!property.naturally_ordered);
+        assert_eq!(property.dataset_id, Some(sorted as i64));
+
+        // sorted and unsorted columns are not aligned
+        assert!(bind(vec![
+            column_property(&mut analysis, sorted, "user"),
+            column_property(&mut analysis, data, "time")]).is_err());
+    }
+}
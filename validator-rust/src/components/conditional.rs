@@ -0,0 +1,233 @@
+use indexmap::map::IndexMap;
+use itertools::Itertools;
+
+use crate::{base, proto, Warnable};
+use crate::base::{ArrayProperties, DataType, IndexKey, Jagged, Nature, NatureCategorical, NatureContinuous, NodeProperties, Value, ValueProperties, Vector1DNull};
+use crate::components::Component;
+use crate::components::transforms::{propagate_binary_group_id, propagate_binary_shape, propagate_union_property};
+use crate::errors::*;
+use crate::utilities::prepend;
+
+impl Component for proto::IfElse {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: NodeProperties,
+        node_id: u32
+    ) -> Result<Warnable<ValueProperties>> {
+        let condition_property = get_condition_property(&properties, "condition")?;
+        let then_property = get_array_property(&properties, "then")?;
+        let otherwise_property = get_array_property(&properties, "otherwise")?;
+
+        let branches_property = propagate_union_property(&then_property, &otherwise_property, node_id)
+            .map_err(prepend("then and otherwise:"))?;
+
+        let num_columns = condition_property.num_columns()
+            .map_err(prepend("condition:"))?;
+        if num_columns != 1 && num_columns != branches_property.num_columns()? {
+            return Err("condition: must have one column, or as many columns as then and otherwise".into())
+        }
+
+        Ok(ValueProperties::Array(propagate_condition(&condition_property, branches_property)?).into())
+    }
+}
+
+impl Component for proto::CaseWhen {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: NodeProperties,
+        node_id: u32
+    ) -> Result<Warnable<ValueProperties>> {
+        let conditions_property = get_condition_property(&properties, "conditions")?;
+        let values_property = get_array_property(&properties, "values")?;
+        let otherwise_property = get_array_property(&properties, "otherwise")?;
+
+        let (num_columns, _) = propagate_binary_shape(&conditions_property, &values_property)?;
+        if conditions_property.num_columns()? != num_columns {
+            return Err("conditions: must have one column for each column of values".into())
+        }
+        if otherwise_property.num_columns()? != 1 {
+            return Err("otherwise: must have one column".into())
+        }
+
+        let branches_property = propagate_union_property(
+            &collapse_columns(values_property)?, &otherwise_property, node_id)
+            .map_err(prepend("values and otherwise:"))?;
+
+        let mut property = propagate_condition(&conditions_property, branches_property)?;
+        // one value is chosen for each row
+        property.num_columns = Some(1);
+        property.dimensionality = Some(1);
+        Ok(ValueProperties::Array(property).into())
+    }
+}
+
+fn get_array_property(properties: &NodeProperties, name: &str) -> Result<ArrayProperties> {
+    Ok(properties.get::<IndexKey>(&name.into())
+        .ok_or_else(|| Error::from(format!("{}: missing", name)))?.array()
+        .map_err(prepend(&format!("{}:", name)))?.clone())
+}
+
+fn get_condition_property(properties: &NodeProperties, name: &str) -> Result<ArrayProperties> {
+    let condition_property = get_array_property(properties, name)?;
+
+    if !condition_property.releasable {
+        condition_property.assert_is_not_aggregated()?;
+        condition_property.assert_is_not_sampled()?;
+    }
+
+    if condition_property.data_type != DataType::Bool {
+        return Err(format!("{}: atomic type must be boolean", name).into())
+    }
+    Ok(condition_property)
+}
+
+/// Properties of the branches, once each row has been chosen by the condition.
+fn propagate_condition(
+    condition_property: &ArrayProperties, mut branches_property: ArrayProperties
+) -> Result<ArrayProperties> {
+    let (_, num_records) = propagate_binary_shape(condition_property, &branches_property)?;
+
+    branches_property.num_records = num_records;
+    branches_property.group_id = propagate_binary_group_id(condition_property, &branches_property)?;
+    if branches_property.releasable {
+        branches_property.dataset_id = condition_property.dataset_id;
+    }
+    branches_property.releasable &= condition_property.releasable;
+    branches_property.c_stability = branches_property.c_stability
+        .max(condition_property.c_stability);
+    branches_property.is_not_empty &= condition_property.is_not_empty;
+    branches_property.dimensionality = branches_property.dimensionality
+        .max(condition_property.dimensionality);
+    Ok(branches_property)
+}
+
+/// Properties of a column that may take the value of any column of the data.
+fn collapse_columns(mut property: ArrayProperties) -> Result<ArrayProperties> {
+    // the widest bound over all columns, if every column is bounded
+    fn collapse<T: Copy + PartialOrd>(bounds: Vec<Option<T>>, lower: bool) -> Vec<Option<T>> {
+        vec![bounds.into_iter().collect::<Option<Vec<T>>>()
+            .and_then(|bounds| bounds.into_iter()
+                .fold1(|l, r| if (r < l) == lower { r } else { l }))]
+    }
+
+    property.nature = match property.nature {
+        Some(Nature::Categorical(nature)) => match nature.categories {
+            Jagged::Int(categories) => Some(Jagged::Int(vec![categories.into_iter().flatten().collect()])),
+            Jagged::Str(categories) => Some(Jagged::Str(vec![categories.into_iter().flatten().collect()])),
+            Jagged::Bool(categories) => Some(Jagged::Bool(vec![categories.into_iter().flatten().collect()])),
+            Jagged::Float(_) => None
+        }.map(|categories| categories.deduplicate()).transpose()?
+            .map(|categories| Nature::Categorical(NatureCategorical { categories })),
+        Some(Nature::Continuous(nature)) => match (nature.lower, nature.upper) {
+            (Vector1DNull::Float(lower), Vector1DNull::Float(upper)) => Some((
+                Vector1DNull::Float(collapse(lower, true)), Vector1DNull::Float(collapse(upper, false)))),
+            (Vector1DNull::Int(lower), Vector1DNull::Int(upper)) => Some((
+                Vector1DNull::Int(collapse(lower, true)), Vector1DNull::Int(collapse(upper, false)))),
+            _ => None
+        }.map(|(lower, upper)| Nature::Continuous(NatureContinuous { lower, upper })),
+        None => None
+    };
+    property.num_columns = Some(1);
+    Ok(property)
+}
+
+#[cfg(test)]
+mod test_conditional {
+    use ndarray::arr1;
+
+    use crate::base::test_data;
+    use crate::bindings::Analysis;
+    use crate::components::clamp::test_clamp;
+
+    use super::*;
+
+    /// Four columns of float data, each clamped to its own bounds.
+    fn analysis_f64_columns() -> (Analysis, u32) {
+        test_clamp::utilities::analysis_f64_cont(
+            test_data::array2d_f64_10(),
+            Some(arr1(&[0., 0., 0., 0.]).into()),
+            Some(arr1(&[10., 5., 30., 1.]).into()))
+    }
+
+    fn get_array_property(analysis: &Analysis, id: u32) -> Result<ArrayProperties> {
+        Ok(analysis.properties(id)?.array()?.clone())
+    }
+
+    #[test]
+    fn test_if_else() {
+        let (mut analysis, data) = test_clamp::utilities::analysis_f64_cont(
+            test_data::array1d_f64_10_uniform(), None, None);
+        let threshold = analysis.literal().value(5.0.into()).value_public(true).build();
+        let condition = analysis.greater_than(data, threshold).build();
+        let otherwise = analysis.literal().value(20.0.into()).value_public(true).build();
+        let if_else = analysis.if_else(condition, data, otherwise).build();
+
+        let property = get_array_property(&analysis, if_else).unwrap();
+        assert_eq!(property.data_type, DataType::Float);
+        assert_eq!(property.num_records, Some(10));
+        assert_eq!(property.num_columns, Some(1));
+        assert_eq!(property.lower_float().unwrap(), vec![0.]);
+        assert_eq!(property.upper_float().unwrap(), vec![20.]);
+
+        // the condition must be boolean
+        let if_else = analysis.if_else(data, data, otherwise).build();
+        assert!(analysis.properties(if_else).is_err());
+    }
+
+    #[test]
+    fn test_case_when() {
+        let (mut analysis, values) = analysis_f64_columns();
+        let threshold = analysis.literal().value(5.0.into()).value_public(true).build();
+        let conditions = analysis.greater_than(values, threshold).build();
+        let otherwise = analysis.literal().value((-1.0).into()).value_public(true).build();
+        let case_when = analysis.case_when(conditions, values, otherwise).build();
+
+        // one value is chosen for each row, from any of the columns
+        let property = get_array_property(&analysis, case_when).unwrap();
+        assert_eq!(property.num_records, Some(10));
+        assert_eq!(property.num_columns, Some(1));
+        assert_eq!(property.dimensionality, Some(1));
+        assert_eq!(property.lower_float().unwrap(), vec![-1.]);
+        assert_eq!(property.upper_float().unwrap(), vec![30.]);
+
+        // otherwise must have a single column
+        let case_when = analysis.case_when(conditions, values, values).build();
+        assert!(analysis.properties(case_when).is_err());
+    }
+
+    #[test]
+    fn test_collapse_columns() {
+        let (analysis, values) = analysis_f64_columns();
+        let property = collapse_columns(get_array_property(&analysis, values).unwrap()).unwrap();
+        assert_eq!(property.num_columns, Some(1));
+        assert_eq!(property.lower_float().unwrap(), vec![0.]);
+        assert_eq!(property.upper_float().unwrap(), vec![30.]);
+
+        // the bound is only known if every column is bounded
+        let mut property = get_array_property(&analysis, values).unwrap();
+        property.nature = Some(Nature::Continuous(NatureContinuous {
+            lower: Vector1DNull::Float(vec![Some(0.), None]),
+            upper: Vector1DNull::Float(vec![Some(1.), Some(2.)]),
+        }));
+        let property = collapse_columns(property).unwrap();
+        assert!(property.lower_float().is_err());
+        assert_eq!(property.upper_float().unwrap(), vec![2.]);
+
+        // the categories are the union of the categories of each column
+        let (analysis, data) = test_clamp::utilities::analysis_string_cat(
+            test_data::array1d_string_10_uniform(), None, None);
+        let mut property = get_array_property(&analysis, data).unwrap();
+        property.nature = Some(Nature::Categorical(NatureCategorical {
+            categories: Jagged::Str(vec![
+                vec!["a".to_string(), "b".to_string()],
+                vec!["b".to_string(), "c".to_string()]])
+        }));
+        let property = collapse_columns(property).unwrap();
+        assert_eq!(property.categories().unwrap().string().unwrap(), vec![
+            vec!["a".to_string(), "b".to_string(), "c".to_string()]]);
+    }
+}
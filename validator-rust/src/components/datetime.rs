@@ -0,0 +1,138 @@
+use indexmap::map::IndexMap;
+use itertools::Itertools;
+
+use crate::{base, Integer, proto, Warnable};
+use crate::base::{Array, ArrayProperties, DataType, IndexKey, Jagged, Nature, NatureCategorical, NodeProperties, Value, ValueProperties};
+use crate::components::Component;
+use crate::errors::*;
+use crate::utilities::{prepend, standardize_null_target_argument};
+use crate::utilities::datetime::{DatetimePart, DatetimeUnit, enumerate_timestamps, extract_timestamp, truncate_timestamp};
+
+/// Largest number of categories a bounded timestamp column may be truncated into.
+const MAX_CATEGORIES: usize = 1 << 16;
+
+impl Component for proto::ParseDatetime {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: NodeProperties,
+        _node_id: u32
+    ) -> Result<Warnable<ValueProperties>> {
+        let mut data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        if !data_property.releasable {
+            data_property.assert_is_not_aggregated()?;
+        }
+
+        if data_property.data_type != DataType::Str {
+            return Err("data: atomic type must be string".into())
+        }
+
+        let num_columns = data_property.num_columns()
+            .map_err(prepend("data:"))?;
+
+        // strings that fail to parse are replaced with the null value, so the result is never null
+        match public_arguments.get::<IndexKey>(&"null_value".into())
+            .ok_or_else(|| Error::from("null_value: missing, must be public"))?.ref_array()? {
+            Array::Int(null) => standardize_null_target_argument(null.clone(), num_columns)?,
+            _ => return Err("null_value: must be an integer timestamp".into())
+        };
+
+        data_property.data_type = DataType::Timestamp;
+        data_property.nature = None;
+        data_property.nullity = false;
+        Ok(ValueProperties::Array(data_property).into())
+    }
+}
+
+impl Component for proto::DatetimeTrunc {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: NodeProperties,
+        _node_id: u32
+    ) -> Result<Warnable<ValueProperties>> {
+        let mut data_property = get_timestamp_property(&properties)?;
+
+        let unit = DatetimeUnit::new(&self.unit)?;
+
+        // bounded timestamps truncate into finitely many categories
+        data_property.nature = match &data_property.nature {
+            Some(Nature::Categorical(nature)) => Some(Jagged::Int(nature.categories.int()?.iter()
+                .map(|column| column.iter()
+                    .map(|v| truncate_timestamp(*v, &unit))
+                    .unique().collect())
+                .collect())),
+            Some(Nature::Continuous(_)) => data_property.lower_int_option()?.into_iter()
+                .zip(data_property.upper_int_option()?)
+                .map(|bounds| match bounds {
+                    (Some(lower), Some(upper)) => enumerate_timestamps(lower, upper, &unit, MAX_CATEGORIES),
+                    _ => None
+                })
+                .collect::<Option<Vec<Vec<Integer>>>>()
+                .map(Jagged::Int),
+            None => None
+        }.map(|categories| Nature::Categorical(NatureCategorical { categories }));
+
+        Ok(ValueProperties::Array(data_property).into())
+    }
+}
+
+impl Component for proto::DatetimeExtract {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: NodeProperties,
+        _node_id: u32
+    ) -> Result<Warnable<ValueProperties>> {
+        let mut data_property = get_timestamp_property(&properties)?;
+        let num_columns = data_property.num_columns()
+            .map_err(prepend("data:"))?;
+
+        let part = DatetimePart::new(&self.part)?;
+
+        data_property.nature = match (part.categories(), &data_property.nature) {
+            (Some(categories), _) => Some((0..num_columns).map(|_| categories.clone()).collect()),
+            // years are only known within the bounds of the data
+            (None, Some(Nature::Categorical(nature))) => Some(nature.categories.int()?.iter()
+                .map(|column| column.iter()
+                    .map(|v| extract_timestamp(*v, &part))
+                    .unique().collect())
+                .collect()),
+            (None, Some(Nature::Continuous(_))) => data_property.lower_int_option()?.into_iter()
+                .zip(data_property.upper_int_option()?)
+                .map(|bounds| match bounds {
+                    (Some(lower), Some(upper)) => {
+                        let (lower, upper) = (extract_timestamp(lower, &part), extract_timestamp(upper, &part));
+                        if upper - lower >= MAX_CATEGORIES as Integer { None } else { Some((lower..=upper).collect()) }
+                    }
+                    _ => None
+                })
+                .collect::<Option<Vec<Vec<Integer>>>>(),
+            (None, None) => None
+        }.map(|categories| Nature::Categorical(NatureCategorical { categories: Jagged::Int(categories) }));
+
+        data_property.data_type = DataType::Int;
+        Ok(ValueProperties::Array(data_property).into())
+    }
+}
+
+fn get_timestamp_property(properties: &NodeProperties) -> Result<ArrayProperties> {
+    let data_property = properties.get::<IndexKey>(&"data".into())
+        .ok_or("data: missing")?.array()
+        .map_err(prepend("data:"))?.clone();
+
+    if !data_property.releasable {
+        data_property.assert_is_not_aggregated()?;
+    }
+
+    if data_property.data_type != DataType::Timestamp {
+        return Err("data: atomic type must be timestamp".into())
+    }
+    Ok(data_property)
+}
@@ -0,0 +1,270 @@
+use indexmap::map::IndexMap;
+use itertools::Itertools;
+
+use crate::{base, Float, Integer, proto, Warnable};
+use crate::base::{ArrayProperties, DataType, IndexKey, NodeProperties, Value, ValueProperties};
+use crate::components::{Component, Expandable, Mechanism, Report};
+use crate::errors::*;
+use crate::utilities::{get_literal, prepend};
+use crate::utilities::inference::infer_property;
+use crate::utilities::privacy::{get_delta, privacy_usage_check};
+use crate::utilities::json::{AlgorithmInfo, JSONRelease, privacy_usage_to_json, value_to_json};
+
+impl Component for proto::DpChiSquareTest {
+    fn propagate_property(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: NodeProperties,
+        node_id: u32,
+    ) -> Result<Warnable<ValueProperties>> {
+        let privacy_definition = privacy_definition.as_ref()
+            .ok_or_else(|| "privacy_definition must be defined")?;
+
+        let data_property: ArrayProperties = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        data_property.assert_is_not_aggregated()?;
+
+        if data_property.num_columns()? != 2 {
+            return Err("data: must contain two columns".into())
+        }
+
+        let num_categories = public_arguments.get::<IndexKey>(&"categories".into())
+            .ok_or_else(|| Error::from("categories: missing, must be public"))?
+            .ref_jagged().map_err(prepend("categories:"))?
+            .num_records();
+        if num_categories.len() != 2 {
+            return Err("categories: must be defined for each column of data".into())
+        }
+        if num_categories.iter().any(|v| *v < 2) {
+            return Err("categories: each column must have at least two categories".into())
+        }
+
+        if self.num_simulations == 0 {
+            return Err("num_simulations: must be greater than zero".into())
+        }
+
+        let privacy_usage = self.privacy_usage.iter().cloned().map(Ok)
+            .fold1(|l, r| l? + r?).ok_or_else(|| "privacy_usage: must be defined")??;
+
+        check_table_test(privacy_definition, &properties, &self.mechanism, &privacy_usage)?;
+
+        let warnings = privacy_usage_check(
+            &privacy_usage,
+            data_property.num_records,
+            privacy_definition.strict_parameter_checks)?;
+
+        Ok(Warnable(test_output_property(&data_property, node_id).into(), warnings))
+    }
+}
+
+impl Expandable for proto::DpChiSquareTest {
+    fn expand_component(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        component: &proto::Component,
+        _public_arguments: &IndexMap<IndexKey, &Value>,
+        properties: &base::NodeProperties,
+        component_id: u32,
+        maximum_id: u32,
+    ) -> Result<base::ComponentExpansion> {
+        let privacy_definition = privacy_definition.as_ref()
+            .ok_or_else(|| "privacy definition must be defined")?;
+
+        if self.privacy_usage.len() != 1 {
+            return Err(Error::from("privacy usage must be of length one"));
+        }
+
+        let data_property: ArrayProperties = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        let mechanism = resolve_mechanism(&self.mechanism);
+        let (mut expansion, mut test_component) = expand_table_test(
+            privacy_definition, component, &data_property, &mechanism, maximum_id)?;
+
+        // update the mechanism and privacy usage
+        if let Some(proto::component::Variant::DpChiSquareTest(variant)) = &mut test_component.variant {
+            variant.mechanism = mechanism;
+            variant.privacy_usage = vec![self.privacy_usage[0].actual_to_effective(
+                data_property.sample_proportion.unwrap_or(1.),
+                data_property.c_stability,
+                privacy_definition.group_size)?];
+            // this case should never happen
+        } else { return Err(Error::from("Variant must be defined")) }
+        expansion.computation_graph.insert(component_id, test_component);
+
+        Ok(expansion)
+    }
+}
+
+impl Mechanism for proto::DpChiSquareTest {
+    fn get_privacy_usage(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        release_usage: Option<&Vec<proto::PrivacyUsage>>,
+        properties: &NodeProperties
+    ) -> Result<Option<Vec<proto::PrivacyUsage>>> {
+        let data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?;
+
+        Some(release_usage.unwrap_or_else(|| &self.privacy_usage).iter()
+            .map(|usage| usage.effective_to_actual(
+                data_property.sample_proportion.unwrap_or(1.),
+                data_property.c_stability,
+                privacy_definition.group_size))
+            .collect::<Result<Vec<proto::PrivacyUsage>>>()).transpose()
+    }
+}
+
+impl Report for proto::DpChiSquareTest {
+    fn summarize(
+        &self,
+        node_id: u32,
+        component: &proto::Component,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        _properties: NodeProperties,
+        release: &Value,
+        variable_names: Option<&Vec<base::IndexKey>>,
+    ) -> Result<Option<Vec<JSONRelease>>> {
+        let variables = (0..2)
+            .map(|column_number| variable_names
+                .and_then(|names| names.get(column_number)).cloned()
+                .unwrap_or_else(|| "[Unknown]".into()).to_string())
+            .collect::<Vec<String>>();
+
+        let release = JSONRelease {
+            description: "DP release information".to_string(),
+            statistic: "DPChiSquareTest".to_string(),
+            variables: serde_json::json!(variables),
+            release_info: value_to_json(release)?,
+            privacy_loss: serde_json::json!(self.privacy_usage.iter().map(privacy_usage_to_json).collect::<Vec<_>>()),
+            accuracy: None,
+            submission: component.submission,
+            node_id,
+            postprocess: false,
+            algorithm_info: AlgorithmInfo {
+                name: "Monte Carlo Independence Test".to_string(),
+                cite: "Gaboardi, Lim, Rogers and Vadhan (2016), Differentially Private Chi-Squared Hypothesis Testing: Goodness of Fit and Independence Testing".to_string(),
+                mechanism: self.mechanism.clone(),
+                argument: serde_json::json!({
+                    "num_simulations": self.num_simulations
+                }),
+            },
+        };
+        Ok(Some(vec![release]))
+    }
+}
+
+/// Resolve the `Automatic` mechanism of the noisy-table tests.
+pub(crate) fn resolve_mechanism(mechanism: &str) -> String {
+    match mechanism.to_lowercase().as_str() {
+        "automatic" => "simplegeometric".to_string(),
+        mechanism => mechanism.to_string()
+    }
+}
+
+/// Check the arguments and privacy usage shared by the noisy-table tests.
+pub(crate) fn check_table_test(
+    privacy_definition: &proto::PrivacyDefinition,
+    properties: &NodeProperties,
+    mechanism: &str,
+    privacy_usage: &proto::PrivacyUsage,
+) -> Result<()> {
+    properties.get::<IndexKey>(&"sensitivity".into())
+        .ok_or("sensitivity: missing")?;
+
+    match mechanism.to_lowercase().as_str() {
+        "simplegeometric" => {
+            properties.get::<IndexKey>(&"upper".into())
+                .ok_or("upper: missing")?;
+        }
+        "laplace" => if privacy_definition.protect_floating_point {
+            return Err("mechanism: Floating-point protections are enabled. The Laplace mechanism is susceptible to floating-point attacks.".into())
+        },
+        _ => bail!("`{}` is not recognized as a valid mechanism. Must be one of [`SimpleGeometric`, `Laplace`]", mechanism)
+    }
+
+    if get_delta(privacy_usage)? != 0. {
+        return Err("delta: must be zero, the tables are privatized with pure differential privacy".into())
+    }
+    Ok(())
+}
+
+/// Insert the categories, sensitivity and count bounds of the noisy table, and retrieve the updated component.
+pub(crate) fn expand_table_test(
+    privacy_definition: &proto::PrivacyDefinition,
+    component: &proto::Component,
+    data_property: &ArrayProperties,
+    mechanism: &str,
+    mut maximum_id: u32,
+) -> Result<(base::ComponentExpansion, proto::Component)> {
+    let mut expansion = base::ComponentExpansion::default();
+    let mut test_component = component.clone();
+
+    let mut insert_literal = |name: &str, value: Value, maximum_id: &mut u32| -> Result<()> {
+        *maximum_id += 1;
+        let id_literal = *maximum_id;
+        let (patch_node, release) = get_literal(value, component.submission)?;
+        expansion.computation_graph.insert(id_literal, patch_node);
+        expansion.properties.insert(id_literal, infer_property(&release.value, None, id_literal)?);
+        expansion.releases.insert(id_literal, release);
+        test_component.insert_argument(&name.into(), id_literal);
+        Ok(())
+    };
+
+    if !component.arguments().contains_key::<IndexKey>(&"categories".into()) {
+        let categories = data_property.categories()
+            .map_err(|_| Error::from("categories: must be supplied when the categories of data are not known"))?;
+        insert_literal("categories", Value::Jagged(categories), &mut maximum_id)?;
+    }
+
+    // always overwrite sensitivity. This is not something a user may configure
+    use proto::privacy_definition::Neighboring;
+    let sensitivity: Float = match Neighboring::from_i32(privacy_definition.neighboring)
+        .ok_or_else(|| Error::from("neighboring definition must be either \"AddRemove\" or \"Substitute\""))? {
+        // only one cell may be edited
+        Neighboring::AddRemove => 1.,
+        // a record may switch from one cell to another
+        Neighboring::Substitute => 2.
+    };
+    insert_literal("sensitivity", Value::from(sensitivity), &mut maximum_id)?;
+
+    if mechanism == "simplegeometric" {
+        let upper = match data_property.num_records {
+            Some(num_records) => num_records as Integer,
+            None => if privacy_definition.protect_elapsed_time {
+                return Err("the number of records must be known when protecting elapsed time".into())
+            } else {
+                Integer::MAX
+            }
+        };
+        insert_literal("upper", Value::from(upper), &mut maximum_id)?;
+    }
+
+    Ok((expansion, test_component))
+}
+
+/// Properties of the statistic and p-value released by the noisy-table tests.
+pub(crate) fn test_output_property(data_property: &ArrayProperties, node_id: u32) -> ArrayProperties {
+    ArrayProperties {
+        num_records: Some(2),
+        num_columns: Some(1),
+        nullity: false,
+        releasable: true,
+        c_stability: 1,
+        aggregator: None,
+        nature: None,
+        data_type: DataType::Float,
+        dataset_id: None,
+        node_id: node_id as i64,
+        is_not_empty: true,
+        dimensionality: Some(1),
+        group_id: data_property.group_id.clone(),
+        naturally_ordered: true,
+        sample_proportion: None,
+    }
+}
@@ -0,0 +1,211 @@
+use indexmap::map::IndexMap;
+use itertools::Itertools;
+
+use crate::{base, Float, proto, Warnable};
+use crate::base::{ArrayProperties, DataType, IndexKey, NodeProperties, SensitivitySpace, Value, ValueProperties};
+use crate::components::{Component, Expandable, Mechanism, Report, Sensitivity};
+use crate::errors::*;
+use crate::utilities::{get_literal, prepend};
+use crate::utilities::inference::infer_property;
+use crate::utilities::privacy::{get_delta, privacy_usage_check};
+use crate::utilities::json::{AlgorithmInfo, JSONRelease, privacy_usage_to_json, value_to_json};
+
+impl Component for proto::DpConfidenceInterval {
+    fn propagate_property(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: NodeProperties,
+        node_id: u32,
+    ) -> Result<Warnable<ValueProperties>> {
+        let privacy_definition = privacy_definition.as_ref()
+            .ok_or_else(|| "privacy_definition must be defined")?;
+
+        if privacy_definition.protect_floating_point {
+            return Err("Floating-point protections are enabled. The Laplace mechanism is susceptible to floating-point attacks.".into())
+        }
+
+        let data_property: ArrayProperties = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        data_property.assert_is_not_aggregated()?;
+        data_property.assert_non_null()?;
+
+        if data_property.num_columns()? != 1 {
+            return Err("data: must contain one column".into())
+        }
+        data_property.num_records()
+            .map_err(|_| Error::from("data: the number of records must be known. Consider resizing the data"))?;
+
+        match data_property.data_type {
+            DataType::Bool => (),
+            DataType::Float | DataType::Int => {
+                data_property.lower_float().map_err(prepend("data:"))?;
+                data_property.upper_float().map_err(prepend("data:"))?;
+                properties.get::<IndexKey>(&"variance_sensitivity".into())
+                    .ok_or("variance_sensitivity: missing")?;
+            }
+            _ => return Err("data: must be numeric or boolean".into())
+        }
+        properties.get::<IndexKey>(&"mean_sensitivity".into())
+            .ok_or("mean_sensitivity: missing")?;
+
+        if !(0. < self.alpha && self.alpha < 1.) {
+            return Err("alpha: must be in (0, 1)".into())
+        }
+        if self.num_simulations == 0 {
+            return Err("num_simulations: must be greater than zero".into())
+        }
+
+        let privacy_usage = self.privacy_usage.iter().cloned().map(Ok)
+            .fold1(|l, r| l? + r?).ok_or_else(|| "privacy_usage: must be defined")??;
+
+        if get_delta(&privacy_usage)? != 0. {
+            return Err("delta: must be zero, the mean and variance are privatized with the Laplace mechanism".into())
+        }
+
+        let warnings = privacy_usage_check(
+            &privacy_usage,
+            data_property.num_records,
+            privacy_definition.strict_parameter_checks)?;
+
+        Ok(Warnable(ArrayProperties {
+            num_records: Some(3),
+            num_columns: Some(1),
+            nullity: false,
+            releasable: true,
+            c_stability: 1,
+            aggregator: None,
+            nature: None,
+            data_type: DataType::Float,
+            dataset_id: None,
+            node_id: node_id as i64,
+            is_not_empty: true,
+            dimensionality: Some(1),
+            group_id: data_property.group_id,
+            naturally_ordered: true,
+            sample_proportion: None,
+        }.into(), warnings))
+    }
+}
+
+impl Expandable for proto::DpConfidenceInterval {
+    fn expand_component(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        component: &proto::Component,
+        _public_arguments: &IndexMap<IndexKey, &Value>,
+        properties: &base::NodeProperties,
+        component_id: u32,
+        mut maximum_id: u32,
+    ) -> Result<base::ComponentExpansion> {
+        let privacy_definition = privacy_definition.as_ref()
+            .ok_or_else(|| "privacy definition must be defined")?;
+
+        if self.privacy_usage.len() != 1 {
+            return Err(Error::from("privacy usage must be of length one"));
+        }
+
+        let data_property: ArrayProperties = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        let mut expansion = base::ComponentExpansion::default();
+        let mut interval_component = component.clone();
+
+        let mut insert_literal = |name: &str, value: Value, maximum_id: &mut u32| -> Result<()> {
+            *maximum_id += 1;
+            let id_literal = *maximum_id;
+            let (patch_node, release) = get_literal(value, component.submission)?;
+            expansion.computation_graph.insert(id_literal, patch_node);
+            expansion.properties.insert(id_literal, infer_property(&release.value, None, id_literal)?);
+            expansion.releases.insert(id_literal, release);
+            interval_component.insert_argument(&name.into(), id_literal);
+            Ok(())
+        };
+
+        // always overwrite sensitivities. These are not something a user may configure
+        if data_property.data_type == DataType::Bool {
+            // the proportion is a mean of values bounded in [0, 1]
+            let num_records = data_property.num_records()? as Float;
+            insert_literal("mean_sensitivity", Value::from(1. / num_records), &mut maximum_id)?;
+        } else {
+            let mean_sensitivity = proto::Mean {}.compute_sensitivity(
+                privacy_definition, properties, &SensitivitySpace::KNorm(1))?;
+            insert_literal("mean_sensitivity", mean_sensitivity, &mut maximum_id)?;
+
+            let variance_sensitivity = proto::Variance { finite_sample_correction: true }.compute_sensitivity(
+                privacy_definition, properties, &SensitivitySpace::KNorm(1))?;
+            insert_literal("variance_sensitivity", variance_sensitivity, &mut maximum_id)?;
+        }
+
+        // update the privacy usage
+        if let Some(proto::component::Variant::DpConfidenceInterval(variant)) = &mut interval_component.variant {
+            variant.privacy_usage = vec![self.privacy_usage[0].actual_to_effective(
+                data_property.sample_proportion.unwrap_or(1.),
+                data_property.c_stability,
+                privacy_definition.group_size)?];
+            // this case should never happen
+        } else { return Err(Error::from("Variant must be defined")) }
+        expansion.computation_graph.insert(component_id, interval_component);
+
+        Ok(expansion)
+    }
+}
+
+impl Mechanism for proto::DpConfidenceInterval {
+    fn get_privacy_usage(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        release_usage: Option<&Vec<proto::PrivacyUsage>>,
+        properties: &NodeProperties
+    ) -> Result<Option<Vec<proto::PrivacyUsage>>> {
+        let data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?;
+
+        Some(release_usage.unwrap_or_else(|| &self.privacy_usage).iter()
+            .map(|usage| usage.effective_to_actual(
+                data_property.sample_proportion.unwrap_or(1.),
+                data_property.c_stability,
+                privacy_definition.group_size))
+            .collect::<Result<Vec<proto::PrivacyUsage>>>()).transpose()
+    }
+}
+
+impl Report for proto::DpConfidenceInterval {
+    fn summarize(
+        &self,
+        node_id: u32,
+        component: &proto::Component,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        _properties: NodeProperties,
+        release: &Value,
+        variable_names: Option<&Vec<base::IndexKey>>,
+    ) -> Result<Option<Vec<JSONRelease>>> {
+        let release = JSONRelease {
+            description: "DP release information".to_string(),
+            statistic: "DPConfidenceInterval".to_string(),
+            variables: serde_json::json!(variable_names.cloned()
+                .unwrap_or_else(Vec::new).iter()
+                .map(|v| v.to_string()).collect::<Vec<String>>()),
+            release_info: value_to_json(release)?,
+            privacy_loss: serde_json::json!(self.privacy_usage.iter().map(privacy_usage_to_json).collect::<Vec<_>>()),
+            accuracy: None,
+            submission: component.submission,
+            node_id,
+            postprocess: false,
+            algorithm_info: AlgorithmInfo {
+                name: "NoisyVar Parametric Bootstrap".to_string(),
+                cite: "Du, Foot, Moniz, Bray, Dang and Xu (2020), Differentially Private Confidence Intervals".to_string(),
+                mechanism: "Laplace".to_string(),
+                argument: serde_json::json!({
+                    "alpha": self.alpha,
+                    "num_simulations": self.num_simulations
+                }),
+            },
+        };
+        Ok(Some(vec![release]))
+    }
+}
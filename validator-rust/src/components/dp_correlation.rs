@@ -0,0 +1,263 @@
+use indexmap::map::IndexMap;
+use itertools::Itertools;
+
+use crate::{base, Float, proto, Warnable};
+use crate::base::{ArrayProperties, DataframeProperties, DataType, IndexKey, Nature, NatureContinuous, NodeProperties, SensitivitySpace, Value, ValueProperties, Vector1DNull};
+use crate::components::{Component, Expandable, Mechanism, Report, Sensitivity};
+use crate::errors::*;
+use crate::utilities::{get_literal, prepend};
+use crate::utilities::inference::infer_property;
+use crate::utilities::privacy::{get_delta, privacy_usage_check};
+use crate::utilities::json::{Accuracy, AlgorithmInfo, JSONRelease, privacy_usage_to_json, value_to_json};
+
+impl Component for proto::DpCorrelation {
+    fn propagate_property(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: NodeProperties,
+        node_id: u32,
+    ) -> Result<Warnable<ValueProperties>> {
+        let privacy_definition = privacy_definition.as_ref()
+            .ok_or_else(|| "privacy_definition must be defined")?;
+
+        if privacy_definition.protect_floating_point {
+            return Err("Floating-point protections are enabled. Perturbation of the covariance matrix is susceptible to floating-point attacks.".into())
+        }
+
+        let data_property: ArrayProperties = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        data_property.assert_is_not_aggregated()?;
+        data_property.assert_non_null()?;
+
+        if data_property.data_type != DataType::Float {
+            return Err("data: must be float".into())
+        }
+        let num_columns = data_property.num_columns()?;
+        if num_columns < 2 {
+            return Err("data: must contain at least two columns".into())
+        }
+        data_property.num_records()
+            .map_err(|_| Error::from("data: the number of records must be known. Consider resizing the data"))?;
+
+        let lower = data_property.lower_float().map_err(prepend("data:"))?;
+        let upper = data_property.upper_float().map_err(prepend("data:"))?;
+        if lower.iter().zip(upper.iter()).any(|(l, u)| l >= u) {
+            return Err("data: the lower bound of each column must be less than the upper bound".into())
+        }
+
+        ["sensitivity", "lower", "upper"].iter().try_for_each(|name|
+            properties.get::<IndexKey>(&name.to_string().into())
+                .map(|_| ()).ok_or_else(|| Error::from(format!("{}: missing", name))))?;
+
+        if !(0. < self.alpha && self.alpha < 1.) {
+            return Err("alpha: must be in (0, 1)".into())
+        }
+        if self.num_simulations == 0 {
+            return Err("num_simulations: must be greater than zero".into())
+        }
+
+        let privacy_usage = self.privacy_usage.iter().cloned().map(Ok)
+            .fold1(|l, r| l? + r?).ok_or_else(|| "privacy_usage: must be defined")??;
+
+        let delta = get_delta(&privacy_usage)?;
+        match self.mechanism.to_lowercase().as_str() {
+            "laplace" => if delta != 0. {
+                return Err("delta: must be zero for the Laplace mechanism".into())
+            },
+            "gaussian" | "analyticgaussian" => if delta == 0. {
+                return Err("delta: must be greater than zero for the Gaussian mechanism".into())
+            },
+            _ => bail!("`{}` is not recognized as a valid mechanism. Must be one of [`Laplace`, `Gaussian`, `AnalyticGaussian`]", self.mechanism)
+        }
+
+        let warnings = privacy_usage_check(
+            &privacy_usage,
+            data_property.num_records,
+            privacy_definition.strict_parameter_checks)?;
+
+        let matrix_property = |nature: Option<Nature>| ValueProperties::Array(ArrayProperties {
+            num_records: Some(num_columns),
+            num_columns: Some(num_columns),
+            nullity: false,
+            releasable: true,
+            c_stability: 1,
+            aggregator: None,
+            nature,
+            data_type: DataType::Float,
+            dataset_id: Some(node_id as i64),
+            node_id: node_id as i64,
+            is_not_empty: true,
+            dimensionality: Some(2),
+            group_id: data_property.group_id.clone(),
+            naturally_ordered: true,
+            sample_proportion: None,
+        });
+
+        Ok(Warnable(ValueProperties::Dataframe(DataframeProperties {
+            children: indexmap![
+                "correlation".into() => matrix_property(Some(Nature::Continuous(NatureContinuous {
+                    lower: Vector1DNull::Float(vec![Some(-1.); num_columns as usize]),
+                    upper: Vector1DNull::Float(vec![Some(1.); num_columns as usize]),
+                }))),
+                "accuracy".into() => matrix_property(None)
+            ],
+        }), warnings))
+    }
+}
+
+impl Expandable for proto::DpCorrelation {
+    fn expand_component(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        component: &proto::Component,
+        _public_arguments: &IndexMap<IndexKey, &Value>,
+        properties: &base::NodeProperties,
+        component_id: u32,
+        mut maximum_id: u32,
+    ) -> Result<base::ComponentExpansion> {
+        let privacy_definition = privacy_definition.as_ref()
+            .ok_or_else(|| "privacy definition must be defined")?;
+
+        if self.privacy_usage.len() != 1 {
+            return Err(Error::from("privacy usage must be of length one"));
+        }
+
+        let data_property: ArrayProperties = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+        let num_columns = data_property.num_columns()? as usize;
+
+        let mechanism = match self.mechanism.to_lowercase().as_str() {
+            "automatic" => "laplace".to_string(),
+            mechanism => mechanism.to_string()
+        };
+
+        let mut expansion = base::ComponentExpansion::default();
+        let mut correlation_component = component.clone();
+
+        let mut insert_literal = |name: &str, value: Value, maximum_id: &mut u32| -> Result<()> {
+            *maximum_id += 1;
+            let id_literal = *maximum_id;
+            let (patch_node, release) = get_literal(value, component.submission)?;
+            expansion.computation_graph.insert(id_literal, patch_node);
+            expansion.properties.insert(id_literal, infer_property(&release.value, None, id_literal)?);
+            expansion.releases.insert(id_literal, release);
+            correlation_component.insert_argument(&name.into(), id_literal);
+            Ok(())
+        };
+
+        // always overwrite the bounds and sensitivity. These are not something a user may configure
+        insert_literal("lower", ndarray::arr1(&data_property.lower_float()?).into_dyn().into(), &mut maximum_id)?;
+        insert_literal("upper", ndarray::arr1(&data_property.upper_float()?).into_dyn().into(), &mut maximum_id)?;
+
+        // the covariance is computed over the data rescaled to the unit interval,
+        // so every entry has the same sensitivity and receives the same noise
+        let mut unit_property = data_property.clone();
+        unit_property.nature = Some(Nature::Continuous(NatureContinuous {
+            lower: Vector1DNull::Float(vec![Some(0.); num_columns]),
+            upper: Vector1DNull::Float(vec![Some(1.); num_columns]),
+        }));
+        let space = if mechanism == "laplace" { 1 } else { 2 };
+        let entry_sensitivities = proto::Covariance { finite_sample_correction: true }.compute_sensitivity(
+            privacy_definition,
+            &indexmap!["data".into() => ValueProperties::Array(unit_property)],
+            &SensitivitySpace::KNorm(space))?.array()?.float()?;
+
+        // sensitivity of the upper triangle of the covariance matrix, as a vector
+        let sensitivity: Float = match space {
+            1 => entry_sensitivities.iter().sum(),
+            _ => entry_sensitivities.iter().map(|v| v.powi(2)).sum::<Float>().sqrt()
+        };
+        insert_literal("sensitivity", Value::from(sensitivity), &mut maximum_id)?;
+
+        // update the mechanism and privacy usage
+        if let Some(proto::component::Variant::DpCorrelation(variant)) = &mut correlation_component.variant {
+            variant.mechanism = mechanism;
+            variant.privacy_usage = vec![self.privacy_usage[0].actual_to_effective(
+                data_property.sample_proportion.unwrap_or(1.),
+                data_property.c_stability,
+                privacy_definition.group_size)?];
+            // this case should never happen
+        } else { return Err(Error::from("Variant must be defined")) }
+        expansion.computation_graph.insert(component_id, correlation_component);
+
+        Ok(expansion)
+    }
+}
+
+impl Mechanism for proto::DpCorrelation {
+    fn get_privacy_usage(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        release_usage: Option<&Vec<proto::PrivacyUsage>>,
+        properties: &NodeProperties
+    ) -> Result<Option<Vec<proto::PrivacyUsage>>> {
+        let data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?;
+
+        Some(release_usage.unwrap_or_else(|| &self.privacy_usage).iter()
+            .map(|usage| usage.effective_to_actual(
+                data_property.sample_proportion.unwrap_or(1.),
+                data_property.c_stability,
+                privacy_definition.group_size))
+            .collect::<Result<Vec<proto::PrivacyUsage>>>()).transpose()
+    }
+}
+
+impl Report for proto::DpCorrelation {
+    fn summarize(
+        &self,
+        node_id: u32,
+        component: &proto::Component,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        _properties: NodeProperties,
+        release: &Value,
+        variable_names: Option<&Vec<base::IndexKey>>,
+    ) -> Result<Option<Vec<JSONRelease>>> {
+        let release = match release {
+            Value::Dataframe(release) => release,
+            _ => return Err("release must be a dataframe".into())
+        };
+        let correlation = release.get::<IndexKey>(&"correlation".into())
+            .ok_or_else(|| Error::from("correlation: missing from release"))?
+            .ref_array()?.ref_float()?;
+        let accuracy = release.get::<IndexKey>(&"accuracy".into())
+            .ok_or_else(|| Error::from("accuracy: missing from release"))?
+            .ref_array()?.ref_float()?;
+
+        let num_columns = correlation.shape().first().cloned().unwrap_or(0);
+        let variable_name = |column_number: usize| variable_names
+            .and_then(|names| names.get(column_number)).cloned()
+            .unwrap_or_else(|| "[Unknown]".into()).to_string();
+
+        // one release per pair of columns, each with its own accuracy
+        Ok(Some((0..num_columns).tuple_combinations()
+            .map(|(i, j)| Ok(JSONRelease {
+                description: "DP release information".to_string(),
+                statistic: "DPCorrelation".to_string(),
+                variables: serde_json::json!([variable_name(i), variable_name(j)]),
+                release_info: value_to_json(&Value::from(correlation[[i, j]]))?,
+                privacy_loss: serde_json::json!(self.privacy_usage.iter().map(privacy_usage_to_json).collect::<Vec<_>>()),
+                accuracy: Some(Accuracy {
+                    accuracy_value: accuracy[[i, j]],
+                    alpha: self.alpha,
+                }),
+                submission: component.submission,
+                node_id,
+                postprocess: false,
+                algorithm_info: AlgorithmInfo {
+                    name: "Projected Noisy Correlation".to_string(),
+                    cite: "Higham (2002), Computing the Nearest Correlation Matrix".to_string(),
+                    mechanism: self.mechanism.clone(),
+                    argument: serde_json::json!({
+                        "num_simulations": self.num_simulations
+                    }),
+                },
+            }))
+            .collect::<Result<Vec<JSONRelease>>>()?))
+    }
+}
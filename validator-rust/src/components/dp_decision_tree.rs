@@ -0,0 +1,319 @@
+use indexmap::map::IndexMap;
+use itertools::Itertools;
+
+use crate::{base, Float, proto, Warnable};
+use crate::base::{ArrayProperties, DataframeProperties, DataType, IndexKey, NodeProperties, Value, ValueProperties};
+use crate::components::{Component, Expandable, Mechanism, Report};
+use crate::errors::*;
+use crate::utilities::{get_literal, prepend};
+use crate::utilities::inference::infer_property;
+use crate::utilities::privacy::{get_delta, privacy_usage_check};
+use crate::utilities::json::{AlgorithmInfo, JSONRelease, privacy_usage_to_json, value_to_json};
+
+/// Largest depth of a tree, as a complete tree has `2^max_depth` leaves.
+const MAX_DEPTH: u32 = 16;
+
+impl Component for proto::DpDecisionTree {
+    fn propagate_property(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: NodeProperties,
+        node_id: u32,
+    ) -> Result<Warnable<ValueProperties>> {
+        propagate_tree(
+            privacy_definition, public_arguments, properties,
+            &self.privacy_usage, self.max_depth, 1, node_id)
+    }
+}
+
+impl Expandable for proto::DpDecisionTree {
+    fn expand_component(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        component: &proto::Component,
+        _public_arguments: &IndexMap<IndexKey, &Value>,
+        properties: &base::NodeProperties,
+        component_id: u32,
+        maximum_id: u32,
+    ) -> Result<base::ComponentExpansion> {
+        let privacy_definition = privacy_definition.as_ref()
+            .ok_or_else(|| "privacy definition must be defined")?;
+
+        if self.privacy_usage.len() != 1 {
+            return Err(Error::from("privacy usage must be of length one"));
+        }
+
+        let label_property: ArrayProperties = properties.get::<IndexKey>(&"label".into())
+            .ok_or("label: missing")?.array()
+            .map_err(prepend("label:"))?.clone();
+
+        let (mut expansion, mut tree_component) = expand_tree(
+            privacy_definition, component, &label_property, maximum_id)?;
+
+        // update the privacy usage
+        if let Some(proto::component::Variant::DpDecisionTree(variant)) = &mut tree_component.variant {
+            variant.privacy_usage = vec![self.privacy_usage[0].actual_to_effective(
+                label_property.sample_proportion.unwrap_or(1.),
+                label_property.c_stability,
+                privacy_definition.group_size)?];
+            // this case should never happen
+        } else { return Err(Error::from("Variant must be defined")) }
+        expansion.computation_graph.insert(component_id, tree_component);
+
+        Ok(expansion)
+    }
+}
+
+impl Mechanism for proto::DpDecisionTree {
+    fn get_privacy_usage(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        release_usage: Option<&Vec<proto::PrivacyUsage>>,
+        properties: &NodeProperties
+    ) -> Result<Option<Vec<proto::PrivacyUsage>>> {
+        let label_property = properties.get::<IndexKey>(&"label".into())
+            .ok_or("label: missing")?.array()
+            .map_err(prepend("label:"))?;
+
+        Some(release_usage.unwrap_or_else(|| &self.privacy_usage).iter()
+            .map(|usage| usage.effective_to_actual(
+                label_property.sample_proportion.unwrap_or(1.),
+                label_property.c_stability,
+                privacy_definition.group_size))
+            .collect::<Result<Vec<proto::PrivacyUsage>>>()).transpose()
+    }
+}
+
+impl Report for proto::DpDecisionTree {
+    fn summarize(
+        &self,
+        node_id: u32,
+        component: &proto::Component,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: NodeProperties,
+        release: &Value,
+        variable_names: Option<&Vec<base::IndexKey>>,
+    ) -> Result<Option<Vec<JSONRelease>>> {
+        let release = summarize_tree(
+            node_id, component, &properties, release, variable_names,
+            "DPDecisionTree", &self.privacy_usage, serde_json::json!({
+                "max_depth": self.max_depth
+            }))?;
+        Ok(Some(vec![release]))
+    }
+}
+
+/// Check the arguments and privacy usage shared by the tree classifiers, and derive the properties of the model.
+pub(crate) fn propagate_tree(
+    privacy_definition: &Option<proto::PrivacyDefinition>,
+    public_arguments: IndexMap<base::IndexKey, &Value>,
+    properties: NodeProperties,
+    privacy_usage: &[proto::PrivacyUsage],
+    max_depth: u32,
+    num_trees: u32,
+    node_id: u32,
+) -> Result<Warnable<ValueProperties>> {
+    let privacy_definition = privacy_definition.as_ref()
+        .ok_or_else(|| "privacy_definition must be defined")?;
+
+    if privacy_definition.protect_floating_point {
+        return Err("Floating-point protections are enabled. The exponential and Laplace mechanisms are susceptible to floating-point attacks.".into())
+    }
+
+    let label_property: ArrayProperties = properties.get::<IndexKey>(&"label".into())
+        .ok_or("label: missing")?.array()
+        .map_err(prepend("label:"))?.clone();
+    let data_property: ArrayProperties = properties.get::<IndexKey>(&"data".into())
+        .ok_or("data: missing")?.array()
+        .map_err(prepend("data:"))?.clone();
+
+    label_property.assert_is_not_aggregated()?;
+    data_property.assert_is_not_aggregated()?;
+    // null values of the data are sent to the right child of each split
+    label_property.assert_non_null()?;
+
+    if label_property.num_columns()? != 1 {
+        return Err("label: must have exactly one column".into())
+    }
+    if data_property.data_type != DataType::Float && data_property.data_type != DataType::Int {
+        return Err("data: atomic type must be numeric".into())
+    }
+    if let (Some(num_records_label), Some(num_records_data)) = (label_property.num_records, data_property.num_records) {
+        if num_records_label != num_records_data {
+            return Err("label and data: must be same length".into())
+        }
+    }
+    if label_property.group_id != data_property.group_id {
+        return Err("data from separate partitions may not be mixed".into())
+    }
+    if label_property.dataset_id != data_property.dataset_id {
+        return Err("label and data must share the same dataset id".into())
+    }
+    // this check should be un-necessary due to the dataset id check
+    if label_property.c_stability != data_property.c_stability {
+        return Err(Error::from("label and data must share the same stabilities"))
+    }
+
+    let num_classes = public_arguments.get::<IndexKey>(&"classes".into())
+        .ok_or_else(|| Error::from("classes: missing, must be public"))?
+        .ref_jagged().map_err(prepend("classes:"))?
+        .num_records();
+    if num_classes.len() != 1 {
+        return Err("classes: must be defined for exactly one column".into())
+    }
+    let num_classes = num_classes[0];
+    if num_classes < 1 {
+        return Err("classes: must contain at least one class".into())
+    }
+
+    let splits = public_arguments.get::<IndexKey>(&"splits".into())
+        .ok_or_else(|| Error::from("splits: missing, must be public"))?
+        .ref_jagged().map_err(prepend("splits:"))?;
+    if splits.data_type() != DataType::Float && splits.data_type() != DataType::Int {
+        return Err("splits: atomic type must be numeric".into())
+    }
+    let num_splits = splits.num_records();
+    if num_splits.len() as i64 != data_property.num_columns()? {
+        return Err("splits: must be defined for each column of data".into())
+    }
+    if num_splits.iter().sum::<i64>() == 0 {
+        return Err("splits: must contain at least one candidate threshold".into())
+    }
+
+    if max_depth == 0 || max_depth > MAX_DEPTH {
+        return Err(format!("max_depth: must be between one and {}", MAX_DEPTH).into())
+    }
+    if num_trees == 0 {
+        return Err("num_trees: must be greater than zero".into())
+    }
+
+    properties.get::<IndexKey>(&"sensitivity".into())
+        .ok_or("sensitivity: missing")?;
+
+    let privacy_usage = privacy_usage.iter().cloned().map(Ok)
+        .fold1(|l, r| l? + r?).ok_or_else(|| "privacy_usage: must be defined")??;
+
+    if get_delta(&privacy_usage)? != 0. {
+        return Err("delta: must be zero, the tree is privatized with pure differential privacy".into())
+    }
+
+    let warnings = privacy_usage_check(
+        &privacy_usage,
+        label_property.num_records,
+        privacy_definition.strict_parameter_checks)?;
+
+    let num_trees = num_trees as i64;
+    let num_splits = (1i64 << max_depth) - 1;
+    let num_leaves = 1i64 << max_depth;
+
+    let model_property = |num_records: i64, num_columns: Option<i64>, data_type: DataType| ValueProperties::Array(ArrayProperties {
+        num_records: Some(num_records),
+        num_columns: Some(num_columns.unwrap_or(1)),
+        nullity: false,
+        releasable: true,
+        c_stability: 1,
+        aggregator: None,
+        nature: None,
+        data_type,
+        dataset_id: Some(node_id as i64),
+        node_id: node_id as i64,
+        is_not_empty: true,
+        dimensionality: Some(if num_columns.is_some() { 2 } else { 1 }),
+        group_id: label_property.group_id.clone(),
+        naturally_ordered: true,
+        sample_proportion: None,
+    });
+
+    Ok(Warnable(ValueProperties::Dataframe(DataframeProperties {
+        children: indexmap![
+            "classes".into() => model_property(num_classes, None, label_property.data_type.clone()),
+            "features".into() => model_property(num_trees, Some(num_splits), DataType::Int),
+            "thresholds".into() => model_property(num_trees, Some(num_splits), DataType::Float),
+            "leaf_counts".into() => model_property(num_trees * num_leaves, Some(num_classes), DataType::Float)
+        ],
+    }), warnings))
+}
+
+/// Insert the classes and sensitivity of the tree classifiers, and retrieve the updated component.
+pub(crate) fn expand_tree(
+    privacy_definition: &proto::PrivacyDefinition,
+    component: &proto::Component,
+    label_property: &ArrayProperties,
+    mut maximum_id: u32,
+) -> Result<(base::ComponentExpansion, proto::Component)> {
+    let mut expansion = base::ComponentExpansion::default();
+    let mut tree_component = component.clone();
+
+    let mut insert_literal = |name: &str, value: Value, maximum_id: &mut u32| -> Result<()> {
+        *maximum_id += 1;
+        let id_literal = *maximum_id;
+        let (patch_node, release) = get_literal(value, component.submission)?;
+        expansion.computation_graph.insert(id_literal, patch_node);
+        expansion.properties.insert(id_literal, infer_property(&release.value, None, id_literal)?);
+        expansion.releases.insert(id_literal, release);
+        tree_component.insert_argument(&name.into(), id_literal);
+        Ok(())
+    };
+
+    if !component.arguments().contains_key::<IndexKey>(&"classes".into()) {
+        let classes = label_property.categories()
+            .map_err(|_| Error::from("classes: must be supplied when the categories of label are not known"))?;
+        insert_literal("classes", Value::Jagged(classes), &mut maximum_id)?;
+    }
+
+    // always overwrite sensitivity. This is not something a user may configure
+    use proto::privacy_definition::Neighboring;
+    let sensitivity: Float = match Neighboring::from_i32(privacy_definition.neighboring)
+        .ok_or_else(|| Error::from("neighboring definition must be either \"AddRemove\" or \"Substitute\""))? {
+        // the record is added to or removed from one node at each level
+        Neighboring::AddRemove => 1.,
+        // the record may move from one node to another at each level
+        Neighboring::Substitute => 2.
+    };
+    // shared by the split scores and the leaf counts
+    insert_literal("sensitivity", Value::from(sensitivity), &mut maximum_id)?;
+
+    Ok((expansion, tree_component))
+}
+
+/// Summarize the model released by a tree classifier.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn summarize_tree(
+    node_id: u32,
+    component: &proto::Component,
+    properties: &NodeProperties,
+    release: &Value,
+    variable_names: Option<&Vec<base::IndexKey>>,
+    statistic: &str,
+    privacy_usage: &[proto::PrivacyUsage],
+    argument: serde_json::Value,
+) -> Result<JSONRelease> {
+    let data_property = properties.get::<IndexKey>(&"data".into())
+        .ok_or("data: missing")?.array()
+        .map_err(prepend("data:"))?;
+
+    let variables = (0..data_property.num_columns()? as usize)
+        .map(|column_number| variable_names
+            .and_then(|names| names.get(column_number)).cloned()
+            .unwrap_or_else(|| "[Unknown]".into()).to_string())
+        .collect::<Vec<String>>();
+
+    Ok(JSONRelease {
+        description: "DP release information".to_string(),
+        statistic: statistic.to_string(),
+        variables: serde_json::json!(variables),
+        release_info: value_to_json(release)?,
+        privacy_loss: serde_json::json!(privacy_usage.iter().map(privacy_usage_to_json).collect::<Vec<_>>()),
+        accuracy: None,
+        submission: component.submission,
+        node_id,
+        postprocess: false,
+        algorithm_info: AlgorithmInfo {
+            name: "Exponential Mechanism Splits".to_string(),
+            cite: "Friedman and Schuster (2010), Data Mining with Differential Privacy".to_string(),
+            mechanism: "Exponential".to_string(),
+            argument,
+        },
+    })
+}
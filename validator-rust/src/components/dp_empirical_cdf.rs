@@ -0,0 +1,385 @@
+use indexmap::map::IndexMap;
+use itertools::Itertools;
+
+use crate::{base, Float, proto, Warnable};
+use crate::base::{ArrayProperties, DataType, IndexKey, Jagged, Nature, NatureContinuous, NodeProperties, SensitivitySpace, Value, ValueProperties, Vector1DNull};
+use crate::components::{Accuracy, Component, Expandable, Mechanism, Report, Sensitivity};
+use crate::errors::*;
+use crate::utilities::{expand_mechanism, prepend};
+use crate::utilities::privacy::{get_delta, get_epsilon, privacy_usage_check};
+use crate::utilities::json::{AlgorithmInfo, JSONRelease, privacy_usage_to_json, value_to_json};
+
+impl Component for proto::DpEmpiricalCdf {
+    fn propagate_property(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: NodeProperties,
+        node_id: u32,
+    ) -> Result<Warnable<ValueProperties>> {
+        let Warnable(mut data_property, warnings) = propagate_histogram_mechanism(
+            privacy_definition, &properties, &self.privacy_usage)?;
+
+        let num_edges = get_edges(&public_arguments)?.len();
+
+        data_property.num_records = Some(num_edges as i64);
+        data_property.nature = Some(Nature::Continuous(NatureContinuous {
+            lower: Vector1DNull::Float(vec![Some(0.)]),
+            upper: Vector1DNull::Float(vec![Some(1.)]),
+        }));
+        data_property.node_id = node_id as i64;
+        Ok(Warnable(data_property.into(), warnings))
+    }
+}
+
+impl Expandable for proto::DpEmpiricalCdf {
+    fn expand_component(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        component: &proto::Component,
+        _public_arguments: &IndexMap<IndexKey, &Value>,
+        properties: &base::NodeProperties,
+        component_id: u32,
+        maximum_id: u32,
+    ) -> Result<base::ComponentExpansion> {
+        expand_histogram_mechanism(
+            &SensitivitySpace::KNorm(1), privacy_definition, &self.privacy_usage,
+            component, properties, component_id, maximum_id)
+    }
+}
+
+impl Mechanism for proto::DpEmpiricalCdf {
+    fn get_privacy_usage(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        release_usage: Option<&Vec<proto::PrivacyUsage>>,
+        properties: &NodeProperties
+    ) -> Result<Option<Vec<proto::PrivacyUsage>>> {
+        histogram_mechanism_privacy_usage(privacy_definition, release_usage.unwrap_or(&self.privacy_usage), properties)
+    }
+}
+
+impl Accuracy for proto::DpEmpiricalCdf {
+    fn accuracy_to_privacy_usage(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        properties: &base::NodeProperties,
+        accuracies: &proto::Accuracies,
+        _public_arguments: IndexMap<base::IndexKey, &Value>
+    ) -> Result<Option<Vec<proto::PrivacyUsage>>> {
+        let accuracy = get_accuracy(accuracies)?;
+        let (sensitivity, num_bins, num_records) = get_histogram_parameters(privacy_definition, properties)?;
+
+        let epsilon = cdf_accuracy(sensitivity, num_bins, num_records, 1., accuracy.alpha) / accuracy.value;
+        Ok(Some(vec![epsilon_usage(epsilon)]))
+    }
+
+    fn privacy_usage_to_accuracy(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        properties: &base::NodeProperties,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        alpha: f64
+    ) -> Result<Option<Vec<proto::Accuracy>>> {
+        let (sensitivity, num_bins, num_records) = get_histogram_parameters(privacy_definition, properties)?;
+        let epsilon = get_total_epsilon(&self.privacy_usage)?;
+
+        Ok(Some(vec![proto::Accuracy {
+            value: cdf_accuracy(sensitivity, num_bins, num_records, epsilon, alpha),
+            alpha,
+        }]))
+    }
+}
+
+impl Report for proto::DpEmpiricalCdf {
+    fn summarize(
+        &self,
+        node_id: u32,
+        component: &proto::Component,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: NodeProperties,
+        release: &Value,
+        variable_names: Option<&Vec<base::IndexKey>>,
+    ) -> Result<Option<Vec<JSONRelease>>> {
+        summarize_histogram_mechanism(
+            "DPEmpiricalCDF", "Isotonic Regression of Noisy Cumulative Counts",
+            node_id, component, &public_arguments, &properties, &self.privacy_usage,
+            release, variable_names, serde_json::json!({}))
+    }
+}
+
+/// Check the properties common to the mechanisms over a histogram of one column, and return the properties of the release.
+pub(crate) fn propagate_histogram_mechanism(
+    privacy_definition: &Option<proto::PrivacyDefinition>,
+    properties: &NodeProperties,
+    privacy_usage: &[proto::PrivacyUsage],
+) -> Result<Warnable<ArrayProperties>> {
+    let privacy_definition = privacy_definition.as_ref()
+        .ok_or_else(|| "privacy_definition must be defined")?;
+
+    if privacy_definition.protect_floating_point {
+        return Err("Floating-point protections are enabled. The Laplace mechanism is susceptible to floating-point attacks.".into())
+    }
+
+    let mut data_property: ArrayProperties = properties.get::<IndexKey>(&"data".into())
+        .ok_or("data: missing")?.array()
+        .map_err(prepend("data:"))?.clone();
+
+    get_histogram_parameters(privacy_definition, properties)?;
+
+    let privacy_usage = privacy_usage.iter().cloned().map(Ok)
+        .fold1(|l, r| l? + r?).ok_or_else(|| "privacy_usage: must be defined")??;
+
+    if get_delta(&privacy_usage)? != 0. {
+        return Err("delta: must be zero, the counts are privatized with the Laplace mechanism".into())
+    }
+
+    let warnings = privacy_usage_check(
+        &privacy_usage,
+        data_property.num_records,
+        privacy_definition.strict_parameter_checks)?;
+
+    data_property.releasable = true;
+    data_property.aggregator = None;
+    data_property.data_type = DataType::Float;
+    data_property.num_columns = Some(1);
+    data_property.dimensionality = Some(1);
+    data_property.dataset_id = None;
+    Ok(Warnable(data_property, warnings))
+}
+
+/// Count the data in a histogram over the edges or categories, and then privatize the counts.
+///
+/// On the first pass, a `Histogram` is inserted in front of the component.
+/// Once the data is aggregated, the sensitivity and effective privacy usage are inserted, as for any mechanism.
+pub(crate) fn expand_histogram_mechanism(
+    sensitivity_type: &SensitivitySpace,
+    privacy_definition: &Option<proto::PrivacyDefinition>,
+    privacy_usage: &[proto::PrivacyUsage],
+    component: &proto::Component,
+    properties: &base::NodeProperties,
+    component_id: u32,
+    mut maximum_id: u32,
+) -> Result<base::ComponentExpansion> {
+    let data_property = properties.get::<IndexKey>(&"data".into())
+        .ok_or("data: missing")?.array()
+        .map_err(prepend("data:"))?;
+
+    if data_property.aggregator.is_some() {
+        return expand_mechanism(
+            sensitivity_type,
+            privacy_definition,
+            privacy_usage,
+            component,
+            properties,
+            component_id,
+            maximum_id)
+    }
+
+    data_property.assert_is_not_aggregated()?;
+    data_property.assert_non_null()?;
+
+    let mut expansion = base::ComponentExpansion::default();
+    let argument_ids = component.arguments();
+
+    if argument_ids.contains_key::<IndexKey>(&"edges".into())
+        && data_property.data_type != DataType::Float && data_property.data_type != DataType::Int {
+        return Err("data: atomic type must be numeric to bin by edges".into())
+    }
+
+    // histogram
+    maximum_id += 1;
+    let id_histogram = maximum_id;
+    let mut histogram_arguments = IndexMap::new();
+    vec!["data", "categories", "null_value", "edges", "inclusive_left"].into_iter()
+        .map(IndexKey::from)
+        .for_each(|name| {
+            argument_ids.get(&name)
+                .map(|v| histogram_arguments.insert(name, *v));
+        });
+    expansion.computation_graph.insert(id_histogram, proto::Component {
+        arguments: Some(proto::ArgumentNodeIds::new(histogram_arguments)),
+        variant: Some(proto::component::Variant::Histogram(proto::Histogram {})),
+        omit: true,
+        submission: component.submission,
+    });
+    expansion.traversal.push(id_histogram);
+
+    // the component now privatizes the histogram
+    let mut component = component.clone();
+    component.arguments = Some(proto::ArgumentNodeIds::new(argument_ids.into_iter()
+        .filter(|(name, _)| name != &IndexKey::from("null_value") && name != &IndexKey::from("inclusive_left"))
+        .map(|(name, id)| if name == IndexKey::from("data") { (name, id_histogram) } else { (name, id) })
+        .collect()));
+    expansion.computation_graph.insert(component_id, component);
+
+    Ok(expansion)
+}
+
+/// Convert the effective privacy usage of a mechanism over a histogram to the actual privacy usage.
+pub(crate) fn histogram_mechanism_privacy_usage(
+    privacy_definition: &proto::PrivacyDefinition,
+    privacy_usage: &[proto::PrivacyUsage],
+    properties: &NodeProperties,
+) -> Result<Option<Vec<proto::PrivacyUsage>>> {
+    let data_property = properties.get::<IndexKey>(&"data".into())
+        .ok_or("data: missing")?.array()
+        .map_err(prepend("data:"))?;
+
+    Some(privacy_usage.iter()
+        .map(|usage| usage.effective_to_actual(
+            data_property.sample_proportion.unwrap_or(1.),
+            data_property.c_stability,
+            privacy_definition.group_size))
+        .collect::<Result<Vec<proto::PrivacyUsage>>>()).transpose()
+}
+
+/// Sensitivity of each count, number of bins, and number of records of the histogram that the data was aggregated with.
+pub(crate) fn get_histogram_parameters(
+    privacy_definition: &proto::PrivacyDefinition,
+    properties: &NodeProperties,
+) -> Result<(Float, usize, usize)> {
+    let data_property = properties.get::<IndexKey>(&"data".into())
+        .ok_or("data: missing")?.array()
+        .map_err(prepend("data:"))?;
+
+    let aggregator = data_property.aggregator.as_ref()
+        .ok_or_else(|| Error::from("data: must be aggregated with a Histogram"))?;
+    if !matches!(aggregator.component, proto::component::Variant::Histogram(_)) {
+        return Err("data: must be aggregated with a Histogram".into())
+    }
+
+    let sensitivity = aggregator.component.compute_sensitivity(
+        privacy_definition,
+        &aggregator.properties,
+        &SensitivitySpace::KNorm(1))?.array()?.float()?
+        .iter().cloned().fold(0., Float::max);
+
+    let (num_bins, num_records) = get_histogram_size(properties)?;
+    Ok((sensitivity, num_bins, num_records))
+}
+
+/// Number of bins and number of records of the histogram that the data was aggregated with.
+///
+/// The final count of the histogram is of the records outside the edges, and is not a bin.
+fn get_histogram_size(properties: &NodeProperties) -> Result<(usize, usize)> {
+    let data_property = properties.get::<IndexKey>(&"data".into())
+        .ok_or("data: missing")?.array()
+        .map_err(prepend("data:"))?;
+    let aggregator = data_property.aggregator.as_ref()
+        .ok_or_else(|| Error::from("data: must be aggregated with a Histogram"))?;
+
+    let histogram_data_property = aggregator.properties.get::<IndexKey>(&"data".into())
+        .ok_or("data: missing")?.array()
+        .map_err(prepend("data:"))?;
+    if histogram_data_property.num_columns()? != 1 {
+        return Err("data: must contain one column".into())
+    }
+    let num_records = histogram_data_property.num_records()
+        .map_err(|_| Error::from("data: the number of records must be known. Consider resizing the data"))?;
+    let num_bins = (data_property.num_records()? as usize).checked_sub(1)
+        .filter(|num_bins| *num_bins > 0)
+        .ok_or_else(|| Error::from("edges: must define at least one bin"))?;
+
+    Ok((num_bins, num_records as usize))
+}
+
+/// The public edges of one column, as floats.
+pub(crate) fn get_edges(public_arguments: &IndexMap<IndexKey, &Value>) -> Result<Vec<Float>> {
+    let edges = match public_arguments.get::<IndexKey>(&"edges".into())
+        .ok_or_else(|| Error::from("edges: missing, must be public"))?.ref_jagged()? {
+        Jagged::Float(edges) => edges.clone(),
+        Jagged::Int(edges) => edges.iter()
+            .map(|column| column.iter().map(|v| *v as Float).collect())
+            .collect(),
+        _ => return Err("edges: must be numeric".into())
+    };
+    if edges.len() != 1 {
+        return Err("edges: must contain one column".into())
+    }
+    Ok(edges.into_iter().next().unwrap())
+}
+
+/// The single accuracy that a release over a histogram is held to.
+pub(crate) fn get_accuracy(accuracies: &proto::Accuracies) -> Result<&proto::Accuracy> {
+    if accuracies.values.len() != 1 {
+        return Err("accuracies: must contain exactly one accuracy".into())
+    }
+    Ok(&accuracies.values[0])
+}
+
+/// Total epsilon of the privacy usages.
+pub(crate) fn get_total_epsilon(privacy_usage: &[proto::PrivacyUsage]) -> Result<Float> {
+    privacy_usage.iter().map(get_epsilon).sum()
+}
+
+pub(crate) fn epsilon_usage(epsilon: Float) -> proto::PrivacyUsage {
+    proto::PrivacyUsage {
+        distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+            epsilon,
+            delta: 0.,
+        }))
+    }
+}
+
+/// Bound on the Laplace noise in every count, that holds with probability `1 - alpha`.
+///
+/// By a union bound over the `num_bins` counts.
+pub(crate) fn max_noise(sensitivity: Float, num_bins: usize, epsilon: Float, alpha: Float) -> Float {
+    sensitivity / epsilon * (num_bins as Float / alpha).ln()
+}
+
+/// Bound on the sup-norm error of the CDF, that holds with probability `1 - alpha`.
+///
+/// The error of each cumulative count is at most `num_bins` times the bound on the noise in each count.
+/// Isotonic regression onto the non-decreasing sequences, and clamping to `[0, 1]`,
+/// do not increase the sup-norm distance to the CDF without noise, which is itself non-decreasing within `[0, 1]`.
+pub fn cdf_accuracy(sensitivity: Float, num_bins: usize, num_records: usize, epsilon: Float, alpha: Float) -> Float {
+    num_bins as Float * max_noise(sensitivity, num_bins, epsilon, alpha) / num_records as Float
+}
+
+/// Summarize a release over a histogram.
+///
+/// Reports are generated from the graph before expansion, so the properties are of the data before aggregation.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn summarize_histogram_mechanism(
+    statistic: &str,
+    name: &str,
+    node_id: u32,
+    component: &proto::Component,
+    public_arguments: &IndexMap<IndexKey, &Value>,
+    properties: &NodeProperties,
+    privacy_usage: &[proto::PrivacyUsage],
+    release: &Value,
+    variable_names: Option<&Vec<base::IndexKey>>,
+    mut argument: serde_json::Value,
+) -> Result<Option<Vec<JSONRelease>>> {
+    let data_property = properties.get::<IndexKey>(&"data".into())
+        .ok_or("data: missing")?.array()
+        .map_err(prepend("data:"))?;
+
+    let variable_name = variable_names
+        .and_then(|names| names.first()).cloned()
+        .unwrap_or_else(|| "[Unknown]".into()).to_string();
+
+    argument["n"] = serde_json::json!(data_property.num_records()?);
+    argument["edges"] = serde_json::json!(get_edges(public_arguments)?);
+
+    Ok(Some(vec![JSONRelease {
+        description: "DP release information".to_string(),
+        statistic: statistic.to_string(),
+        variables: serde_json::json!(variable_name),
+        release_info: value_to_json(release)?,
+        privacy_loss: serde_json::json!(privacy_usage.iter().map(privacy_usage_to_json).collect::<Vec<_>>()),
+        accuracy: None,
+        submission: component.submission,
+        node_id,
+        postprocess: false,
+        algorithm_info: AlgorithmInfo {
+            name: name.to_string(),
+            cite: "".to_string(),
+            mechanism: "Laplace".to_string(),
+            argument,
+        },
+    }]))
+}
@@ -0,0 +1,173 @@
+use indexmap::map::IndexMap;
+use itertools::Itertools;
+
+use crate::{base, proto, Warnable};
+use crate::base::{ArrayProperties, IndexKey, NodeProperties, Value, ValueProperties};
+use crate::components::{Component, Expandable, Mechanism, Report};
+use crate::components::dp_chi_square_test::{check_table_test, expand_table_test, resolve_mechanism, test_output_property};
+use crate::errors::*;
+use crate::utilities::prepend;
+use crate::utilities::privacy::privacy_usage_check;
+use crate::utilities::json::{AlgorithmInfo, JSONRelease, privacy_usage_to_json, value_to_json};
+
+impl Component for proto::DpGoodnessOfFit {
+    fn propagate_property(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: NodeProperties,
+        node_id: u32,
+    ) -> Result<Warnable<ValueProperties>> {
+        let privacy_definition = privacy_definition.as_ref()
+            .ok_or_else(|| "privacy_definition must be defined")?;
+
+        let data_property: ArrayProperties = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        data_property.assert_is_not_aggregated()?;
+
+        if data_property.num_columns()? != 1 {
+            return Err("data: must contain one column".into())
+        }
+
+        let num_categories = public_arguments.get::<IndexKey>(&"categories".into())
+            .ok_or_else(|| Error::from("categories: missing, must be public"))?
+            .ref_jagged().map_err(prepend("categories:"))?
+            .num_records();
+        if num_categories.len() != 1 {
+            return Err("categories: must be defined for one column".into())
+        }
+        if num_categories[0] < 2 {
+            return Err("categories: must contain at least two categories".into())
+        }
+
+        let expected = public_arguments.get::<IndexKey>(&"expected".into())
+            .ok_or_else(|| Error::from("expected: missing, must be public"))?
+            .ref_array()?.ref_float().map_err(prepend("expected:"))?;
+        if expected.len() as i64 != num_categories[0] {
+            return Err("expected: must contain one proportion for each category".into())
+        }
+        if expected.iter().any(|v| !v.is_finite() || *v < 0.) {
+            return Err("expected: proportions must be finite and non-negative".into())
+        }
+        if expected.iter().all(|v| *v == 0.) {
+            return Err("expected: at least one proportion must be positive".into())
+        }
+
+        if self.num_simulations == 0 {
+            return Err("num_simulations: must be greater than zero".into())
+        }
+
+        let privacy_usage = self.privacy_usage.iter().cloned().map(Ok)
+            .fold1(|l, r| l? + r?).ok_or_else(|| "privacy_usage: must be defined")??;
+
+        check_table_test(privacy_definition, &properties, &self.mechanism, &privacy_usage)?;
+
+        let warnings = privacy_usage_check(
+            &privacy_usage,
+            data_property.num_records,
+            privacy_definition.strict_parameter_checks)?;
+
+        Ok(Warnable(test_output_property(&data_property, node_id).into(), warnings))
+    }
+}
+
+impl Expandable for proto::DpGoodnessOfFit {
+    fn expand_component(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        component: &proto::Component,
+        _public_arguments: &IndexMap<IndexKey, &Value>,
+        properties: &base::NodeProperties,
+        component_id: u32,
+        maximum_id: u32,
+    ) -> Result<base::ComponentExpansion> {
+        let privacy_definition = privacy_definition.as_ref()
+            .ok_or_else(|| "privacy definition must be defined")?;
+
+        if self.privacy_usage.len() != 1 {
+            return Err(Error::from("privacy usage must be of length one"));
+        }
+
+        let data_property: ArrayProperties = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        let mechanism = resolve_mechanism(&self.mechanism);
+        let (mut expansion, mut test_component) = expand_table_test(
+            privacy_definition, component, &data_property, &mechanism, maximum_id)?;
+
+        // update the mechanism and privacy usage
+        if let Some(proto::component::Variant::DpGoodnessOfFit(variant)) = &mut test_component.variant {
+            variant.mechanism = mechanism;
+            variant.privacy_usage = vec![self.privacy_usage[0].actual_to_effective(
+                data_property.sample_proportion.unwrap_or(1.),
+                data_property.c_stability,
+                privacy_definition.group_size)?];
+            // this case should never happen
+        } else { return Err(Error::from("Variant must be defined")) }
+        expansion.computation_graph.insert(component_id, test_component);
+
+        Ok(expansion)
+    }
+}
+
+impl Mechanism for proto::DpGoodnessOfFit {
+    fn get_privacy_usage(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        release_usage: Option<&Vec<proto::PrivacyUsage>>,
+        properties: &NodeProperties
+    ) -> Result<Option<Vec<proto::PrivacyUsage>>> {
+        let data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?;
+
+        Some(release_usage.unwrap_or_else(|| &self.privacy_usage).iter()
+            .map(|usage| usage.effective_to_actual(
+                data_property.sample_proportion.unwrap_or(1.),
+                data_property.c_stability,
+                privacy_definition.group_size))
+            .collect::<Result<Vec<proto::PrivacyUsage>>>()).transpose()
+    }
+}
+
+impl Report for proto::DpGoodnessOfFit {
+    fn summarize(
+        &self,
+        node_id: u32,
+        component: &proto::Component,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
+        _properties: NodeProperties,
+        release: &Value,
+        variable_names: Option<&Vec<base::IndexKey>>,
+    ) -> Result<Option<Vec<JSONRelease>>> {
+        let expected = public_arguments.get::<IndexKey>(&"expected".into())
+            .ok_or_else(|| Error::from("expected: missing, must be public"))?;
+
+        let release = JSONRelease {
+            description: "DP release information".to_string(),
+            statistic: "DPGoodnessOfFit".to_string(),
+            variables: serde_json::json!(variable_names.cloned()
+                .unwrap_or_else(Vec::new).iter()
+                .map(|v| v.to_string()).collect::<Vec<String>>()),
+            release_info: value_to_json(release)?,
+            privacy_loss: serde_json::json!(self.privacy_usage.iter().map(privacy_usage_to_json).collect::<Vec<_>>()),
+            accuracy: None,
+            submission: component.submission,
+            node_id,
+            postprocess: false,
+            algorithm_info: AlgorithmInfo {
+                name: "Monte Carlo Goodness of Fit Test".to_string(),
+                cite: "Gaboardi, Lim, Rogers and Vadhan (2016), Differentially Private Chi-Squared Hypothesis Testing: Goodness of Fit and Independence Testing".to_string(),
+                mechanism: self.mechanism.clone(),
+                argument: serde_json::json!({
+                    "expected": value_to_json(expected)?,
+                    "num_simulations": self.num_simulations
+                }),
+            },
+        };
+        Ok(Some(vec![release]))
+    }
+}
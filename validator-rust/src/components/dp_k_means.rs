@@ -0,0 +1,215 @@
+use indexmap::map::IndexMap;
+use itertools::Itertools;
+use ndarray::Ix1;
+
+use crate::{base, Float, proto, Warnable};
+use crate::base::{ArrayProperties, DataType, IndexKey, Nature, NatureContinuous, NodeProperties, Value, ValueProperties, Vector1DNull};
+use crate::components::{Component, Expandable, Mechanism, Report};
+use crate::errors::*;
+use crate::utilities::{prepend, standardize_numeric_argument};
+use crate::utilities::privacy::{get_delta, privacy_usage_check};
+use crate::utilities::json::{AlgorithmInfo, JSONRelease, privacy_usage_to_json, value_to_json};
+
+impl Component for proto::DpkMeans {
+    fn propagate_property(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: NodeProperties,
+        node_id: u32,
+    ) -> Result<Warnable<ValueProperties>> {
+        let privacy_definition = privacy_definition.as_ref()
+            .ok_or_else(|| "privacy_definition must be defined")?;
+
+        if privacy_definition.protect_floating_point {
+            return Err("Floating-point protections are enabled. The noisy sums in k-means are susceptible to floating-point attacks.".into())
+        }
+
+        let data_property: ArrayProperties = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        data_property.assert_is_not_aggregated()?;
+        data_property.assert_non_null()?;
+        data_property.assert_is_not_empty()?;
+
+        if data_property.data_type != DataType::Float {
+            return Err("data: atomic type must be float".into());
+        }
+
+        let num_columns = data_property.num_columns()?;
+        let (lower, upper) = get_bounding_box(&public_arguments, num_columns)?;
+
+        if self.num_clusters == 0 {
+            return Err("num_clusters: must be greater than zero".into())
+        }
+        if self.num_iterations == 0 {
+            return Err("num_iterations: must be greater than zero".into())
+        }
+
+        let privacy_usage = self.privacy_usage.iter().cloned().map(Ok)
+            .fold1(|l, r| l? + r?).ok_or_else(|| "privacy_usage: must be defined")??;
+
+        if get_delta(&privacy_usage)? != 0. {
+            return Err("delta: must be zero, the counts and sums are privatized with the Laplace mechanism".into())
+        }
+
+        let warnings = privacy_usage_check(
+            &privacy_usage,
+            data_property.num_records,
+            privacy_definition.strict_parameter_checks)?;
+
+        Ok(Warnable(ValueProperties::Array(ArrayProperties {
+            num_records: Some(self.num_clusters as i64),
+            num_columns: Some(num_columns),
+            nullity: false,
+            releasable: true,
+            c_stability: 1,
+            aggregator: None,
+            // centroids are clamped to the bounding box
+            nature: Some(Nature::Continuous(NatureContinuous {
+                lower: Vector1DNull::Float(lower.into_iter().map(Some).collect()),
+                upper: Vector1DNull::Float(upper.into_iter().map(Some).collect()),
+            })),
+            data_type: DataType::Float,
+            dataset_id: None,
+            node_id: node_id as i64,
+            is_not_empty: true,
+            dimensionality: Some(2),
+            group_id: data_property.group_id,
+            naturally_ordered: true,
+            sample_proportion: None,
+        }), warnings))
+    }
+}
+
+impl Expandable for proto::DpkMeans {
+    fn expand_component(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        component: &proto::Component,
+        _public_arguments: &IndexMap<IndexKey, &Value>,
+        properties: &base::NodeProperties,
+        component_id: u32,
+        _maximum_id: u32,
+    ) -> Result<base::ComponentExpansion> {
+        let mut expansion = base::ComponentExpansion::default();
+
+        let data_property: ArrayProperties = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        let privacy_definition = privacy_definition.as_ref()
+            .ok_or_else(|| "privacy definition must be defined")?;
+
+        if self.privacy_usage.len() != 1 {
+            return Err(Error::from("privacy usage must be of length one"));
+        }
+
+        // update the privacy usage
+        let mut updated_component = component.clone();
+        if let Some(proto::component::Variant::DpKMeans(variant)) = &mut updated_component.variant {
+            variant.privacy_usage = vec![self.privacy_usage[0].actual_to_effective(
+                data_property.sample_proportion.unwrap_or(1.),
+                data_property.c_stability,
+                privacy_definition.group_size)?];
+            // this case should never happen
+        } else { return Err(Error::from("Variant must be defined")) }
+        expansion.computation_graph.insert(component_id, updated_component);
+
+        Ok(expansion)
+    }
+}
+
+impl Mechanism for proto::DpkMeans {
+    fn get_privacy_usage(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        release_usage: Option<&Vec<proto::PrivacyUsage>>,
+        properties: &NodeProperties
+    ) -> Result<Option<Vec<proto::PrivacyUsage>>> {
+        let data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?;
+
+        Some(release_usage.unwrap_or_else(|| &self.privacy_usage).iter()
+            .map(|usage| usage.effective_to_actual(
+                data_property.sample_proportion.unwrap_or(1.),
+                data_property.c_stability,
+                privacy_definition.group_size))
+            .collect::<Result<Vec<proto::PrivacyUsage>>>()).transpose()
+    }
+}
+
+impl Report for proto::DpkMeans {
+    fn summarize(
+        &self,
+        node_id: u32,
+        component: &proto::Component,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: NodeProperties,
+        release: &Value,
+        variable_names: Option<&Vec<base::IndexKey>>,
+    ) -> Result<Option<Vec<JSONRelease>>> {
+        let data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        let num_columns = data_property.num_columns()?;
+        let (lower, upper) = get_bounding_box(&public_arguments, num_columns)?;
+
+        let variables = (0..num_columns as usize)
+            .map(|column_number| variable_names
+                .and_then(|names| names.get(column_number)).cloned()
+                .unwrap_or_else(|| "[Unknown]".into()).to_string())
+            .collect::<Vec<String>>();
+
+        let release = JSONRelease {
+            description: "DP release information".to_string(),
+            statistic: "DPKMeans".to_string(),
+            variables: serde_json::json!(variables),
+            release_info: value_to_json(release)?,
+            privacy_loss: serde_json::json!(self.privacy_usage.iter().map(privacy_usage_to_json).collect::<Vec<_>>()),
+            accuracy: None,
+            submission: component.submission,
+            node_id,
+            postprocess: false,
+            algorithm_info: AlgorithmInfo {
+                name: "Lloyd's Algorithm".to_string(),
+                cite: "Blum, Dwork, McSherry and Nissim (2005), Practical privacy: the SuLQ framework".to_string(),
+                mechanism: "laplace".into(),
+                argument: serde_json::json!({
+                    "num_clusters": self.num_clusters,
+                    "num_iterations": self.num_iterations,
+                    "constraint": {
+                        "lowerbound": lower,
+                        "upperbound": upper
+                    }
+                }),
+            },
+        };
+        Ok(Some(vec![release]))
+    }
+}
+
+/// Retrieve the public bounding box, broadcast to the number of columns.
+fn get_bounding_box(public_arguments: &IndexMap<IndexKey, &Value>, num_columns: i64) -> Result<(Vec<Float>, Vec<Float>)> {
+    let get_bound = |name: &str| -> Result<Vec<Float>> {
+        Ok(standardize_numeric_argument(
+            public_arguments.get::<IndexKey>(&name.into())
+                .ok_or_else(|| Error::from(format!("{}: missing, must be public", name)))?
+                .ref_array()?.ref_float().map_err(prepend(&format!("{}:", name)))?.clone(),
+            num_columns).map_err(prepend(&format!("{}:", name)))?
+            .into_dimensionality::<Ix1>()?.to_vec())
+    };
+    let lower = get_bound("lower")?;
+    let upper = get_bound("upper")?;
+
+    if !lower.iter().chain(upper.iter()).all(|v| v.is_finite()) {
+        return Err("lower and upper: must be finite".into())
+    }
+    if lower.iter().zip(upper.iter()).any(|(l, u)| l > u) {
+        return Err("lower: must not be greater than upper".into())
+    }
+    Ok((lower, upper))
+}
@@ -0,0 +1,165 @@
+use indexmap::map::IndexMap;
+
+use crate::{base, Float, proto, Warnable};
+use crate::base::{Array, IndexKey, Nature, NatureContinuous, NodeProperties, SensitivitySpace, Value, ValueProperties, Vector1DNull};
+use crate::components::{Accuracy, Component, Expandable, Mechanism, Report};
+use crate::components::dp_empirical_cdf::{
+    epsilon_usage, expand_histogram_mechanism, get_accuracy, get_edges, get_histogram_parameters,
+    get_total_epsilon, histogram_mechanism_privacy_usage, max_noise, propagate_histogram_mechanism,
+    summarize_histogram_mechanism};
+use crate::errors::*;
+use crate::utilities::json::JSONRelease;
+
+impl Component for proto::DpKernelDensity {
+    fn propagate_property(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: NodeProperties,
+        node_id: u32,
+    ) -> Result<Warnable<ValueProperties>> {
+        let Warnable(mut data_property, warnings) = propagate_histogram_mechanism(
+            privacy_definition, &properties, &self.privacy_usage)?;
+
+        if self.bandwidth <= 0. {
+            return Err("bandwidth: must be greater than zero".into())
+        }
+        get_edges(&public_arguments)?;
+        let num_points = get_grid(&public_arguments)?.len();
+
+        data_property.num_records = Some(num_points as i64);
+        data_property.nature = Some(Nature::Continuous(NatureContinuous {
+            lower: Vector1DNull::Float(vec![Some(0.)]),
+            upper: Vector1DNull::Float(vec![None]),
+        }));
+        data_property.node_id = node_id as i64;
+        Ok(Warnable(data_property.into(), warnings))
+    }
+}
+
+impl Expandable for proto::DpKernelDensity {
+    fn expand_component(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        component: &proto::Component,
+        _public_arguments: &IndexMap<IndexKey, &Value>,
+        properties: &base::NodeProperties,
+        component_id: u32,
+        maximum_id: u32,
+    ) -> Result<base::ComponentExpansion> {
+        expand_histogram_mechanism(
+            &SensitivitySpace::KNorm(1), privacy_definition, &self.privacy_usage,
+            component, properties, component_id, maximum_id)
+    }
+}
+
+impl Mechanism for proto::DpKernelDensity {
+    fn get_privacy_usage(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        release_usage: Option<&Vec<proto::PrivacyUsage>>,
+        properties: &NodeProperties
+    ) -> Result<Option<Vec<proto::PrivacyUsage>>> {
+        histogram_mechanism_privacy_usage(privacy_definition, release_usage.unwrap_or(&self.privacy_usage), properties)
+    }
+}
+
+impl Accuracy for proto::DpKernelDensity {
+    fn accuracy_to_privacy_usage(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        properties: &base::NodeProperties,
+        accuracies: &proto::Accuracies,
+        public_arguments: IndexMap<base::IndexKey, &Value>
+    ) -> Result<Option<Vec<proto::PrivacyUsage>>> {
+        let accuracy = get_accuracy(accuracies)?;
+        let epsilon = self.accuracy(privacy_definition, properties, &public_arguments, 1., accuracy.alpha)?
+            / accuracy.value;
+        Ok(Some(vec![epsilon_usage(epsilon)]))
+    }
+
+    fn privacy_usage_to_accuracy(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        properties: &base::NodeProperties,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
+        alpha: f64
+    ) -> Result<Option<Vec<proto::Accuracy>>> {
+        let epsilon = get_total_epsilon(&self.privacy_usage)?;
+        Ok(Some(vec![proto::Accuracy {
+            value: self.accuracy(privacy_definition, properties, &public_arguments, epsilon, alpha)?,
+            alpha,
+        }]))
+    }
+}
+
+impl Report for proto::DpKernelDensity {
+    fn summarize(
+        &self,
+        node_id: u32,
+        component: &proto::Component,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: NodeProperties,
+        release: &Value,
+        variable_names: Option<&Vec<base::IndexKey>>,
+    ) -> Result<Option<Vec<JSONRelease>>> {
+        summarize_histogram_mechanism(
+            "DPKernelDensity", "Gaussian Kernel over Noisy Binned Counts",
+            node_id, component, &public_arguments, &properties, &self.privacy_usage,
+            release, variable_names, serde_json::json!({"bandwidth": self.bandwidth}))
+    }
+}
+
+impl proto::DpKernelDensity {
+    fn accuracy(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        properties: &NodeProperties,
+        public_arguments: &IndexMap<IndexKey, &Value>,
+        epsilon: Float,
+        alpha: Float,
+    ) -> Result<Float> {
+        let (sensitivity, num_bins, num_records) = get_histogram_parameters(privacy_definition, properties)?;
+        let mass = kernel_mass(&get_edges(public_arguments)?, &get_grid(public_arguments)?, self.bandwidth);
+        Ok(kernel_density_accuracy(sensitivity, num_bins, num_records, mass, epsilon, alpha))
+    }
+}
+
+/// The public grid of one column, as floats.
+fn get_grid(public_arguments: &IndexMap<IndexKey, &Value>) -> Result<Vec<Float>> {
+    let grid = match public_arguments.get::<IndexKey>(&"grid".into())
+        .ok_or_else(|| Error::from("grid: missing, must be public"))?.ref_array()? {
+        Array::Float(grid) => grid.iter().cloned().collect::<Vec<Float>>(),
+        Array::Int(grid) => grid.iter().map(|v| *v as Float).collect(),
+        _ => return Err("grid: must be numeric".into())
+    };
+    if grid.is_empty() {
+        return Err("grid: must contain at least one point".into())
+    }
+    Ok(grid)
+}
+
+/// Largest total weight that the kernels centered on the midpoints of the bins place on any point of the grid.
+pub fn kernel_mass(edges: &[Float], grid: &[Float], bandwidth: Float) -> Float {
+    let midpoints = edges.windows(2).map(|w| (w[0] + w[1]) / 2.).collect::<Vec<Float>>();
+    grid.iter()
+        .map(|point| midpoints.iter()
+            .map(|midpoint| gaussian_kernel(point - midpoint, bandwidth))
+            .sum::<Float>())
+        .fold(0., Float::max)
+}
+
+/// Density of a normal distribution with mean zero and standard deviation `bandwidth`.
+pub fn gaussian_kernel(distance: Float, bandwidth: Float) -> Float {
+    (-0.5 * (distance / bandwidth).powi(2)).exp() / (bandwidth * (2. * std::f64::consts::PI).sqrt())
+}
+
+/// Bound on the sup-norm error of the density over the grid, that holds with probability `1 - alpha`.
+///
+/// The error at each point is the noise in each count, weighted by the kernel of its bin.
+/// Flooring the counts at zero does not increase the noise in any count, as the counts without noise are non-negative.
+pub fn kernel_density_accuracy(
+    sensitivity: Float, num_bins: usize, num_records: usize, kernel_mass: Float, epsilon: Float, alpha: Float,
+) -> Float {
+    max_noise(sensitivity, num_bins, epsilon, alpha) * kernel_mass / num_records as Float
+}
@@ -12,27 +12,48 @@ use crate::utilities::privacy::spread_privacy_usage;
 impl Expandable for proto::DpLinearRegression {
     fn expand_component(
         &self,
-        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
         component: &proto::Component,
         public_arguments: &IndexMap<IndexKey, &Value>,
-        _properties: &base::NodeProperties,
+        properties: &base::NodeProperties,
         component_id: u32,
         mut maximum_id: u32,
     ) -> Result<base::ComponentExpansion> {
         const DEFAULT_K: u32 = 100;
 
-        let mut privacy_usages = spread_privacy_usage(&self.privacy_usage, 2)?;
-        let slope_privacy_usage = privacy_usages.remove(0);
-        let intercept_privacy_usage = privacy_usages.remove(0);
-
-        let mut expansion = base::ComponentExpansion::default();
-
         let get_id = |name: &str| -> Result<u32> {
             component.arguments().get::<base::IndexKey>(&name.into())
                 .ok_or_else(|| Error::from(format!("{} must be provided as an argument", name)))
                 .map(|v| *v)
         };
 
+        if self.implementation.to_lowercase().as_str() == "sufficient-statistics" {
+            // the noisy sufficient statistics are released by a single mechanism
+            let variant = proto::LinregNoisyStats {
+                mechanism: self.mechanism.clone(),
+                regularization: self.regularization,
+                privacy_usage: self.privacy_usage.clone(),
+            };
+            let noise_component = proto::Component {
+                arguments: Some(proto::ArgumentNodeIds::new(indexmap![
+                    "data_x".into() => get_id("data_x")?,
+                    "data_y".into() => get_id("data_y")?
+                ])),
+                variant: Some(proto::component::Variant::LinregNoisyStats(variant.clone())),
+                omit: component.omit,
+                submission: component.submission,
+            };
+            return variant.expand_component(
+                privacy_definition, &noise_component, public_arguments,
+                properties, component_id, maximum_id)
+        }
+
+        let mut privacy_usages = spread_privacy_usage(&self.privacy_usage, 2)?;
+        let slope_privacy_usage = privacy_usages.remove(0);
+        let intercept_privacy_usage = privacy_usages.remove(0);
+
+        let mut expansion = base::ComponentExpansion::default();
+
         let id_data_x = get_id("data_x")?;
         let id_data_y = get_id("data_y")?;
         let id_lower_slope = get_id("lower_slope")?;
@@ -0,0 +1,281 @@
+use indexmap::map::IndexMap;
+use itertools::Itertools;
+
+use crate::{base, proto, Warnable};
+use crate::base::{ArrayProperties, DataType, IndexKey, NodeProperties, Value, ValueProperties};
+use crate::components::{Component, Expandable, Mechanism, Named, Report};
+use crate::errors::*;
+use crate::utilities::prepend;
+use crate::utilities::privacy::{get_delta, get_epsilon, privacy_usage_check};
+use crate::utilities::json::{Accuracy, AlgorithmInfo, JSONRelease, privacy_usage_to_json, value_to_json};
+use statrs::function::gamma::gamma_lr;
+
+impl Component for proto::DpLogisticRegression {
+    fn propagate_property(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: NodeProperties,
+        node_id: u32,
+    ) -> Result<Warnable<ValueProperties>> {
+        let privacy_definition = privacy_definition.as_ref()
+            .ok_or_else(|| "privacy_definition must be defined")?;
+
+        if privacy_definition.protect_floating_point {
+            return Err("Floating-point protections are enabled. Perturbation of the logistic regression is susceptible to floating-point attacks.".into())
+        }
+
+        let data_property_x: ArrayProperties = properties.get::<IndexKey>(&"data_x".into())
+            .ok_or("data_x: missing")?.array()
+            .map_err(prepend("data_x:"))?.clone();
+
+        let data_property_y: ArrayProperties = properties.get::<IndexKey>(&"data_y".into())
+            .ok_or("data_y: missing")?.array()
+            .map_err(prepend("data_y:"))?.clone();
+
+        data_property_x.assert_is_not_aggregated()?;
+        data_property_y.assert_is_not_aggregated()?;
+        data_property_x.assert_non_null()?;
+        data_property_y.assert_non_null()?;
+        data_property_x.assert_is_not_empty()?;
+
+        if data_property_x.data_type != DataType::Float {
+            return Err("data_x: atomic type must be float".into());
+        }
+        if data_property_y.data_type != DataType::Bool {
+            return Err("data_y: atomic type must be boolean".into());
+        }
+        if data_property_y.num_columns()? != 1 {
+            return Err("data_y: must have exactly one column".into());
+        }
+
+        // the sensitivity analysis of both implementations depends on a public number of records
+        let num_records = data_property_x.num_records()?;
+        if Some(num_records) != data_property_y.num_records {
+            return Err("data_x and data_y: must be same length".into());
+        }
+
+        if data_property_x.group_id != data_property_y.group_id {
+            return Err("data from separate partitions may not be mixed".into())
+        }
+        if data_property_x.dataset_id != data_property_y.dataset_id {
+            return Err("data_x and data_y must share the same dataset id".into())
+        }
+        // this check should be un-necessary due to the dataset id check
+        if data_property_x.c_stability != data_property_y.c_stability {
+            return Err(Error::from("data_x and data_y must share the same stabilities"))
+        }
+
+        match self.implementation.to_lowercase().as_str() {
+            "objective-perturbation" | "output-perturbation" => (),
+            _ => bail!("`{}` is not recognized as a valid implementation. Must be one of [`objective-perturbation`, `output-perturbation`]", self.implementation)
+        }
+
+        if self.regularization <= 0. {
+            return Err("regularization: must be greater than zero".into())
+        }
+        if self.clip_norm <= 0. {
+            return Err("clip_norm: must be greater than zero".into())
+        }
+        if !(0. < self.alpha && self.alpha < 1.) {
+            return Err("alpha: must be in (0, 1)".into())
+        }
+
+        let privacy_usage = self.privacy_usage.iter().cloned().map(Ok)
+            .fold1(|l, r| l? + r?).ok_or_else(|| "privacy_usage: must be defined")??;
+
+        if get_delta(&privacy_usage)? != 0. {
+            return Err("delta: must be zero, both implementations satisfy pure differential privacy".into())
+        }
+
+        let warnings = privacy_usage_check(
+            &privacy_usage,
+            Some(num_records),
+            privacy_definition.strict_parameter_checks)?;
+
+        Ok(Warnable(ValueProperties::Array(ArrayProperties {
+            num_records: Some(1),
+            num_columns: Some(data_property_x.num_columns()?),
+            nullity: false,
+            releasable: true,
+            c_stability: 1,
+            aggregator: None,
+            nature: None,
+            data_type: DataType::Float,
+            dataset_id: None,
+            node_id: node_id as i64,
+            is_not_empty: true,
+            dimensionality: Some(2),
+            group_id: data_property_x.group_id,
+            naturally_ordered: true,
+            sample_proportion: None,
+        }), warnings))
+    }
+}
+
+impl Expandable for proto::DpLogisticRegression {
+    fn expand_component(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        component: &proto::Component,
+        _public_arguments: &IndexMap<IndexKey, &Value>,
+        properties: &base::NodeProperties,
+        component_id: u32,
+        _maximum_id: u32,
+    ) -> Result<base::ComponentExpansion> {
+        let mut expansion = base::ComponentExpansion::default();
+
+        let data_property_x: ArrayProperties = properties.get::<IndexKey>(&"data_x".into())
+            .ok_or("data_x: missing")?.array()
+            .map_err(prepend("data_x:"))?.clone();
+
+        let privacy_definition = privacy_definition.as_ref()
+            .ok_or_else(|| "privacy definition must be defined")?;
+
+        if self.privacy_usage.len() != 1 {
+            return Err(Error::from("privacy usage must be of length one"));
+        }
+
+        // update the privacy usage
+        let mut updated_component = component.clone();
+        if let Some(proto::component::Variant::DpLogisticRegression(variant)) = &mut updated_component.variant {
+            variant.privacy_usage = vec![self.privacy_usage[0].actual_to_effective(
+                data_property_x.sample_proportion.unwrap_or(1.),
+                data_property_x.c_stability,
+                privacy_definition.group_size)?];
+            // this case should never happen
+        } else { return Err(Error::from("Variant must be defined")) }
+        expansion.computation_graph.insert(component_id, updated_component);
+
+        Ok(expansion)
+    }
+}
+
+impl Mechanism for proto::DpLogisticRegression {
+    fn get_privacy_usage(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        release_usage: Option<&Vec<proto::PrivacyUsage>>,
+        properties: &NodeProperties
+    ) -> Result<Option<Vec<proto::PrivacyUsage>>> {
+        let data_property_x = properties.get::<IndexKey>(&"data_x".into())
+            .ok_or("data_x: missing")?.array()
+            .map_err(prepend("data_x:"))?;
+
+        Some(release_usage.unwrap_or_else(|| &self.privacy_usage).iter()
+            .map(|usage| usage.effective_to_actual(
+                data_property_x.sample_proportion.unwrap_or(1.),
+                data_property_x.c_stability,
+                privacy_definition.group_size))
+            .collect::<Result<Vec<proto::PrivacyUsage>>>()).transpose()
+    }
+}
+
+impl Named for proto::DpLogisticRegression {
+    fn get_names(
+        &self,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        argument_variables: IndexMap<base::IndexKey, Vec<IndexKey>>,
+        _release: Option<&Value>
+    ) -> Result<Vec<IndexKey>> {
+        // one coefficient is released for each predictor
+        argument_variables.get(&IndexKey::from("data_x")).cloned()
+            .ok_or_else(|| "data_x: variable names are missing".into())
+    }
+}
+
+impl Report for proto::DpLogisticRegression {
+    fn summarize(
+        &self,
+        node_id: u32,
+        component: &proto::Component,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: NodeProperties,
+        release: &Value,
+        variable_names: Option<&Vec<base::IndexKey>>,
+    ) -> Result<Option<Vec<JSONRelease>>> {
+        let data_property_x = properties.get::<IndexKey>(&"data_x".into())
+            .ok_or("data_x: missing")?.array()
+            .map_err(prepend("data_x:"))?.clone();
+
+        let variables = (0..data_property_x.num_columns()? as usize)
+            .map(|column_number| variable_names
+                .and_then(|names| names.get(column_number)).cloned()
+                .unwrap_or_else(|| "[Unknown]".into()).to_string())
+            .collect::<Vec<String>>();
+
+        let (name, cite) = match self.implementation.to_lowercase().as_str() {
+            "objective-perturbation" => ("Objective Perturbation", "Chaudhuri, Monteleoni and Sarwate (2011), Algorithm 2"),
+            "output-perturbation" => ("Output Perturbation", "Chaudhuri, Monteleoni and Sarwate (2011), Algorithm 1"),
+            _ => bail!("`{}` is not recognized as a valid implementation. Must be one of [`objective-perturbation`, `output-perturbation`]", self.implementation)
+        };
+
+        let num_records = data_property_x.num_records()?;
+        let accuracy = match self.implementation.to_lowercase().as_str() {
+            // the error of the coefficients is the output noise, whose norm is gamma distributed
+            "output-perturbation" => {
+                let privacy_usage = self.privacy_usage.iter().cloned().map(Ok)
+                    .fold1(|l, r| l? + r?).ok_or_else(|| "privacy_usage: must be defined")??;
+                let scale = 2. / (num_records as f64 * self.regularization * get_epsilon(&privacy_usage)?);
+                Some(Accuracy {
+                    accuracy_value: scale * gamma_quantile(variables.len() as f64, 1. - self.alpha) / self.clip_norm,
+                    alpha: self.alpha,
+                })
+            }
+            // the noise perturbs the objective, so its effect on the coefficients depends on the data
+            _ => None
+        };
+
+        let release = JSONRelease {
+            description: "DP release information".to_string(),
+            statistic: "DPLogisticRegression".to_string(),
+            variables: serde_json::json!(variables),
+            release_info: value_to_json(release)?,
+            privacy_loss: serde_json::json!(self.privacy_usage.iter().map(privacy_usage_to_json).collect::<Vec<_>>()),
+            accuracy,
+            submission: component.submission,
+            node_id,
+            postprocess: false,
+            algorithm_info: AlgorithmInfo {
+                name: name.to_string(),
+                cite: cite.to_string(),
+                mechanism: "gamma-norm".into(),
+                argument: serde_json::json!({
+                    "n": num_records,
+                    "regularization": self.regularization,
+                    "constraint": {
+                        "clip_norm": self.clip_norm
+                    }
+                }),
+            },
+        };
+        Ok(Some(vec![release]))
+    }
+}
+
+/// Quantile of the gamma distribution with unit scale, found by bisection on its CDF.
+fn gamma_quantile(shape: f64, probability: f64) -> f64 {
+    let mut upper = shape.max(1.);
+    while gamma_lr(shape, upper) < probability {
+        upper *= 2.;
+    }
+    let mut lower = 0.;
+    for _ in 0..100 {
+        let middle = (lower + upper) / 2.;
+        if gamma_lr(shape, middle) < probability { lower = middle } else { upper = middle }
+    }
+    upper
+}
+
+#[cfg(test)]
+mod test_dp_logistic_regression {
+    use crate::components::dp_logistic_regression::gamma_quantile;
+
+    #[test]
+    fn test_gamma_quantile() {
+        // with shape one, the gamma distribution is exponential
+        assert!((gamma_quantile(1., 0.95) - -(0.05_f64).ln()).abs() < 1e-8);
+        // the median of a gamma with shape two is about 1.678
+        assert!((gamma_quantile(2., 0.5) - 1.678_346_99).abs() < 1e-6);
+    }
+}
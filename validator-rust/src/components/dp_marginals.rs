@@ -0,0 +1,318 @@
+use indexmap::map::IndexMap;
+use itertools::Itertools;
+use ndarray::Array;
+
+use crate::{base, Float, Integer, proto, Warnable};
+use crate::base::{ArrayProperties, DataType, IndexKey, NodeProperties, Value, ValueProperties};
+use crate::components::{Component, Expandable, Mechanism, Report};
+use crate::errors::*;
+use crate::utilities::{get_literal, prepend};
+use crate::utilities::inference::infer_property;
+use crate::utilities::privacy::{get_delta, privacy_usage_check};
+use crate::utilities::json::{AlgorithmInfo, JSONRelease, privacy_usage_to_json, value_to_json};
+
+impl Component for proto::DpMarginals {
+    fn propagate_property(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: NodeProperties,
+        node_id: u32,
+    ) -> Result<Warnable<ValueProperties>> {
+        let privacy_definition = privacy_definition.as_ref()
+            .ok_or_else(|| "privacy_definition must be defined")?;
+
+        if privacy_definition.protect_floating_point {
+            return Err("Floating-point protections are enabled. Perturbation of the contingency tables is susceptible to floating-point attacks.".into())
+        }
+
+        let data_property: ArrayProperties = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        data_property.assert_is_not_aggregated()?;
+
+        properties.get::<IndexKey>(&"sensitivity".into())
+            .ok_or("sensitivity: missing")?;
+
+        let num_columns = data_property.num_columns()?;
+
+        let num_categories = public_arguments.get::<IndexKey>(&"categories".into())
+            .ok_or_else(|| Error::from("categories: missing, must be public"))?
+            .ref_jagged().map_err(prepend("categories:"))?
+            .num_records();
+        if num_categories.len() as i64 != num_columns {
+            return Err("categories: must be defined for each column of data".into())
+        }
+
+        let marginals = get_marginals(public_arguments.get::<IndexKey>(&"marginals".into())
+            .ok_or_else(|| Error::from("marginals: missing, must be public"))?, num_columns)?;
+
+        // total number of cells over all tables
+        let num_cells = marginals.iter()
+            .map(|marginal| marginal.iter()
+                .try_fold(1i64, |size, column| size.checked_mul(num_categories[*column])))
+            .try_fold(0i64, |total, size| total.checked_add(size?))
+            .ok_or_else(|| Error::from("marginals: the contingency tables are too large"))?;
+
+        let privacy_usage = self.privacy_usage.iter().cloned().map(Ok)
+            .fold1(|l, r| l? + r?).ok_or_else(|| "privacy_usage: must be defined")??;
+
+        let delta = get_delta(&privacy_usage)?;
+        match self.mechanism.to_lowercase().as_str() {
+            "laplace" => if delta != 0. {
+                return Err("delta: must be zero for the Laplace mechanism".into())
+            },
+            "gaussian" | "analyticgaussian" => if delta == 0. {
+                return Err("delta: must be greater than zero for the Gaussian mechanism".into())
+            },
+            _ => bail!("`{}` is not recognized as a valid mechanism. Must be one of [`Laplace`, `Gaussian`, `AnalyticGaussian`]", self.mechanism)
+        }
+
+        let warnings = privacy_usage_check(
+            &privacy_usage,
+            data_property.num_records,
+            privacy_definition.strict_parameter_checks)?;
+
+        Ok(Warnable(ValueProperties::Array(ArrayProperties {
+            num_records: Some(num_cells),
+            num_columns: Some(1),
+            nullity: false,
+            releasable: true,
+            c_stability: 1,
+            aggregator: None,
+            nature: None,
+            data_type: DataType::Float,
+            dataset_id: None,
+            node_id: node_id as i64,
+            is_not_empty: num_cells > 0,
+            dimensionality: Some(1),
+            group_id: data_property.group_id,
+            naturally_ordered: true,
+            sample_proportion: None,
+        }), warnings))
+    }
+}
+
+impl Expandable for proto::DpMarginals {
+    fn expand_component(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        component: &proto::Component,
+        public_arguments: &IndexMap<IndexKey, &Value>,
+        properties: &base::NodeProperties,
+        component_id: u32,
+        mut maximum_id: u32,
+    ) -> Result<base::ComponentExpansion> {
+        let mut expansion = base::ComponentExpansion::default();
+
+        let privacy_definition = privacy_definition.as_ref()
+            .ok_or_else(|| "privacy definition must be defined")?;
+
+        if self.privacy_usage.len() != 1 {
+            return Err(Error::from("privacy usage must be of length one"));
+        }
+
+        let data_property: ArrayProperties = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+        let num_columns = data_property.num_columns()?;
+
+        let mut noise_component = component.clone();
+
+        if !component.arguments().contains_key::<IndexKey>(&"categories".into()) {
+            let categories = data_property.categories()
+                .map_err(|_| Error::from("categories: must be supplied when the categories of data are not known"))?;
+
+            maximum_id += 1;
+            let id_categories = maximum_id;
+            let (patch_node, release) = get_literal(Value::Jagged(categories), component.submission)?;
+            expansion.computation_graph.insert(id_categories, patch_node);
+            expansion.properties.insert(id_categories, infer_property(&release.value, None, id_categories)?);
+            expansion.releases.insert(id_categories, release);
+            noise_component.insert_argument(&"categories".into(), id_categories);
+        }
+
+        let num_marginals = match public_arguments.get::<IndexKey>(&"marginals".into()) {
+            Some(marginals) => get_marginals(marginals, num_columns)?.len(),
+            None => {
+                if self.k == 0 || self.k as i64 > num_columns {
+                    return Err(format!("k: must be between one and the number of columns ({})", num_columns).into())
+                }
+                // every k-way combination of columns
+                let marginals = (0..num_columns as Integer)
+                    .combinations(self.k as usize)
+                    .collect::<Vec<Vec<Integer>>>();
+                let num_marginals = marginals.len();
+
+                maximum_id += 1;
+                let id_marginals = maximum_id;
+                let value = Array::from_shape_vec(
+                    (num_marginals, self.k as usize),
+                    marginals.into_iter().flatten().collect())?.into_dyn().into();
+                let (patch_node, release) = get_literal(value, component.submission)?;
+                expansion.computation_graph.insert(id_marginals, patch_node);
+                expansion.properties.insert(id_marginals, infer_property(&release.value, None, id_marginals)?);
+                expansion.releases.insert(id_marginals, release);
+                noise_component.insert_argument(&"marginals".into(), id_marginals);
+                num_marginals
+            }
+        };
+
+        let mechanism = match self.mechanism.to_lowercase().as_str() {
+            "automatic" => if get_delta(&self.privacy_usage[0])? == 0. {
+                "laplace"
+            } else {
+                "gaussian"
+            },
+            mechanism => mechanism
+        }.to_string();
+
+        // always overwrite sensitivity. This is not something a user may configure
+        let sensitivity = compute_sensitivity(privacy_definition, num_marginals, &mechanism)?;
+
+        maximum_id += 1;
+        let id_sensitivity = maximum_id;
+        let (patch_node, release) = get_literal(Value::from(sensitivity), component.submission)?;
+        expansion.computation_graph.insert(id_sensitivity, patch_node);
+        expansion.properties.insert(id_sensitivity, infer_property(&release.value, None, id_sensitivity)?);
+        expansion.releases.insert(id_sensitivity, release);
+        noise_component.insert_argument(&"sensitivity".into(), id_sensitivity);
+
+        // update the mechanism and privacy usage
+        if let Some(proto::component::Variant::DpMarginals(variant)) = &mut noise_component.variant {
+            variant.mechanism = mechanism;
+            variant.privacy_usage = vec![self.privacy_usage[0].actual_to_effective(
+                data_property.sample_proportion.unwrap_or(1.),
+                data_property.c_stability,
+                privacy_definition.group_size)?];
+            // this case should never happen
+        } else { return Err(Error::from("Variant must be defined")) }
+        expansion.computation_graph.insert(component_id, noise_component);
+
+        Ok(expansion)
+    }
+}
+
+impl Mechanism for proto::DpMarginals {
+    fn get_privacy_usage(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        release_usage: Option<&Vec<proto::PrivacyUsage>>,
+        properties: &NodeProperties
+    ) -> Result<Option<Vec<proto::PrivacyUsage>>> {
+        let data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?;
+
+        Some(release_usage.unwrap_or_else(|| &self.privacy_usage).iter()
+            .map(|usage| usage.effective_to_actual(
+                data_property.sample_proportion.unwrap_or(1.),
+                data_property.c_stability,
+                privacy_definition.group_size))
+            .collect::<Result<Vec<proto::PrivacyUsage>>>()).transpose()
+    }
+}
+
+impl Report for proto::DpMarginals {
+    fn summarize(
+        &self,
+        node_id: u32,
+        component: &proto::Component,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: NodeProperties,
+        release: &Value,
+        variable_names: Option<&Vec<base::IndexKey>>,
+    ) -> Result<Option<Vec<JSONRelease>>> {
+        let data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        let num_columns = data_property.num_columns()?;
+        let marginals = get_marginals(public_arguments.get::<IndexKey>(&"marginals".into())
+            .ok_or_else(|| Error::from("marginals: missing, must be public"))?, num_columns)?;
+
+        let variables = (0..num_columns as usize)
+            .map(|column_number| variable_names
+                .and_then(|names| names.get(column_number)).cloned()
+                .unwrap_or_else(|| "[Unknown]".into()).to_string())
+            .collect::<Vec<String>>();
+
+        let release = JSONRelease {
+            description: "DP release information".to_string(),
+            statistic: "DPMarginals".to_string(),
+            variables: serde_json::json!(variables),
+            release_info: value_to_json(release)?,
+            privacy_loss: serde_json::json!(self.privacy_usage.iter().map(privacy_usage_to_json).collect::<Vec<_>>()),
+            accuracy: None,
+            submission: component.submission,
+            node_id,
+            postprocess: false,
+            algorithm_info: AlgorithmInfo {
+                name: "".to_string(),
+                cite: "".to_string(),
+                mechanism: self.mechanism.clone(),
+                argument: serde_json::json!({
+                    "marginals": marginals.iter()
+                        .map(|marginal| marginal.iter()
+                            .map(|column| variables[*column].clone())
+                            .collect::<Vec<String>>())
+                        .collect::<Vec<Vec<String>>>(),
+                    "consistent": self.consistent
+                }),
+            },
+        };
+        Ok(Some(vec![release]))
+    }
+}
+
+/// Retrieve the column indices of each marginal, and check that they are valid.
+fn get_marginals(marginals: &Value, num_columns: i64) -> Result<Vec<Vec<usize>>> {
+    let marginals = marginals.ref_array()?.ref_int().map_err(prepend("marginals:"))?;
+
+    if marginals.ndim() != 2 {
+        return Err("marginals: must be a 2-dimensional array".into())
+    }
+    if marginals.is_empty() {
+        return Err("marginals: must contain at least one marginal".into())
+    }
+
+    marginals.genrows().into_iter()
+        .map(|marginal| {
+            let columns = marginal.iter()
+                .map(|column| if *column < 0 || *column >= num_columns {
+                    Err(format!("marginals: column indices must be between zero and the number of columns ({})", num_columns).into())
+                } else { Ok(*column as usize) })
+                .collect::<Result<Vec<usize>>>()?;
+            if columns.iter().unique().count() != columns.len() {
+                return Err("marginals: a column may not be repeated within a marginal".into())
+            }
+            Ok(columns)
+        })
+        .collect()
+}
+
+/// Sensitivity of the concatenated tables.
+///
+/// Each record contributes to exactly one cell of each table.
+/// Under substitution, a record may move from one cell to another in every table.
+fn compute_sensitivity(
+    privacy_definition: &proto::PrivacyDefinition,
+    num_marginals: usize,
+    mechanism: &str,
+) -> Result<Float> {
+    use proto::privacy_definition::Neighboring;
+    let neighboring_type = Neighboring::from_i32(privacy_definition.neighboring)
+        .ok_or_else(|| Error::from("neighboring definition must be either \"AddRemove\" or \"Substitute\""))?;
+
+    let cells_per_table = match neighboring_type {
+        Neighboring::AddRemove => 1.,
+        Neighboring::Substitute => 2.
+    };
+
+    Ok(match mechanism {
+        "laplace" => cells_per_table * num_marginals as Float,
+        "gaussian" | "analyticgaussian" => (cells_per_table * num_marginals as Float).sqrt(),
+        _ => bail!("`{}` is not recognized as a valid mechanism. Must be one of [`Laplace`, `Gaussian`, `AnalyticGaussian`]", mechanism)
+    })
+}
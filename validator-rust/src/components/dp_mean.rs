@@ -1,10 +1,12 @@
 use indexmap::map::IndexMap;
+use ndarray::arr1;
 
-use crate::{base, proto};
-use crate::base::{IndexKey, NodeProperties, Value};
+use crate::{base, Float, proto};
+use crate::base::{DataType, IndexKey, NodeProperties, Value};
 use crate::components::{Expandable, Report};
 use crate::errors::*;
-use crate::utilities::{array::get_ith_column, prepend, privacy::spread_privacy_usage};
+use crate::utilities::{array::get_ith_column, get_literal, prepend, privacy::spread_privacy_usage};
+use crate::utilities::inference::infer_property;
 use crate::utilities::json::{AlgorithmInfo, JSONRelease, privacy_usage_to_json, value_to_json};
 
 impl Expandable for proto::DpMean {
@@ -155,8 +157,220 @@ impl Expandable for proto::DpMean {
             Ok(expansion)
         }
 
+        else if ["trimmed", "winsorized"].contains(&self.implementation.to_lowercase().as_str()) {
+            let is_trimmed = self.implementation.to_lowercase().as_str() == "trimmed";
+
+            let data_property = properties.get::<base::IndexKey>(&"data".into())
+                .ok_or("data: missing")?.array()
+                .map_err(prepend("data:"))?.clone();
+
+            if data_property.data_type != DataType::Float {
+                return Err("data: must be float to estimate the quantiles. Consider casting with ToFloat".into())
+            }
+            let num_columns = data_property.num_columns()?;
+            if is_trimmed && num_columns != 1 {
+                return Err("data: trimmed means may only be computed over one column. Consider the winsorized implementation".into())
+            }
+            if !(0. < self.tail_proportion && self.tail_proportion < 0.5) {
+                return Err("tail_proportion: must be in (0, 0.5)".into())
+            }
+
+            let id_data = *argument_ids.get::<base::IndexKey>(&"data".into())
+                .ok_or_else(|| Error::from("data must be provided as an argument"))?;
+
+            // a quarter of the budget of each column is spent on each quantile, and the remaining half on the mean
+            let privacy_usages = spread_privacy_usage(&self.privacy_usage, num_columns as usize)?;
+            let quantile_usages = privacy_usages.iter().cloned()
+                .map(|v| v / 4.)
+                .collect::<Result<Vec<proto::PrivacyUsage>>>()?;
+            let mean_usages = privacy_usages.into_iter()
+                .map(|v| v / 2.)
+                .collect::<Result<Vec<proto::PrivacyUsage>>>()?;
+
+            // dp quantiles
+            let mut quantile_ids = Vec::new();
+            for alpha in &[self.tail_proportion, 1. - self.tail_proportion] {
+                maximum_id += 1;
+                expansion.computation_graph.insert(maximum_id, proto::Component {
+                    arguments: Some(proto::ArgumentNodeIds::new(
+                        indexmap!["data".into() => id_data])),
+                    variant: Some(proto::component::Variant::DpQuantile(proto::DpQuantile {
+                        alpha: *alpha,
+                        mechanism: "Automatic".to_string(),
+                        privacy_usage: quantile_usages.clone(),
+                        interpolation: "midpoint".to_string(),
+                    })),
+                    omit: true,
+                    submission: component.submission,
+                });
+                expansion.traversal.push(maximum_id);
+                quantile_ids.push(maximum_id);
+            }
+
+            // the noisy quantiles may cross or coincide, but the bounds of the clamp must be strictly ordered
+            maximum_id += 1;
+            let id_lower = maximum_id;
+            expansion.computation_graph.insert(id_lower, proto::Component {
+                arguments: Some(proto::ArgumentNodeIds::new(indexmap![
+                    "left".into() => quantile_ids[0],
+                    "right".into() => quantile_ids[1]])),
+                variant: Some(proto::component::Variant::RowMin(proto::RowMin {})),
+                omit: true,
+                submission: component.submission,
+            });
+            expansion.traversal.push(id_lower);
+
+            maximum_id += 1;
+            let id_maximum = maximum_id;
+            expansion.computation_graph.insert(id_maximum, proto::Component {
+                arguments: Some(proto::ArgumentNodeIds::new(indexmap![
+                    "left".into() => quantile_ids[0],
+                    "right".into() => quantile_ids[1]])),
+                variant: Some(proto::component::Variant::RowMax(proto::RowMax {})),
+                omit: true,
+                submission: component.submission,
+            });
+            expansion.traversal.push(id_maximum);
+
+            // smallest gap between the bounds, relative to the width and magnitude of the bounds of the data
+            let gaps = data_property.lower_float().map_err(prepend("lower:"))?.into_iter()
+                .zip(data_property.upper_float().map_err(prepend("upper:"))?)
+                .map(|(lower, upper)| ((upper - lower) * 1e-6)
+                    .max(lower.abs().max(upper.abs()) * 1e-12)
+                    .max(Float::MIN_POSITIVE))
+                .collect::<Vec<Float>>();
+            maximum_id += 1;
+            let id_gap = maximum_id;
+            let (patch_node, release) = get_literal(arr1(&gaps).into_dyn().into(), component.submission)?;
+            expansion.computation_graph.insert(id_gap, patch_node);
+            expansion.properties.insert(id_gap, infer_property(&release.value, None, id_gap)?);
+            expansion.releases.insert(id_gap, release);
+
+            maximum_id += 1;
+            let id_gap_upper = maximum_id;
+            expansion.computation_graph.insert(id_gap_upper, proto::Component {
+                arguments: Some(proto::ArgumentNodeIds::new(indexmap![
+                    "left".into() => id_lower,
+                    "right".into() => id_gap])),
+                variant: Some(proto::component::Variant::Add(proto::Add {})),
+                omit: true,
+                submission: component.submission,
+            });
+            expansion.traversal.push(id_gap_upper);
+
+            maximum_id += 1;
+            let id_upper = maximum_id;
+            expansion.computation_graph.insert(id_upper, proto::Component {
+                arguments: Some(proto::ArgumentNodeIds::new(indexmap![
+                    "left".into() => id_maximum,
+                    "right".into() => id_gap_upper])),
+                variant: Some(proto::component::Variant::RowMax(proto::RowMax {})),
+                omit: true,
+                submission: component.submission,
+            });
+            expansion.traversal.push(id_upper);
+
+            // drop the records outside of the quantiles
+            let id_kept = if is_trimmed {
+                let mut negated_ids = Vec::new();
+                for (id_quantile, variant) in &[
+                    (id_lower, proto::component::Variant::LessThan(proto::LessThan {})),
+                    (id_upper, proto::component::Variant::GreaterThan(proto::GreaterThan {}))] {
+                    maximum_id += 1;
+                    let id_comparison = maximum_id;
+                    expansion.computation_graph.insert(id_comparison, proto::Component {
+                        arguments: Some(proto::ArgumentNodeIds::new(indexmap![
+                            "left".into() => id_data,
+                            "right".into() => *id_quantile])),
+                        variant: Some(variant.clone()),
+                        omit: true,
+                        submission: component.submission,
+                    });
+                    expansion.traversal.push(id_comparison);
+
+                    maximum_id += 1;
+                    expansion.computation_graph.insert(maximum_id, proto::Component {
+                        arguments: Some(proto::ArgumentNodeIds::new(
+                            indexmap!["data".into() => id_comparison])),
+                        variant: Some(proto::component::Variant::Negate(proto::Negate {})),
+                        omit: true,
+                        submission: component.submission,
+                    });
+                    expansion.traversal.push(maximum_id);
+                    negated_ids.push(maximum_id);
+                }
+
+                // mask
+                maximum_id += 1;
+                let id_mask = maximum_id;
+                expansion.computation_graph.insert(id_mask, proto::Component {
+                    arguments: Some(proto::ArgumentNodeIds::new(indexmap![
+                        "left".into() => negated_ids[0],
+                        "right".into() => negated_ids[1]])),
+                    variant: Some(proto::component::Variant::LogicalAnd(proto::And {})),
+                    omit: true,
+                    submission: component.submission,
+                });
+                expansion.traversal.push(id_mask);
+
+                // filter
+                maximum_id += 1;
+                let id_filter = maximum_id;
+                expansion.computation_graph.insert(id_filter, proto::Component {
+                    arguments: Some(proto::ArgumentNodeIds::new(indexmap![
+                        "data".into() => id_data,
+                        "mask".into() => id_mask])),
+                    variant: Some(proto::component::Variant::Filter(proto::Filter {})),
+                    omit: true,
+                    submission: component.submission,
+                });
+                expansion.traversal.push(id_filter);
+                id_filter
+            } else { id_data };
+
+            // clamp to the quantiles. When trimmed, this only tightens the bounds of the kept records
+            maximum_id += 1;
+            let id_clamp = maximum_id;
+            expansion.computation_graph.insert(id_clamp, proto::Component {
+                arguments: Some(proto::ArgumentNodeIds::new(indexmap![
+                    "data".into() => id_kept,
+                    "lower".into() => id_lower,
+                    "upper".into() => id_upper])),
+                variant: Some(proto::component::Variant::Clamp(proto::Clamp {})),
+                omit: true,
+                submission: component.submission,
+            });
+            expansion.traversal.push(id_clamp);
+
+            // the number of records is unknown after trimming
+            let implementation = if is_trimmed || data_property.num_records.is_none() {
+                "plug-in"
+            } else { "resize" };
+
+            let mut arguments = indexmap!["data".into() => id_clamp];
+            argument_ids.get::<IndexKey>(&"lower".into())
+                .map(|lower| arguments.insert("lower".into(), *lower));
+            argument_ids.get::<IndexKey>(&"upper".into())
+                .map(|upper| arguments.insert("upper".into(), *upper));
+
+            // dp mean over the clamped data
+            expansion.computation_graph.insert(component_id, proto::Component {
+                arguments: Some(proto::ArgumentNodeIds::new(arguments)),
+                variant: Some(proto::component::Variant::DpMean(proto::DpMean {
+                    implementation: implementation.to_string(),
+                    mechanism: self.mechanism.clone(),
+                    tail_proportion: self.tail_proportion,
+                    privacy_usage: mean_usages,
+                })),
+                omit: component.omit,
+                submission: component.submission,
+            });
+
+            Ok(expansion)
+        }
+
         else {
-            bail!("`{}` is not recognized as a valid implementation. Must be one of [`resize`, `plug-in`]", self.implementation)
+            bail!("`{}` is not recognized as a valid implementation. Must be one of [`resize`, `plug-in`, `trimmed`, `winsorized`]", self.implementation)
         }
     }
 }
@@ -188,12 +402,14 @@ impl Report for proto::DpMean {
 
         let lower = data_property.lower_float()?;
         let upper = data_property.upper_float()?;
-        let num_records = data_property.num_records()?;
+        // the number of records is not known for the plug-in and trimmed implementations
+        let num_records = data_property.num_records;
 
         let num_columns = data_property.num_columns()?;
         let privacy_usages = spread_privacy_usage(&self.privacy_usage, num_columns as usize)?;
 
         let release = release.ref_array()?.ref_float()?;
+        let is_robust = ["trimmed", "winsorized"].contains(&self.implementation.to_lowercase().as_str());
 
         for column_number in 0..(num_columns as usize) {
             let variable_name = variable_names
@@ -217,18 +433,191 @@ impl Report for proto::DpMean {
                     name: "".to_string(),
                     cite: "".to_string(),
                     mechanism: self.mechanism.clone(),
-                    argument: serde_json::json!({
+                    argument: {
                         // TODO: AlgorithmInfo -> serde_json::Value, move implementation into algorithm_info
-                        "implementation": self.implementation.clone(),
-                        "n": num_records,
-                        "constraint": {
-                            "lowerbound": lower[column_number],
-                            "upperbound": upper[column_number]
+                        let mut argument = serde_json::json!({
+                            "implementation": self.implementation.clone(),
+                            "n": num_records,
+                            "constraint": {
+                                "lowerbound": lower[column_number],
+                                "upperbound": upper[column_number]
+                            }
+                        });
+                        if is_robust {
+                            argument["tail_proportion"] = serde_json::json!(self.tail_proportion);
+                            argument["privacy_loss"] = serde_json::json!({
+                                "lower_quantile": privacy_usage_to_json(&(privacy_usages[column_number].clone() / 4.)?),
+                                "upper_quantile": privacy_usage_to_json(&(privacy_usages[column_number].clone() / 4.)?),
+                                "mean": privacy_usage_to_json(&(privacy_usages[column_number].clone() / 2.)?)
+                            });
                         }
-                    })
+                        argument
+                    }
                 }
             });
         }
         Ok(Some(releases))
     }
 }
+
+#[cfg(test)]
+mod test_dp_mean {
+    use ndarray::arr1;
+
+    use crate::base::{ComponentExpansion, ReleaseNode, test_data, ValueProperties};
+    use crate::bindings::Analysis;
+    use crate::components::clamp::test_clamp;
+
+    use super::*;
+
+    fn privacy_usage(epsilon: Float) -> Vec<proto::PrivacyUsage> {
+        vec![proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                epsilon, delta: 0.
+            }))
+        }]
+    }
+
+    fn get_epsilon(usages: &[proto::PrivacyUsage]) -> Float {
+        match usages[0].distance.clone().unwrap() {
+            proto::privacy_usage::Distance::Approximate(distance) => distance.epsilon
+        }
+    }
+
+    /// Expand a robust mean over ten records bounded within [0, 1].
+    fn expand_robust(implementation: &str, tail_proportion: Float) -> Result<(Analysis, u32, ComponentExpansion)> {
+        let (mut analysis, clamped) = test_clamp::utilities::analysis_f64_cont(
+            test_data::array1d_f64_10_uniform(), Some(0.0.into()), Some(1.0.into()));
+        let data_property = analysis.properties(clamped)?;
+
+        let mean = analysis.dp_mean(clamped, privacy_usage(1.))
+            .implementation(implementation.to_string())
+            .tail_proportion(tail_proportion)
+            .build();
+        let component = analysis.components[&mean].clone();
+        let dp_mean = match &component.variant {
+            Some(proto::component::Variant::DpMean(variant)) => variant.clone(),
+            _ => unreachable!()
+        };
+
+        let expansion = dp_mean.expand_component(
+            &Some(analysis.privacy_definition.clone()),
+            &component,
+            &IndexMap::new(),
+            &indexmap!["data".into() => data_property],
+            mean, analysis.component_count)?;
+        Ok((analysis, mean, expansion))
+    }
+
+    fn find_nodes(expansion: &ComponentExpansion, matches: fn(&proto::component::Variant) -> bool) -> Vec<u32> {
+        let mut ids = expansion.computation_graph.iter()
+            .filter(|(_, component)| component.variant.as_ref().map(matches).unwrap_or(false))
+            .map(|(id, _)| *id)
+            .collect::<Vec<u32>>();
+        ids.sort_unstable();
+        ids
+    }
+
+    fn get_clamp(expansion: &ComponentExpansion) -> (u32, proto::Component) {
+        let id = find_nodes(expansion, |v| matches!(v, proto::component::Variant::Clamp(_)))[0];
+        (id, expansion.computation_graph[&id].clone())
+    }
+
+    #[test]
+    fn test_robust_expansion() {
+        for implementation in &["trimmed", "winsorized"] {
+            let (_, mean, expansion) = expand_robust(implementation, 0.1).unwrap();
+
+            // a quarter of the budget is spent on each quantile
+            let quantiles = find_nodes(&expansion, |v| matches!(v, proto::component::Variant::DpQuantile(_))).into_iter()
+                .map(|id| match &expansion.computation_graph[&id].variant {
+                    Some(proto::component::Variant::DpQuantile(quantile)) => (quantile.alpha, get_epsilon(&quantile.privacy_usage)),
+                    _ => unreachable!()
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(quantiles, vec![(0.1, 0.25), (0.9, 0.25)]);
+
+            // the remaining half is spent on the mean
+            match &expansion.computation_graph[&mean].variant {
+                Some(proto::component::Variant::DpMean(dp_mean)) => {
+                    assert_eq!(get_epsilon(&dp_mean.privacy_usage), 0.5);
+                    assert_eq!(dp_mean.implementation, if *implementation == "trimmed" { "plug-in" } else { "resize" });
+                }
+                _ => panic!("the mean must be computed over the clamped data")
+            }
+
+            // the clamp is bounded by the ordered quantiles
+            let (_, clamp) = get_clamp(&expansion);
+            assert!(matches!(expansion.computation_graph[&clamp.arguments()[&IndexKey::from("lower")]].variant,
+                Some(proto::component::Variant::RowMin(_))));
+            assert!(matches!(expansion.computation_graph[&clamp.arguments()[&IndexKey::from("upper")]].variant,
+                Some(proto::component::Variant::RowMax(_))));
+
+            // only the trimmed mean drops records
+            let filters = find_nodes(&expansion, |v| matches!(v, proto::component::Variant::Filter(_)));
+            assert_eq!(filters.len(), if *implementation == "trimmed" { 1 } else { 0 });
+        }
+    }
+
+    #[test]
+    fn test_robust_crossed_quantiles() {
+        for (lower, upper) in &[(0.6, 0.4), (0.5, 0.5)] {
+            let (mut analysis, _, expansion) = expand_robust("winsorized", 0.25).unwrap();
+            let quantiles = find_nodes(&expansion, |v| matches!(v, proto::component::Variant::DpQuantile(_)));
+            let (id_clamp, _) = get_clamp(&expansion);
+
+            analysis.components.extend(expansion.computation_graph);
+            analysis.release.extend(expansion.releases);
+            // release noisy quantiles that are out of order, or equal
+            for (id, value) in quantiles.into_iter().zip(&[*lower, *upper]) {
+                analysis.release.insert(id, ReleaseNode::new(arr1(&[*value]).into_dyn().into()));
+                analysis.release.get_mut(&id).unwrap().public = true;
+            }
+
+            let clamped = match analysis.properties(id_clamp).unwrap() {
+                ValueProperties::Array(property) => property,
+                _ => panic!("clamped data must be an array")
+            };
+            let (clamp_lower, clamp_upper) = (clamped.lower_float().unwrap()[0], clamped.upper_float().unwrap()[0]);
+            assert_eq!(clamp_lower, lower.min(*upper));
+            assert!(clamp_upper > clamp_lower);
+        }
+    }
+
+    #[test]
+    fn test_robust_failures() {
+        assert!(expand_robust("trimmed", 0.5).is_err());
+        assert!(expand_robust("winsorized", 0.).is_err());
+
+        let (mut analysis, data) = test_clamp::utilities::analysis_i64_cont(
+            test_data::array1d_i64_10_uniform(), Some(0.into()), Some(10.into()));
+        let mean = analysis.dp_mean(data, privacy_usage(1.))
+            .implementation("winsorized".to_string())
+            .build();
+        assert!(analysis.properties(mean).is_err());
+    }
+
+    #[test]
+    fn test_robust_report() {
+        let (analysis, mean, _) = expand_robust("trimmed", 0.1).unwrap();
+        let component = analysis.components[&mean].clone();
+        let clamped = *component.arguments().get::<IndexKey>(&"data".into()).unwrap();
+        let dp_mean = match &component.variant {
+            Some(proto::component::Variant::DpMean(variant)) => variant.clone(),
+            _ => unreachable!()
+        };
+
+        let releases = dp_mean.summarize(
+            mean, &component, IndexMap::new(),
+            indexmap!["data".into() => analysis.properties(clamped).unwrap()],
+            &arr1(&[0.4]).into_dyn().into(), None).unwrap().unwrap();
+
+        let argument = &releases[0].algorithm_info.argument;
+        assert_eq!(argument["implementation"], "trimmed");
+        assert_eq!(argument["tail_proportion"], 0.1);
+        assert_eq!(argument["privacy_loss"]["lower_quantile"]["epsilon"], 0.25);
+        assert_eq!(argument["privacy_loss"]["upper_quantile"]["epsilon"], 0.25);
+        assert_eq!(argument["privacy_loss"]["mean"]["epsilon"], 0.5);
+        assert_eq!(releases[0].privacy_loss["epsilon"], 1.);
+    }
+}
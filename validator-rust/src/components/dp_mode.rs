@@ -0,0 +1,199 @@
+use indexmap::map::IndexMap;
+use itertools::Itertools;
+
+use crate::{base, proto, Warnable};
+use crate::base::{ArrayProperties, DataType, IndexKey, Jagged, NodeProperties, SensitivitySpace, Value, ValueProperties};
+use crate::components::{Component, Expandable, Mechanism, Report};
+use crate::components::dp_empirical_cdf::{expand_histogram_mechanism, get_edges, histogram_mechanism_privacy_usage};
+use crate::errors::*;
+use crate::utilities::{get_literal, prepend};
+use crate::utilities::inference::infer_property;
+use crate::utilities::json::{AlgorithmInfo, JSONRelease, privacy_usage_to_json, value_to_json};
+use crate::utilities::privacy::{get_delta, privacy_usage_check, spread_privacy_usage};
+
+impl Component for proto::DpMode {
+    fn propagate_property(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: NodeProperties,
+        node_id: u32,
+    ) -> Result<Warnable<ValueProperties>> {
+        let privacy_definition = privacy_definition.as_ref()
+            .ok_or_else(|| "privacy_definition must be defined")?;
+
+        if get_mechanism(&self.mechanism)? == "reportnoisymax" && privacy_definition.protect_floating_point {
+            return Err("Floating-point protections are enabled. Report noisy max adds Laplace noise, which is susceptible to floating-point attacks.".into())
+        }
+
+        let data_property: ArrayProperties = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        let aggregator = data_property.aggregator.as_ref()
+            .ok_or_else(|| Error::from("data: must be aggregated with a Histogram"))?;
+        if !matches!(aggregator.component, proto::component::Variant::Histogram(_)) {
+            return Err("data: must be aggregated with a Histogram".into())
+        }
+        if data_property.num_columns()? != 1 {
+            return Err("data: must contain one column".into())
+        }
+
+        // the mode is selected from the bins, or from the categories
+        let data_type = match public_arguments.get::<IndexKey>(&"edges".into()) {
+            Some(_) => {
+                if get_edges(&public_arguments)?.len() < 2 {
+                    return Err("edges: must define at least one bin".into())
+                }
+                DataType::Float
+            }
+            None => {
+                let categories = public_arguments.get::<IndexKey>(&"categories".into())
+                    .ok_or_else(|| Error::from("categories: missing, must be public"))?.ref_jagged()?;
+                if categories.num_columns() != 1 || categories.num_records()[0] == 0 {
+                    return Err("categories: must contain one non-empty column".into())
+                }
+                match categories {
+                    Jagged::Bool(_) => DataType::Bool,
+                    Jagged::Float(_) => DataType::Float,
+                    Jagged::Int(_) => DataType::Int,
+                    Jagged::Str(_) => DataType::Str,
+                }
+            }
+        };
+
+        let privacy_usage = self.privacy_usage.iter().cloned().map(Ok)
+            .fold1(|l, r| l? + r?).ok_or_else(|| "privacy_usage: must be defined")??;
+
+        if get_delta(&privacy_usage)? != 0. {
+            return Err("delta: must be zero, the mode is selected under pure differential privacy".into())
+        }
+
+        let warnings = privacy_usage_check(
+            &privacy_usage,
+            Some(1),
+            privacy_definition.strict_parameter_checks)?;
+
+        let output_property = ArrayProperties {
+            num_records: Some(1),
+            num_columns: Some(1),
+            nullity: false,
+            releasable: true,
+            c_stability: 1,
+            aggregator: None,
+            nature: None,
+            data_type,
+            dataset_id: None,
+            node_id: node_id as i64,
+            is_not_empty: true,
+            dimensionality: Some(0),
+            group_id: data_property.group_id,
+            naturally_ordered: true,
+            sample_proportion: None
+        };
+
+        Ok(Warnable(output_property.into(), warnings))
+    }
+}
+
+impl Expandable for proto::DpMode {
+    fn expand_component(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        component: &proto::Component,
+        _public_arguments: &IndexMap<IndexKey, &Value>,
+        properties: &base::NodeProperties,
+        component_id: u32,
+        mut maximum_id: u32,
+    ) -> Result<base::ComponentExpansion> {
+        let data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?;
+
+        let argument_ids = component.arguments();
+        if data_property.aggregator.is_some()
+            || argument_ids.contains_key::<IndexKey>(&"categories".into())
+            || argument_ids.contains_key::<IndexKey>(&"edges".into()) {
+            return expand_histogram_mechanism(
+                &SensitivitySpace::Exponential, privacy_definition, &self.privacy_usage,
+                component, properties, component_id, maximum_id)
+        }
+
+        // the histogram counts over the categories of the data, which are also the candidates for the mode
+        let categories = data_property.categories()
+            .map_err(|_| Error::from("either edges or categories must be supplied"))?;
+
+        maximum_id += 1;
+        let id_categories = maximum_id;
+        let (patch_node, categories_release) = get_literal(Value::Jagged(categories), component.submission)?;
+
+        let mut expansion = expand_histogram_mechanism(
+            &SensitivitySpace::Exponential, privacy_definition, &self.privacy_usage,
+            component, properties, component_id, maximum_id)?;
+
+        expansion.computation_graph.insert(id_categories, patch_node);
+        expansion.properties.insert(id_categories, infer_property(&categories_release.value, None, id_categories)?);
+        expansion.releases.insert(id_categories, categories_release);
+        expansion.computation_graph.get_mut(&component_id)
+            .ok_or_else(|| Error::from("component is missing from the expansion"))?
+            .insert_argument(&"categories".into(), id_categories);
+
+        Ok(expansion)
+    }
+}
+
+impl Mechanism for proto::DpMode {
+    fn get_privacy_usage(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        release_usage: Option<&Vec<proto::PrivacyUsage>>,
+        properties: &NodeProperties
+    ) -> Result<Option<Vec<proto::PrivacyUsage>>> {
+        histogram_mechanism_privacy_usage(privacy_definition, release_usage.unwrap_or(&self.privacy_usage), properties)
+    }
+}
+
+impl Report for proto::DpMode {
+    fn summarize(
+        &self,
+        node_id: u32,
+        component: &proto::Component,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        _properties: NodeProperties,
+        release: &Value,
+        variable_names: Option<&Vec<base::IndexKey>>,
+    ) -> Result<Option<Vec<JSONRelease>>> {
+        let privacy_usage = spread_privacy_usage(&self.privacy_usage, 1)?.remove(0);
+
+        let variable_name = variable_names
+            .and_then(|names| names.first()).cloned()
+            .unwrap_or_else(|| "[Unknown]".into());
+
+        Ok(Some(vec![JSONRelease {
+            description: "DP release information".to_string(),
+            statistic: "DPMode".to_string(),
+            variables: serde_json::json!(variable_name.to_string()),
+            release_info: value_to_json(release)?,
+            privacy_loss: privacy_usage_to_json(&privacy_usage),
+            accuracy: None,
+            submission: component.submission,
+            node_id,
+            postprocess: false,
+            algorithm_info: AlgorithmInfo {
+                name: "".to_string(),
+                cite: "".to_string(),
+                mechanism: self.mechanism.clone(),
+                argument: serde_json::json!({}),
+            },
+        }]))
+    }
+}
+
+/// Normalize the name of the mechanism that selects the mode.
+fn get_mechanism(mechanism: &str) -> Result<&'static str> {
+    Ok(match mechanism.to_lowercase().as_str() {
+        "automatic" | "exponential" => "exponential",
+        "reportnoisymax" => "reportnoisymax",
+        _ => bail!("Unexpected invalid token {:?}", mechanism)
+    })
+}
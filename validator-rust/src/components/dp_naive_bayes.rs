@@ -0,0 +1,341 @@
+use indexmap::map::IndexMap;
+use itertools::Itertools;
+
+use crate::{base, Float, proto, Warnable};
+use crate::base::{ArrayProperties, DataframeProperties, DataType, IndexKey, NodeProperties, Value, ValueProperties};
+use crate::components::{Component, Expandable, Mechanism, Named, Report};
+use crate::errors::*;
+use crate::utilities::{get_literal, prepend};
+use crate::utilities::inference::infer_property;
+use crate::utilities::privacy::{get_delta, privacy_usage_check};
+use crate::utilities::json::{AlgorithmInfo, JSONRelease, privacy_usage_to_json, value_to_json};
+
+impl Component for proto::DpNaiveBayes {
+    fn propagate_property(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: NodeProperties,
+        node_id: u32,
+    ) -> Result<Warnable<ValueProperties>> {
+        let privacy_definition = privacy_definition.as_ref()
+            .ok_or_else(|| "privacy_definition must be defined")?;
+
+        if privacy_definition.protect_floating_point {
+            return Err("Floating-point protections are enabled. Perturbation of the sufficient statistics is susceptible to floating-point attacks.".into())
+        }
+
+        let (label_property, categorical_property, continuous_property) = get_data_properties(&properties)?;
+
+        let num_classes = public_arguments.get::<IndexKey>(&"classes".into())
+            .ok_or_else(|| Error::from("classes: missing, must be public"))?
+            .ref_jagged().map_err(prepend("classes:"))?
+            .num_records();
+        if num_classes.len() != 1 {
+            return Err("classes: must be defined for exactly one column".into())
+        }
+        let num_classes = num_classes[0];
+        if num_classes < 1 {
+            return Err("classes: must contain at least one class".into())
+        }
+
+        properties.get::<IndexKey>(&"sensitivity".into())
+            .ok_or("sensitivity: missing")?;
+
+        let num_categories = match &categorical_property {
+            Some(categorical_property) => {
+                let num_categories = public_arguments.get::<IndexKey>(&"categories".into())
+                    .ok_or_else(|| Error::from("categories: missing, must be public"))?
+                    .ref_jagged().map_err(prepend("categories:"))?
+                    .num_records();
+                if num_categories.len() as i64 != categorical_property.num_columns()? {
+                    return Err("categories: must be defined for each column of data_categorical".into())
+                }
+                if num_categories.iter().any(|v| *v < 1) {
+                    return Err("categories: each column must have at least one category".into())
+                }
+                num_categories
+            }
+            None => vec![]
+        };
+
+        if continuous_property.is_some() {
+            ["lower", "upper"].iter().try_for_each(|name|
+                properties.get::<IndexKey>(&name.to_string().into())
+                    .map(|_| ()).ok_or_else(|| Error::from(format!("{}: missing", name))))?;
+        }
+
+        let privacy_usage = self.privacy_usage.iter().cloned().map(Ok)
+            .fold1(|l, r| l? + r?).ok_or_else(|| "privacy_usage: must be defined")??;
+
+        if get_delta(&privacy_usage)? != 0. {
+            return Err("delta: must be zero, the sufficient statistics are privatized with the Laplace mechanism".into())
+        }
+
+        let warnings = privacy_usage_check(
+            &privacy_usage,
+            label_property.num_records,
+            privacy_definition.strict_parameter_checks)?;
+
+        let model_property = |num_records: i64, num_columns: Option<i64>, data_type: DataType| ValueProperties::Array(ArrayProperties {
+            num_records: Some(num_records),
+            num_columns: Some(num_columns.unwrap_or(1)),
+            nullity: false,
+            releasable: true,
+            c_stability: 1,
+            aggregator: None,
+            nature: None,
+            data_type,
+            dataset_id: Some(node_id as i64),
+            node_id: node_id as i64,
+            is_not_empty: true,
+            dimensionality: Some(if num_columns.is_some() { 2 } else { 1 }),
+            group_id: label_property.group_id.clone(),
+            naturally_ordered: true,
+            sample_proportion: None,
+        });
+
+        let mut children = indexmap![
+            "classes".into() => model_property(num_classes, None, label_property.data_type.clone()),
+            "log_prior".into() => model_property(num_classes, None, DataType::Float)
+        ];
+        if let Some(categorical_property) = categorical_property {
+            let total_categories = num_categories.iter().sum();
+            children.insert("categories".into(), model_property(total_categories, None, categorical_property.data_type));
+            children.insert("num_categories".into(), model_property(num_categories.len() as i64, None, DataType::Int));
+            children.insert("log_likelihoods".into(), model_property(num_classes, Some(total_categories), DataType::Float));
+        }
+        if let Some(continuous_property) = continuous_property {
+            let num_columns = continuous_property.num_columns()?;
+            children.insert("means".into(), model_property(num_classes, Some(num_columns), DataType::Float));
+            children.insert("variances".into(), model_property(num_classes, Some(num_columns), DataType::Float));
+        }
+
+        Ok(Warnable(ValueProperties::Dataframe(DataframeProperties { children }), warnings))
+    }
+}
+
+impl Expandable for proto::DpNaiveBayes {
+    fn expand_component(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        component: &proto::Component,
+        _public_arguments: &IndexMap<IndexKey, &Value>,
+        properties: &base::NodeProperties,
+        component_id: u32,
+        mut maximum_id: u32,
+    ) -> Result<base::ComponentExpansion> {
+        let privacy_definition = privacy_definition.as_ref()
+            .ok_or_else(|| "privacy definition must be defined")?;
+
+        if self.privacy_usage.len() != 1 {
+            return Err(Error::from("privacy usage must be of length one"));
+        }
+
+        let (label_property, categorical_property, continuous_property) = get_data_properties(properties)?;
+
+        let mut expansion = base::ComponentExpansion::default();
+        let mut naive_bayes_component = component.clone();
+
+        let mut insert_literal = |name: &str, value: Value, maximum_id: &mut u32| -> Result<()> {
+            *maximum_id += 1;
+            let id_literal = *maximum_id;
+            let (patch_node, release) = get_literal(value, component.submission)?;
+            expansion.computation_graph.insert(id_literal, patch_node);
+            expansion.properties.insert(id_literal, infer_property(&release.value, None, id_literal)?);
+            expansion.releases.insert(id_literal, release);
+            naive_bayes_component.insert_argument(&name.into(), id_literal);
+            Ok(())
+        };
+
+        if !component.arguments().contains_key::<IndexKey>(&"classes".into()) {
+            let classes = label_property.categories()
+                .map_err(|_| Error::from("classes: must be supplied when the categories of label are not known"))?;
+            insert_literal("classes", Value::Jagged(classes), &mut maximum_id)?;
+        }
+
+        if let Some(categorical_property) = &categorical_property {
+            if !component.arguments().contains_key::<IndexKey>(&"categories".into()) {
+                let categories = categorical_property.categories()
+                    .map_err(|_| Error::from("categories: must be supplied when the categories of data_categorical are not known"))?;
+                insert_literal("categories", Value::Jagged(categories), &mut maximum_id)?;
+            }
+        }
+
+        // always overwrite the bounds and sensitivity. These are not something a user may configure
+        use proto::privacy_definition::Neighboring;
+        let factor: Float = match Neighboring::from_i32(privacy_definition.neighboring)
+            .ok_or_else(|| Error::from("neighboring definition must be either \"AddRemove\" or \"Substitute\""))? {
+            // a record is added to or removed from one class
+            Neighboring::AddRemove => 1.,
+            // a record may switch from one class to another
+            Neighboring::Substitute => 2.
+        };
+
+        // the class counts and each histogram are vectors with an L1 sensitivity of one edited record
+        let mut sensitivity = vec![factor];
+        if let Some(continuous_property) = &continuous_property {
+            let lower = continuous_property.lower_float()?;
+            let upper = continuous_property.upper_float()?;
+
+            // sensitivities of the per-class sum and sum of squares of each column
+            lower.iter().zip(upper.iter()).for_each(|(lower, upper)| {
+                sensitivity.push(factor * lower.abs().max(upper.abs()));
+                sensitivity.push(factor * lower.powi(2).max(upper.powi(2)));
+            });
+
+            insert_literal("lower", ndarray::arr1(&lower).into_dyn().into(), &mut maximum_id)?;
+            insert_literal("upper", ndarray::arr1(&upper).into_dyn().into(), &mut maximum_id)?;
+        }
+        insert_literal("sensitivity", ndarray::arr1(&sensitivity).into_dyn().into(), &mut maximum_id)?;
+
+        // update the privacy usage
+        if let Some(proto::component::Variant::DpNaiveBayes(variant)) = &mut naive_bayes_component.variant {
+            variant.privacy_usage = vec![self.privacy_usage[0].actual_to_effective(
+                label_property.sample_proportion.unwrap_or(1.),
+                label_property.c_stability,
+                privacy_definition.group_size)?];
+            // this case should never happen
+        } else { return Err(Error::from("Variant must be defined")) }
+        expansion.computation_graph.insert(component_id, naive_bayes_component);
+
+        Ok(expansion)
+    }
+}
+
+impl Mechanism for proto::DpNaiveBayes {
+    fn get_privacy_usage(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        release_usage: Option<&Vec<proto::PrivacyUsage>>,
+        properties: &NodeProperties
+    ) -> Result<Option<Vec<proto::PrivacyUsage>>> {
+        let label_property = properties.get::<IndexKey>(&"label".into())
+            .ok_or("label: missing")?.array()
+            .map_err(prepend("label:"))?;
+
+        Some(release_usage.unwrap_or_else(|| &self.privacy_usage).iter()
+            .map(|usage| usage.effective_to_actual(
+                label_property.sample_proportion.unwrap_or(1.),
+                label_property.c_stability,
+                privacy_definition.group_size))
+            .collect::<Result<Vec<proto::PrivacyUsage>>>()).transpose()
+    }
+}
+
+impl Named for proto::DpNaiveBayes {
+    fn get_names(
+        &self,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        argument_variables: IndexMap<base::IndexKey, Vec<IndexKey>>,
+        _release: Option<&Value>
+    ) -> Result<Vec<IndexKey>> {
+        // names of the features, in the order the model stores them
+        Ok(["data_categorical", "data_continuous"].iter()
+            .filter_map(|name| argument_variables.get::<IndexKey>(&name.to_string().into()))
+            .flatten().cloned()
+            .collect())
+    }
+}
+
+impl Report for proto::DpNaiveBayes {
+    fn summarize(
+        &self,
+        node_id: u32,
+        component: &proto::Component,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: NodeProperties,
+        release: &Value,
+        variable_names: Option<&Vec<base::IndexKey>>,
+    ) -> Result<Option<Vec<JSONRelease>>> {
+        let (label_property, categorical_property, continuous_property) = get_data_properties(&properties)?;
+
+        let num_features = categorical_property.map(|v| v.num_columns()).transpose()?.unwrap_or(0)
+            + continuous_property.map(|v| v.num_columns()).transpose()?.unwrap_or(0);
+        let variables = (0..num_features as usize)
+            .map(|column_number| variable_names
+                .and_then(|names| names.get(column_number)).cloned()
+                .unwrap_or_else(|| "[Unknown]".into()).to_string())
+            .collect::<Vec<String>>();
+
+        let release = JSONRelease {
+            description: "DP release information".to_string(),
+            statistic: "DPNaiveBayes".to_string(),
+            variables: serde_json::json!(variables),
+            release_info: value_to_json(release)?,
+            privacy_loss: serde_json::json!(self.privacy_usage.iter().map(privacy_usage_to_json).collect::<Vec<_>>()),
+            accuracy: None,
+            submission: component.submission,
+            node_id,
+            postprocess: false,
+            algorithm_info: AlgorithmInfo {
+                name: "Noisy Sufficient Statistics".to_string(),
+                cite: "Vaidya, Shafiq, Basu and Hong (2013), Differentially Private Naive Bayes Classification".to_string(),
+                mechanism: "Laplace".to_string(),
+                argument: serde_json::json!({
+                    "n": label_property.num_records
+                }),
+            },
+        };
+        Ok(Some(vec![release]))
+    }
+}
+
+/// Retrieve the properties of the label and of the optional categorical and continuous features.
+fn get_data_properties(properties: &NodeProperties) -> Result<(ArrayProperties, Option<ArrayProperties>, Option<ArrayProperties>)> {
+    let label_property: ArrayProperties = properties.get::<IndexKey>(&"label".into())
+        .ok_or("label: missing")?.array()
+        .map_err(prepend("label:"))?.clone();
+
+    label_property.assert_is_not_aggregated()?;
+    label_property.assert_non_null()?;
+    if label_property.num_columns()? != 1 {
+        return Err("label: must have exactly one column".into())
+    }
+
+    let get_feature_property = |name: &str| -> Result<Option<ArrayProperties>> {
+        let property = match properties.get::<IndexKey>(&name.into()) {
+            Some(property) => property.array().map_err(prepend(&format!("{}:", name)))?.clone(),
+            None => return Ok(None)
+        };
+        property.assert_is_not_aggregated()?;
+        property.assert_non_null()?;
+
+        if let (Some(num_records_label), Some(num_records)) = (label_property.num_records, property.num_records) {
+            if num_records_label != num_records {
+                return Err(format!("label and {}: must be same length", name).into())
+            }
+        }
+        if label_property.group_id != property.group_id {
+            return Err("data from separate partitions may not be mixed".into())
+        }
+        if label_property.dataset_id != property.dataset_id {
+            return Err(format!("label and {} must share the same dataset id", name).into())
+        }
+        // this check should be un-necessary due to the dataset id check
+        if label_property.c_stability != property.c_stability {
+            return Err(format!("label and {} must share the same stabilities", name).into())
+        }
+        Ok(Some(property))
+    };
+
+    let categorical_property = get_feature_property("data_categorical")?;
+    let continuous_property = get_feature_property("data_continuous")?;
+
+    if categorical_property.is_none() && continuous_property.is_none() {
+        return Err("at least one of data_categorical and data_continuous must be supplied".into())
+    }
+
+    if let Some(continuous_property) = &continuous_property {
+        if continuous_property.data_type != DataType::Float {
+            return Err("data_continuous: atomic type must be float".into())
+        }
+        let lower = continuous_property.lower_float().map_err(prepend("data_continuous:"))?;
+        let upper = continuous_property.upper_float().map_err(prepend("data_continuous:"))?;
+        if lower.iter().zip(upper.iter()).any(|(l, u)| l >= u) {
+            return Err("data_continuous: the lower bound of each column must be less than the upper bound".into())
+        }
+    }
+
+    Ok((label_property, categorical_property, continuous_property))
+}
@@ -0,0 +1,254 @@
+use indexmap::map::IndexMap;
+use itertools::Itertools;
+
+use crate::{base, proto, Warnable};
+use crate::base::{ArrayProperties, DataType, IndexKey, NodeProperties, SensitivitySpace, Value, ValueProperties};
+use crate::components::{Component, Expandable, Mechanism, Report, Sensitivity};
+use crate::errors::*;
+use crate::utilities::{get_literal, prepend};
+use crate::utilities::inference::infer_property;
+use crate::utilities::privacy::{get_delta, privacy_usage_check};
+use crate::utilities::json::{AlgorithmInfo, JSONRelease, privacy_usage_to_json, value_to_json};
+
+impl Component for proto::Dppca {
+    fn propagate_property(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: NodeProperties,
+        node_id: u32,
+    ) -> Result<Warnable<ValueProperties>> {
+        let privacy_definition = privacy_definition.as_ref()
+            .ok_or_else(|| "privacy_definition must be defined")?;
+
+        if privacy_definition.protect_floating_point {
+            return Err("Floating-point protections are enabled. Perturbation of the covariance matrix is susceptible to floating-point attacks.".into())
+        }
+
+        let data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        properties.get::<IndexKey>(&"sensitivity".into())
+            .ok_or("sensitivity: missing")?;
+
+        let covariance_property = get_covariance_data_property(&data_property)?;
+        let num_columns = covariance_property.num_columns()?;
+
+        if self.num_components == 0 || self.num_components as i64 > num_columns {
+            return Err(format!("num_components: must be between one and the number of columns ({})", num_columns).into())
+        }
+
+        let privacy_usage = self.privacy_usage.iter().cloned().map(Ok)
+            .fold1(|l, r| l? + r?).ok_or_else(|| "privacy_usage: must be defined")??;
+
+        let delta = get_delta(&privacy_usage)?;
+        match self.implementation.to_lowercase().as_str() {
+            "analyze-gauss" => if delta == 0. {
+                return Err("delta: must be greater than zero for the analyze-gauss implementation".into())
+            },
+            "iterative-exponential" => if delta != 0. {
+                return Err("delta: must be zero for the iterative-exponential implementation".into())
+            },
+            _ => bail!("`{}` is not recognized as a valid implementation. Must be one of [`analyze-gauss`, `iterative-exponential`]", self.implementation)
+        }
+
+        let warnings = privacy_usage_check(
+            &privacy_usage,
+            covariance_property.num_records,
+            privacy_definition.strict_parameter_checks)?;
+
+        Ok(Warnable(ValueProperties::Array(ArrayProperties {
+            num_records: Some(self.num_components as i64),
+            num_columns: Some(num_columns + 1),
+            nullity: false,
+            releasable: true,
+            c_stability: 1,
+            aggregator: None,
+            nature: None,
+            data_type: DataType::Float,
+            dataset_id: None,
+            node_id: node_id as i64,
+            is_not_empty: true,
+            dimensionality: Some(2),
+            group_id: data_property.group_id,
+            naturally_ordered: true,
+            sample_proportion: None,
+        }), warnings))
+    }
+}
+
+impl Expandable for proto::Dppca {
+    fn expand_component(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        component: &proto::Component,
+        _public_arguments: &IndexMap<IndexKey, &Value>,
+        properties: &base::NodeProperties,
+        component_id: u32,
+        mut maximum_id: u32,
+    ) -> Result<base::ComponentExpansion> {
+        let mut expansion = base::ComponentExpansion::default();
+
+        let privacy_definition = privacy_definition.as_ref()
+            .ok_or_else(|| "privacy definition must be defined")?;
+
+        let data_property: ArrayProperties = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        // first pass: aggregate the data into a covariance matrix
+        if data_property.aggregator.is_none() {
+            let id_data = *component.arguments().get::<IndexKey>(&"data".into())
+                .ok_or_else(|| Error::from("data must be provided as an argument"))?;
+
+            maximum_id += 1;
+            let id_covariance = maximum_id;
+            expansion.computation_graph.insert(id_covariance, proto::Component {
+                arguments: Some(proto::ArgumentNodeIds::new(indexmap!["data".into() => id_data])),
+                variant: Some(proto::component::Variant::Covariance(proto::Covariance {
+                    finite_sample_correction: self.finite_sample_correction
+                })),
+                omit: true,
+                submission: component.submission,
+            });
+            expansion.traversal.push(id_covariance);
+
+            let mut pca_component = component.clone();
+            pca_component.insert_argument(&"data".into(), id_covariance);
+            expansion.computation_graph.insert(component_id, pca_component);
+            return Ok(expansion)
+        }
+
+        // second pass: the data is a covariance matrix
+        get_covariance_data_property(&data_property)?;
+
+        if self.privacy_usage.len() != 1 {
+            return Err(Error::from("privacy usage must be of length one"));
+        }
+
+        let aggregator = data_property.aggregator.as_ref()
+            .ok_or_else(|| Error::from("aggregator: missing"))?;
+
+        // always overwrite sensitivity. This is not something a user may configure
+        let mut entry_sensitivities = aggregator.component.compute_sensitivity(
+            privacy_definition,
+            &aggregator.properties,
+            &SensitivitySpace::KNorm(2))?.array()?.float()?;
+
+        let lipschitz = aggregator.lipschitz_constants.clone().array()?.float()?;
+        if lipschitz.iter().any(|v| v != &1.) {
+            entry_sensitivities *= &lipschitz;
+        }
+
+        // L2 sensitivity of the upper triangle of the covariance matrix
+        let sensitivity = entry_sensitivities.iter().map(|v| v.powi(2)).sum::<f64>().sqrt();
+
+        maximum_id += 1;
+        let id_sensitivity = maximum_id;
+        let (patch_node, release) = get_literal(Value::from(sensitivity), component.submission)?;
+        expansion.computation_graph.insert(id_sensitivity, patch_node);
+        expansion.properties.insert(id_sensitivity, infer_property(&release.value, None, id_sensitivity)?);
+        expansion.releases.insert(id_sensitivity, release);
+
+        let mut noise_component = component.clone();
+        noise_component.insert_argument(&"sensitivity".into(), id_sensitivity);
+
+        // update the privacy usage
+        if let Some(proto::component::Variant::DpPca(variant)) = &mut noise_component.variant {
+            variant.privacy_usage = vec![self.privacy_usage[0].actual_to_effective(
+                data_property.sample_proportion.unwrap_or(1.),
+                data_property.c_stability,
+                privacy_definition.group_size)?];
+            // this case should never happen
+        } else { return Err(Error::from("Variant must be defined")) }
+        expansion.computation_graph.insert(component_id, noise_component);
+
+        Ok(expansion)
+    }
+}
+
+impl Mechanism for proto::Dppca {
+    fn get_privacy_usage(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        release_usage: Option<&Vec<proto::PrivacyUsage>>,
+        properties: &NodeProperties
+    ) -> Result<Option<Vec<proto::PrivacyUsage>>> {
+        let data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?;
+
+        Some(release_usage.unwrap_or(&self.privacy_usage).iter()
+            .map(|usage| usage.effective_to_actual(
+                data_property.sample_proportion.unwrap_or(1.),
+                data_property.c_stability,
+                privacy_definition.group_size))
+            .collect::<Result<Vec<proto::PrivacyUsage>>>()).transpose()
+    }
+}
+
+impl Report for proto::Dppca {
+    fn summarize(
+        &self,
+        node_id: u32,
+        component: &proto::Component,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: NodeProperties,
+        release: &Value,
+        variable_names: Option<&Vec<base::IndexKey>>,
+    ) -> Result<Option<Vec<JSONRelease>>> {
+        let data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+        let covariance_property = get_covariance_data_property(&data_property)?;
+
+        let (name, cite, mechanism) = match self.implementation.to_lowercase().as_str() {
+            "analyze-gauss" => ("Analyze Gauss", "Dwork, Talwar, Thakurta and Zhang (2014), Algorithm 1", "gaussian"),
+            "iterative-exponential" => ("Iterative Eigenvector Sampling", "Amin, Dick, Kulesza, Munoz Medina and Vassilvitskii (2019), Algorithm 1", "exponential"),
+            _ => bail!("`{}` is not recognized as a valid implementation. Must be one of [`analyze-gauss`, `iterative-exponential`]", self.implementation)
+        };
+
+        let release = JSONRelease {
+            description: "DP release information".to_string(),
+            statistic: "DPPCA".to_string(),
+            variables: serde_json::json!(variable_names.cloned()
+                .unwrap_or_else(Vec::new).iter()
+                .map(|v| v.to_string()).collect::<Vec<String>>()),
+            release_info: value_to_json(release)?,
+            privacy_loss: serde_json::json!(self.privacy_usage.iter().map(privacy_usage_to_json).collect::<Vec<_>>()),
+            accuracy: None,
+            submission: component.submission,
+            node_id,
+            postprocess: false,
+            algorithm_info: AlgorithmInfo {
+                name: name.to_string(),
+                cite: cite.to_string(),
+                mechanism: mechanism.to_string(),
+                argument: serde_json::json!({
+                    "n": covariance_property.num_records()?,
+                    "num_components": self.num_components,
+                    "constraint": {
+                        "lowerbound": covariance_property.lower_float()?,
+                        "upperbound": covariance_property.upper_float()?
+                    }
+                }),
+            },
+        };
+        Ok(Some(vec![release]))
+    }
+}
+
+/// Retrieve the properties of the data the covariance matrix was computed from.
+fn get_covariance_data_property(data_property: &ArrayProperties) -> Result<ArrayProperties> {
+    let aggregator = data_property.aggregator.as_ref()
+        .ok_or_else(|| Error::from("data: must be aggregated into a covariance matrix"))?;
+
+    if let proto::component::Variant::Covariance(_) = aggregator.component {} else {
+        return Err("data: must be aggregated into a covariance matrix".into())
+    }
+
+    Ok(aggregator.properties.get::<IndexKey>(&"data".into())
+        .ok_or("data: the covariance matrix must be computed from a single data argument")?.array()
+        .map_err(prepend("data:"))?.clone())
+}
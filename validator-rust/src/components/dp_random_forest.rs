@@ -0,0 +1,102 @@
+use indexmap::map::IndexMap;
+
+use crate::{base, proto, Warnable};
+use crate::base::{ArrayProperties, IndexKey, NodeProperties, Value, ValueProperties};
+use crate::components::{Component, Expandable, Mechanism, Report};
+use crate::components::dp_decision_tree::{expand_tree, propagate_tree, summarize_tree};
+use crate::errors::*;
+use crate::utilities::json::JSONRelease;
+use crate::utilities::prepend;
+
+impl Component for proto::DpRandomForest {
+    fn propagate_property(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: NodeProperties,
+        node_id: u32,
+    ) -> Result<Warnable<ValueProperties>> {
+        // the trees are grown on disjoint partitions, so the usage of the forest is the usage of one tree
+        propagate_tree(
+            privacy_definition, public_arguments, properties,
+            &self.privacy_usage, self.max_depth, self.num_trees, node_id)
+    }
+}
+
+impl Expandable for proto::DpRandomForest {
+    fn expand_component(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        component: &proto::Component,
+        _public_arguments: &IndexMap<IndexKey, &Value>,
+        properties: &base::NodeProperties,
+        component_id: u32,
+        maximum_id: u32,
+    ) -> Result<base::ComponentExpansion> {
+        let privacy_definition = privacy_definition.as_ref()
+            .ok_or_else(|| "privacy definition must be defined")?;
+
+        if self.privacy_usage.len() != 1 {
+            return Err(Error::from("privacy usage must be of length one"));
+        }
+
+        let label_property: ArrayProperties = properties.get::<IndexKey>(&"label".into())
+            .ok_or("label: missing")?.array()
+            .map_err(prepend("label:"))?.clone();
+
+        let (mut expansion, mut forest_component) = expand_tree(
+            privacy_definition, component, &label_property, maximum_id)?;
+
+        // update the privacy usage
+        if let Some(proto::component::Variant::DpRandomForest(variant)) = &mut forest_component.variant {
+            variant.privacy_usage = vec![self.privacy_usage[0].actual_to_effective(
+                label_property.sample_proportion.unwrap_or(1.),
+                label_property.c_stability,
+                privacy_definition.group_size)?];
+            // this case should never happen
+        } else { return Err(Error::from("Variant must be defined")) }
+        expansion.computation_graph.insert(component_id, forest_component);
+
+        Ok(expansion)
+    }
+}
+
+impl Mechanism for proto::DpRandomForest {
+    fn get_privacy_usage(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        release_usage: Option<&Vec<proto::PrivacyUsage>>,
+        properties: &NodeProperties
+    ) -> Result<Option<Vec<proto::PrivacyUsage>>> {
+        let label_property = properties.get::<IndexKey>(&"label".into())
+            .ok_or("label: missing")?.array()
+            .map_err(prepend("label:"))?;
+
+        Some(release_usage.unwrap_or_else(|| &self.privacy_usage).iter()
+            .map(|usage| usage.effective_to_actual(
+                label_property.sample_proportion.unwrap_or(1.),
+                label_property.c_stability,
+                privacy_definition.group_size))
+            .collect::<Result<Vec<proto::PrivacyUsage>>>()).transpose()
+    }
+}
+
+impl Report for proto::DpRandomForest {
+    fn summarize(
+        &self,
+        node_id: u32,
+        component: &proto::Component,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: NodeProperties,
+        release: &Value,
+        variable_names: Option<&Vec<base::IndexKey>>,
+    ) -> Result<Option<Vec<JSONRelease>>> {
+        let release = summarize_tree(
+            node_id, component, &properties, release, variable_names,
+            "DPRandomForest", &self.privacy_usage, serde_json::json!({
+                "num_trees": self.num_trees,
+                "max_depth": self.max_depth
+            }))?;
+        Ok(Some(vec![release]))
+    }
+}
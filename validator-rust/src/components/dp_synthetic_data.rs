@@ -0,0 +1,254 @@
+use indexmap::map::IndexMap;
+use itertools::Itertools;
+
+use crate::{base, proto, Warnable};
+use crate::base::{ArrayProperties, DataframeProperties, DataType, IndexKey, Jagged, Nature, NatureCategorical, NodeProperties, Value, ValueProperties};
+use crate::components::{Component, Expandable, Mechanism, Named, Report};
+use crate::errors::*;
+use crate::utilities::{get_argument, get_literal, prepend};
+use crate::utilities::inference::infer_property;
+use crate::utilities::privacy::{get_delta, privacy_usage_check};
+use crate::utilities::json::{AlgorithmInfo, JSONRelease, privacy_usage_to_json, value_to_json};
+
+/// Largest number of cells in the joint domain that the multiplicative weights distribution may span.
+const MAX_DOMAIN_SIZE: i64 = 1 << 24;
+
+impl Component for proto::DpSyntheticData {
+    fn propagate_property(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: NodeProperties,
+        node_id: u32,
+    ) -> Result<Warnable<ValueProperties>> {
+        let privacy_definition = privacy_definition.as_ref()
+            .ok_or_else(|| "privacy_definition must be defined")?;
+
+        if privacy_definition.protect_floating_point {
+            return Err("Floating-point protections are enabled. The noisy marginals are susceptible to floating-point attacks.".into())
+        }
+
+        let data_property: ArrayProperties = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        data_property.assert_is_not_aggregated()?;
+
+        if data_property.data_type == DataType::Float {
+            return Err("data: atomic type may not be float".into())
+        }
+
+        let num_columns = data_property.num_columns()?;
+
+        let names = self.get_names(public_arguments.clone(), IndexMap::new(), None)?;
+        if names.len() as i64 != num_columns {
+            return Err("names: must be the same length as the number of columns".into())
+        }
+
+        let categories = public_arguments.get::<IndexKey>(&"categories".into())
+            .ok_or_else(|| Error::from("categories: missing, must be public"))?
+            .ref_jagged().map_err(prepend("categories:"))?.clone();
+        if categories.num_columns() != num_columns {
+            return Err("categories: must be defined for each column of data".into())
+        }
+        if categories.data_type() != data_property.data_type {
+            return Err("categories: must share the same atomic type as data".into())
+        }
+
+        categories.num_records().into_iter()
+            .try_fold(1i64, |size, num_categories| size.checked_mul(num_categories)
+                .filter(|size| *size <= MAX_DOMAIN_SIZE))
+            .ok_or_else(|| Error::from(format!("categories: the joint domain may not have more than {} cells", MAX_DOMAIN_SIZE)))?;
+
+        if self.k == 0 || self.k as i64 > num_columns {
+            return Err(format!("k: must be between one and the number of columns ({})", num_columns).into())
+        }
+        if self.num_iterations == 0 {
+            return Err("num_iterations: must be greater than zero".into())
+        }
+
+        let num_records = match public_arguments.get::<IndexKey>(&"num_records".into()) {
+            Some(num_records) => {
+                let num_records = num_records.ref_array()?.first_int().map_err(prepend("num_records:"))?;
+                if num_records < 0 {
+                    return Err("num_records: must be non-negative".into())
+                }
+                Some(num_records)
+            }
+            None => None
+        };
+
+        let privacy_usage = self.privacy_usage.iter().cloned().map(Ok)
+            .fold1(|l, r| l? + r?).ok_or_else(|| "privacy_usage: must be defined")??;
+
+        if get_delta(&privacy_usage)? == 0. {
+            return Err("delta: must be greater than zero, the marginals are measured with the Gaussian mechanism".into())
+        }
+
+        let warnings = privacy_usage_check(
+            &privacy_usage,
+            data_property.num_records,
+            privacy_definition.strict_parameter_checks)?;
+
+        Ok(Warnable(ValueProperties::Dataframe(DataframeProperties {
+            children: names.into_iter().enumerate()
+                .map(|(column_number, name)| (name, ValueProperties::Array(ArrayProperties {
+                    num_records,
+                    num_columns: Some(1),
+                    nullity: false,
+                    releasable: true,
+                    c_stability: 1,
+                    aggregator: None,
+                    nature: Some(Nature::Categorical(NatureCategorical {
+                        categories: select_column(&categories, column_number)
+                    })),
+                    data_type: data_property.data_type.clone(),
+                    dataset_id: Some(node_id as i64),
+                    node_id: node_id as i64,
+                    is_not_empty: num_records.map(|v| v > 0).unwrap_or(false),
+                    dimensionality: Some(1),
+                    group_id: data_property.group_id.clone(),
+                    naturally_ordered: true,
+                    sample_proportion: None,
+                })))
+                .collect(),
+        }), warnings))
+    }
+}
+
+impl Expandable for proto::DpSyntheticData {
+    fn expand_component(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        component: &proto::Component,
+        _public_arguments: &IndexMap<IndexKey, &Value>,
+        properties: &base::NodeProperties,
+        component_id: u32,
+        mut maximum_id: u32,
+    ) -> Result<base::ComponentExpansion> {
+        let mut expansion = base::ComponentExpansion::default();
+
+        let privacy_definition = privacy_definition.as_ref()
+            .ok_or_else(|| "privacy definition must be defined")?;
+
+        if self.privacy_usage.len() != 1 {
+            return Err(Error::from("privacy usage must be of length one"));
+        }
+
+        let data_property: ArrayProperties = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        let mut synthesis_component = component.clone();
+
+        if !component.arguments().contains_key::<IndexKey>(&"categories".into()) {
+            let categories = data_property.categories()
+                .map_err(|_| Error::from("categories: must be supplied when the categories of data are not known"))?;
+
+            maximum_id += 1;
+            let id_categories = maximum_id;
+            let (patch_node, release) = get_literal(Value::Jagged(categories), component.submission)?;
+            expansion.computation_graph.insert(id_categories, patch_node);
+            expansion.properties.insert(id_categories, infer_property(&release.value, None, id_categories)?);
+            expansion.releases.insert(id_categories, release);
+            synthesis_component.insert_argument(&"categories".into(), id_categories);
+        }
+
+        // update the privacy usage
+        if let Some(proto::component::Variant::DpSyntheticData(variant)) = &mut synthesis_component.variant {
+            variant.privacy_usage = vec![self.privacy_usage[0].actual_to_effective(
+                data_property.sample_proportion.unwrap_or(1.),
+                data_property.c_stability,
+                privacy_definition.group_size)?];
+            // this case should never happen
+        } else { return Err(Error::from("Variant must be defined")) }
+        expansion.computation_graph.insert(component_id, synthesis_component);
+
+        Ok(expansion)
+    }
+}
+
+impl Mechanism for proto::DpSyntheticData {
+    fn get_privacy_usage(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        release_usage: Option<&Vec<proto::PrivacyUsage>>,
+        properties: &NodeProperties
+    ) -> Result<Option<Vec<proto::PrivacyUsage>>> {
+        let data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?;
+
+        Some(release_usage.unwrap_or_else(|| &self.privacy_usage).iter()
+            .map(|usage| usage.effective_to_actual(
+                data_property.sample_proportion.unwrap_or(1.),
+                data_property.c_stability,
+                privacy_definition.group_size))
+            .collect::<Result<Vec<proto::PrivacyUsage>>>()).transpose()
+    }
+}
+
+impl Named for proto::DpSyntheticData {
+    fn get_names(
+        &self,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
+        _argument_variables: IndexMap<base::IndexKey, Vec<IndexKey>>,
+        _release: Option<&Value>
+    ) -> Result<Vec<IndexKey>> {
+        Ok(match get_argument(&public_arguments, "names")?.ref_array()? {
+            base::Array::Str(names) =>
+                names.iter().map(|v| IndexKey::from(v.to_string())).collect(),
+            base::Array::Bool(names) =>
+                names.iter().map(|v| IndexKey::from(*v)).collect(),
+            base::Array::Int(names) =>
+                names.iter().map(|v| IndexKey::from(*v)).collect(),
+            _ => return Err("floats may not be used for column names".into())
+        })
+    }
+}
+
+impl Report for proto::DpSyntheticData {
+    fn summarize(
+        &self,
+        node_id: u32,
+        component: &proto::Component,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
+        _properties: NodeProperties,
+        release: &Value,
+        _variable_names: Option<&Vec<base::IndexKey>>,
+    ) -> Result<Option<Vec<JSONRelease>>> {
+        let names = self.get_names(public_arguments, IndexMap::new(), None)?;
+
+        let release = JSONRelease {
+            description: "DP release information".to_string(),
+            statistic: "DPSyntheticData".to_string(),
+            variables: serde_json::json!(names.iter().map(|v| v.to_string()).collect::<Vec<String>>()),
+            release_info: value_to_json(release)?,
+            privacy_loss: serde_json::json!(self.privacy_usage.iter().map(privacy_usage_to_json).collect::<Vec<_>>()),
+            accuracy: None,
+            submission: component.submission,
+            node_id,
+            postprocess: false,
+            algorithm_info: AlgorithmInfo {
+                name: "MWEM".to_string(),
+                cite: "Hardt, Ligett and McSherry (2012), A Simple and Practical Algorithm for Differentially Private Data Release".to_string(),
+                mechanism: "gaussian".to_string(),
+                argument: serde_json::json!({
+                    "k": self.k,
+                    "num_iterations": self.num_iterations
+                }),
+            },
+        };
+        Ok(Some(vec![release]))
+    }
+}
+
+/// Retrieve the categories of one column, as a single-column jagged matrix.
+fn select_column(categories: &Jagged, column_number: usize) -> Jagged {
+    match categories {
+        Jagged::Bool(categories) => Jagged::Bool(vec![categories[column_number].clone()]),
+        Jagged::Int(categories) => Jagged::Int(vec![categories[column_number].clone()]),
+        Jagged::Float(categories) => Jagged::Float(vec![categories[column_number].clone()]),
+        Jagged::Str(categories) => Jagged::Str(vec![categories[column_number].clone()]),
+    }
+}
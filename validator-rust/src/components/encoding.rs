@@ -0,0 +1,308 @@
+use std::hash::Hash;
+
+use indexmap::map::IndexMap;
+use ndarray::ArrayD;
+
+use crate::{base, proto, Warnable};
+use crate::base::{Array, ArrayProperties, DataType, IndexKey, Jagged, Nature, NatureCategorical, NatureContinuous, NodeProperties, Value, ValueProperties, Vector1DNull};
+use crate::components::{Component, Expandable};
+use crate::errors::*;
+use crate::utilities::{deduplicate, get_literal, prepend, standardize_null_target_argument};
+use crate::utilities::inference::infer_property;
+
+impl Component for proto::OneHot {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: NodeProperties,
+        _node_id: u32
+    ) -> Result<Warnable<ValueProperties>> {
+        let mut data_property = get_data_property(&properties)?;
+        let num_columns = data_property.num_columns()
+            .map_err(prepend("data:"))?;
+
+        let categories = public_arguments.get::<IndexKey>(&"categories".into())
+            .ok_or_else(|| Error::from("categories: missing, must be public"))?.ref_jagged()?
+            .clone().standardize(num_columns)
+            .map_err(prepend("categories:"))?;
+
+        if categories.data_type() != data_property.data_type {
+            return Err("data and categories must share the same data type".into())
+        }
+        if categories.num_columns() != num_columns {
+            return Err("categories: must contain one set of categories, or one set for each column of data".into())
+        }
+        if categories.num_records().contains(&0) {
+            return Err("categories: each set of categories must be non-empty".into())
+        }
+
+        // one indicator column for each category of each column
+        let num_indicators = categories.num_records().iter().sum::<i64>();
+
+        data_property.data_type = get_indicator_type(&self.atomic_type)?;
+        data_property.nature = Some(match data_property.data_type {
+            DataType::Int => Nature::Continuous(NatureContinuous {
+                lower: Vector1DNull::Int((0..num_indicators).map(|_| Some(0)).collect()),
+                upper: Vector1DNull::Int((0..num_indicators).map(|_| Some(1)).collect()),
+            }),
+            _ => Nature::Categorical(NatureCategorical {
+                categories: Jagged::Bool((0..num_indicators).map(|_| vec![false, true]).collect())
+            })
+        });
+        data_property.num_columns = Some(num_indicators);
+        data_property.dimensionality = Some(2);
+        data_property.nullity = false;
+        Ok(ValueProperties::Array(data_property).into())
+    }
+}
+
+impl Expandable for proto::OneHot {
+    /// If categories are not supplied, then use the categories of the data
+    fn expand_component(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        component: &proto::Component,
+        _public_arguments: &IndexMap<IndexKey, &Value>,
+        properties: &base::NodeProperties,
+        component_id: u32,
+        mut maximum_id: u32,
+    ) -> Result<base::ComponentExpansion> {
+        let mut component = component.clone();
+        let mut expansion = base::ComponentExpansion::default();
+
+        if !properties.contains_key::<IndexKey>(&"categories".into()) {
+            let categories = properties.get::<IndexKey>(&"data".into())
+                .ok_or("data: missing")?.array()
+                .map_err(prepend("data:"))?.categories()
+                .map_err(|_| Error::from("categories: must be supplied if the categories of the data are not known"))?;
+
+            maximum_id += 1;
+            let id_categories = maximum_id;
+            let (patch_node, release) = get_literal(Value::Jagged(categories), component.submission)?;
+            expansion.computation_graph.insert(id_categories, patch_node);
+            expansion.properties.insert(id_categories, infer_property(&release.value, None, id_categories)?);
+            expansion.releases.insert(id_categories, release);
+            component.insert_argument(&"categories".into(), id_categories);
+        }
+
+        expansion.computation_graph.insert(component_id, component);
+
+        Ok(expansion)
+    }
+}
+
+impl Component for proto::Recode {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: NodeProperties,
+        _node_id: u32
+    ) -> Result<Warnable<ValueProperties>> {
+        let mut data_property = get_data_property(&properties)?;
+        let num_columns = data_property.num_columns()
+            .map_err(prepend("data:"))?;
+
+        let categories = public_arguments.get::<IndexKey>(&"categories".into())
+            .ok_or_else(|| Error::from("categories: missing, must be public"))?.ref_jagged()?;
+        let recoded = public_arguments.get::<IndexKey>(&"recoded".into())
+            .ok_or_else(|| Error::from("recoded: missing, must be public"))?.ref_jagged()?;
+        let null_value = public_arguments.get::<IndexKey>(&"null_value".into())
+            .ok_or_else(|| Error::from("null_value: missing, must be public"))?.ref_array()?;
+
+        if categories.data_type() != data_property.data_type {
+            return Err("data and categories must share the same data type".into())
+        }
+        // categories are not deduplicated, because each category is paired with a recoded category
+        if categories.deduplicate().map_err(prepend("categories:"))?.num_records() != categories.num_records() {
+            return Err("categories: must not contain duplicates".into())
+        }
+        if standardize_columns(categories.num_records(), num_columns).map_err(prepend("categories:"))?
+            != standardize_columns(recoded.num_records(), num_columns).map_err(prepend("recoded:"))? {
+            return Err("recoded: must contain one recoded category for each category".into())
+        }
+
+        data_property.nature = Some(match (recoded, null_value) {
+            (Jagged::Float(recoded), Array::Float(null)) => {
+                let recoded = standardize_columns(recoded.clone(), num_columns)?;
+                let null = standardize_null_target_argument(null.clone(), num_columns)?;
+                if !recoded.iter().flatten().chain(null.iter()).all(|v| v.is_finite()) {
+                    return Err("recoded and null_value must be finite".into())
+                }
+                // float data may not be categorical, so the recoded values are bounded instead
+                Nature::Continuous(NatureContinuous {
+                    lower: Vector1DNull::Float(recoded.iter().zip(null.iter())
+                        .map(|(column, null)| Some(column.iter().fold(*null, |l, r| l.min(*r))))
+                        .collect()),
+                    upper: Vector1DNull::Float(recoded.iter().zip(null.iter())
+                        .map(|(column, null)| Some(column.iter().fold(*null, |l, r| l.max(*r))))
+                        .collect()),
+                })
+            }
+            (Jagged::Int(recoded), Array::Int(null)) => Nature::Categorical(NatureCategorical {
+                categories: Jagged::Int(recoded_categories(recoded.clone(), null.clone(), num_columns)?)
+            }),
+            (Jagged::Str(recoded), Array::Str(null)) => {
+                // empty strings are null, but the recoded data is not nullable
+                if recoded.iter().flatten().chain(null.iter()).any(String::is_empty) {
+                    return Err("recoded and null_value must not be empty".into())
+                }
+                Nature::Categorical(NatureCategorical {
+                    categories: Jagged::Str(recoded_categories(recoded.clone(), null.clone(), num_columns)?)
+                })
+            }
+            (Jagged::Bool(recoded), Array::Bool(null)) => Nature::Categorical(NatureCategorical {
+                categories: Jagged::Bool(recoded_categories(recoded.clone(), null.clone(), num_columns)?)
+            }),
+            _ => return Err("recoded and null_value must share the same data type".into())
+        });
+
+        data_property.data_type = recoded.data_type();
+        data_property.nullity = false;
+        Ok(ValueProperties::Array(data_property).into())
+    }
+}
+
+fn get_data_property(properties: &NodeProperties) -> Result<ArrayProperties> {
+    let data_property = properties.get::<IndexKey>(&"data".into())
+        .ok_or("data: missing")?.array()
+        .map_err(prepend("data:"))?.clone();
+
+    if !data_property.releasable {
+        data_property.assert_is_not_aggregated()?;
+    }
+
+    if data_property.data_type == DataType::Unknown {
+        return Err("data: data_type must be known".into())
+    }
+    Ok(data_property)
+}
+
+/// Atomic type of the indicator columns. One of "int" or "bool".
+fn get_indicator_type(atomic_type: &str) -> Result<DataType> {
+    Ok(match atomic_type.to_lowercase().as_str() {
+        "int" | "integer" => DataType::Int,
+        "bool" => DataType::Bool,
+        _ => bail!("atomic_type: must be one of \"int\" or \"bool\"")
+    })
+}
+
+/// Broadcast a set of values for one column across all columns.
+///
+/// Unlike categorical arguments, the values are not deduplicated, so that they stay aligned with the categories.
+fn standardize_columns<T: Clone>(values: Vec<T>, num_columns: i64) -> Result<Vec<T>> {
+    if values.len() as i64 == num_columns {
+        return Ok(values)
+    }
+    if values.len() == 1 {
+        return Ok((0..num_columns).map(|_| values[0].clone()).collect())
+    }
+    bail!("must contain one set of values, or one set for each column of data")
+}
+
+/// Categories of each column after recoding, where values outside the categories become null.
+fn recoded_categories<T: Clone + Eq + Hash>(
+    recoded: Vec<Vec<T>>, null: ArrayD<T>, num_columns: i64
+) -> Result<Vec<Vec<T>>> {
+    Ok(standardize_columns(recoded, num_columns)?.into_iter()
+        .zip(standardize_null_target_argument(null, num_columns)?)
+        .map(|(mut column, null)| {
+            column.push(null);
+            deduplicate(column)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod test_encoding {
+    use crate::base::test_data;
+    use crate::bindings::Analysis;
+    use crate::components::clamp::test_clamp;
+
+    use super::*;
+
+    fn get_array_property(analysis: &Analysis, id: u32) -> Result<ArrayProperties> {
+        Ok(analysis.properties(id)?.array()?.clone())
+    }
+
+    fn literal_jagged<T>(analysis: &mut Analysis, value: Vec<Vec<T>>) -> u32 where Jagged: From<Vec<Vec<T>>> {
+        analysis.literal().value(Value::Jagged(value.into())).value_public(true).build()
+    }
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_one_hot() {
+        // the categories of the data are "a" through "d", and the null "e"
+        let (mut analysis, data) = test_clamp::utilities::analysis_string_cat(
+            test_data::array1d_string_10_uniform(), None, None);
+        let one_hot = analysis.one_hot(data).atomic_type("int".to_string()).build();
+        let property = get_array_property(&analysis, one_hot).unwrap();
+        assert_eq!(property.data_type, DataType::Int);
+        assert_eq!(property.num_columns, Some(5));
+        assert_eq!(property.dimensionality, Some(2));
+        assert_eq!(property.lower_int().unwrap(), vec![0; 5]);
+        assert_eq!(property.upper_int().unwrap(), vec![1; 5]);
+        assert!(!property.nullity);
+
+        let categories = literal_jagged(&mut analysis, vec![strings(&["a", "b"])]);
+        let one_hot = analysis.one_hot(data).categories(categories).atomic_type("bool".to_string()).build();
+        let property = get_array_property(&analysis, one_hot).unwrap();
+        assert_eq!(property.data_type, DataType::Bool);
+        assert_eq!(property.num_columns, Some(2));
+        assert_eq!(property.categories().unwrap().bool().unwrap(), vec![vec![false, true]; 2]);
+
+        let one_hot = analysis.one_hot(data).atomic_type("float".to_string()).build();
+        assert!(analysis.properties(one_hot).is_err());
+    }
+
+    #[test]
+    fn test_recode() {
+        let (mut analysis, data) = test_clamp::utilities::analysis_string_cat(
+            test_data::array1d_string_10_uniform(), None, None);
+        let categories = literal_jagged(&mut analysis, vec![strings(&["a", "b"])]);
+
+        // values outside of the categories become the null value
+        let recoded = literal_jagged(&mut analysis, vec![vec![1, 1]]);
+        let null_value = analysis.literal().value(0.into()).value_public(true).build();
+        let recode = analysis.recode(data, categories, recoded, null_value).build();
+        let property = get_array_property(&analysis, recode).unwrap();
+        assert_eq!(property.data_type, DataType::Int);
+        assert_eq!(property.categories().unwrap().int().unwrap(), vec![vec![1, 0]]);
+        assert!(!property.nullity);
+
+        // float data is bounded by the recoded values and null value
+        let recoded = literal_jagged(&mut analysis, vec![vec![0.5, 2.]]);
+        let null_value = analysis.literal().value((-1.).into()).value_public(true).build();
+        let recode = analysis.recode(data, categories, recoded, null_value).build();
+        let property = get_array_property(&analysis, recode).unwrap();
+        assert_eq!(property.data_type, DataType::Float);
+        assert_eq!(property.lower_float().unwrap(), vec![-1.]);
+        assert_eq!(property.upper_float().unwrap(), vec![2.]);
+
+        // each category may only be recoded once
+        let duplicates = literal_jagged(&mut analysis, vec![strings(&["a", "a"])]);
+        let recode = analysis.recode(data, duplicates, recoded, null_value).build();
+        assert!(analysis.properties(recode).unwrap_err().to_string().contains("must not contain duplicates"));
+
+        // an empty string would be null
+        let recoded = literal_jagged(&mut analysis, vec![strings(&["x", ""])]);
+        let null_value = analysis.literal().value("z".to_string().into()).value_public(true).build();
+        let recode = analysis.recode(data, categories, recoded, null_value).build();
+        assert!(analysis.properties(recode).unwrap_err().to_string().contains("must not be empty"));
+
+        let recoded = literal_jagged(&mut analysis, vec![strings(&["x", "y"])]);
+        let null_value = analysis.literal().value("".to_string().into()).value_public(true).build();
+        let recode = analysis.recode(data, categories, recoded, null_value).build();
+        assert!(analysis.properties(recode).unwrap_err().to_string().contains("must not be empty"));
+
+        let null_value = analysis.literal().value("z".to_string().into()).value_public(true).build();
+        let recode = analysis.recode(data, categories, recoded, null_value).build();
+        let property = get_array_property(&analysis, recode).unwrap();
+        assert_eq!(property.categories().unwrap().string().unwrap(), vec![strings(&["x", "y", "z"])]);
+        assert!(!property.nullity);
+    }
+}
@@ -0,0 +1,186 @@
+use indexmap::map::IndexMap;
+use itertools::Itertools;
+
+use crate::{base, proto, Warnable};
+use crate::base::{Array, ArrayProperties, DataframeProperties, DataType, IndexKey, Value, ValueProperties};
+use crate::components::{Component, Expandable, Named};
+use crate::components::index::to_name_vec;
+use crate::errors::*;
+use crate::utilities::{get_argument, get_common_value, prepend};
+use crate::utilities::privacy::get_group_id_path;
+
+impl Component for proto::GroupBy {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: base::NodeProperties,
+        node_id: u32,
+    ) -> Result<Warnable<ValueProperties>> {
+        // upon expansion, data holds the aggregate of each group
+        let groups_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.partitions()
+            .map_err(prepend("data:"))?;
+
+        let names = get_column_names(get_argument(&public_arguments, "names")?)
+            .map_err(prepend("names:"))?;
+
+        let aggregate_properties = groups_property.children.values()
+            .map(|property| {
+                let property = property.array()?;
+                if !property.releasable {
+                    return Err("component: must release each group, like DPCount or DPMean".into())
+                }
+                Ok(property)
+            })
+            .collect::<Result<Vec<&ArrayProperties>>>()?;
+
+        let keys = groups_property.children.keys().map(split_key).collect::<Vec<Vec<IndexKey>>>();
+        let key_data_types = keys.first()
+            .ok_or_else(|| Error::from("by: must have at least one combination of categories"))?
+            .iter().map(|key| match key {
+                IndexKey::Str(_) => Ok(DataType::Str),
+                IndexKey::Int(_) => Ok(DataType::Int),
+                IndexKey::Bool(_) => Ok(DataType::Bool),
+                IndexKey::Tuple(_) => Err(Error::from("by: keys may not be nested"))
+            })
+            .collect::<Result<Vec<DataType>>>()?;
+
+        let num_aggregates = get_common_value(&aggregate_properties.iter()
+            .map(|property| property.num_columns()).collect::<Result<Vec<i64>>>()?)
+            .ok_or_else(|| Error::from("component: must release the same number of values for each group"))?;
+        if names.len() as i64 != key_data_types.len() as i64 + num_aggregates {
+            return Err("names: must name each key column, followed by each value the aggregator releases".into())
+        }
+        if names.iter().unique().count() != names.len() {
+            return Err("names: must be unique".into())
+        }
+
+        let aggregate_data_type = get_common_value(&aggregate_properties.iter()
+            .map(|property| property.data_type.clone()).collect())
+            .ok_or_else(|| Error::from("component: must release the same data type for each group"))?;
+
+        let group_id = get_group_id_path(aggregate_properties.iter()
+            .map(|property| property.group_id.clone())
+            .collect())?;
+
+        let num_groups = keys.len() as i64;
+        let nullity = aggregate_properties.iter().any(|property| property.nullity);
+
+        let num_keys = key_data_types.len();
+        let data_types = key_data_types.into_iter()
+            .chain(std::iter::repeat(aggregate_data_type));
+
+        Ok(ValueProperties::Dataframe(DataframeProperties {
+            children: names.into_iter().zip(data_types).enumerate()
+                .map(|(index, (name, data_type))| (name, ValueProperties::Array(ArrayProperties {
+                    num_records: Some(num_groups),
+                    num_columns: Some(1),
+                    nullity: index >= num_keys && nullity,
+                    releasable: true,
+                    c_stability: 1,
+                    aggregator: None,
+                    nature: None,
+                    data_type,
+                    dataset_id: Some(node_id as i64),
+                    node_id: node_id as i64,
+                    is_not_empty: num_groups > 0,
+                    dimensionality: Some(1),
+                    group_id: group_id.clone(),
+                    naturally_ordered: true,
+                    sample_proportion: None,
+                })))
+                .collect()
+        }).into())
+    }
+}
+
+impl Expandable for proto::GroupBy {
+    /// Partition the data by the keys, and map the aggregator over the partitions.
+    /// The GroupBy then collects the aggregates of each group into a dataframe.
+    fn expand_component(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        component: &proto::Component,
+        _public_arguments: &IndexMap<IndexKey, &Value>,
+        properties: &base::NodeProperties,
+        component_id: u32,
+        mut maximum_id: u32,
+    ) -> Result<base::ComponentExpansion> {
+        let mut expansion = base::ComponentExpansion::default();
+
+        // the groups have already been aggregated
+        if properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.partitions().is_ok() {
+            return Ok(expansion)
+        }
+
+        let aggregator = self.component.as_ref()
+            .ok_or("component: must be defined")?;
+
+        let argument_ids = component.arguments();
+        let get_id = |name: &str| argument_ids.get::<IndexKey>(&name.into()).cloned()
+            .ok_or_else(|| Error::from(format!("{}: missing from component arguments", name)));
+
+        // one partition for each combination of the categories of the keys
+        maximum_id += 1;
+        let id_partition = maximum_id;
+        expansion.computation_graph.insert(id_partition, proto::Component {
+            arguments: Some(proto::ArgumentNodeIds::new(
+                indexmap!["data".into() => get_id("data")?, "by".into() => get_id("by")?])),
+            omit: true,
+            submission: component.submission,
+            variant: Some(proto::component::Variant::Partition(proto::Partition {})),
+        });
+        expansion.traversal.push(id_partition);
+
+        maximum_id += 1;
+        let id_aggregates = maximum_id;
+        expansion.computation_graph.insert(id_aggregates, proto::Component {
+            arguments: Some(proto::ArgumentNodeIds::new(indexmap!["data".into() => id_partition])),
+            omit: true,
+            submission: component.submission,
+            variant: Some(proto::component::Variant::Map(Box::new(proto::Map {
+                component: Some(aggregator.clone())
+            }))),
+        });
+        expansion.traversal.push(id_aggregates);
+
+        // the keys are restored from the partitions, so the private key columns are dropped
+        let mut component = component.clone();
+        component.arguments = Some(proto::ArgumentNodeIds::new(
+            indexmap!["data".into() => id_aggregates, "names".into() => get_id("names")?]));
+        expansion.computation_graph.insert(component_id, component);
+
+        Ok(expansion)
+    }
+}
+
+impl Named for proto::GroupBy {
+    fn get_names(
+        &self,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
+        _argument_variables: IndexMap<base::IndexKey, Vec<IndexKey>>,
+        _release: Option<&Value>
+    ) -> Result<Vec<IndexKey>> {
+        get_column_names(get_argument(&public_arguments, "names")?)
+    }
+}
+
+/// Column names, from a scalar or vector of names.
+pub fn get_column_names(names: &Value) -> Result<Vec<IndexKey>> {
+    Ok(match names.ref_array()?.clone() {
+        Array::Str(names) => to_name_vec(names)?.into_iter().map(IndexKey::from).collect(),
+        Array::Int(names) => to_name_vec(names)?.into_iter().map(IndexKey::from).collect(),
+        Array::Bool(names) => to_name_vec(names)?.into_iter().map(IndexKey::from).collect(),
+        Array::Float(_) => return Err("floats are not valid column names".into())
+    })
+}
+
+/// The value of each key column, from the key of a partition.
+pub fn split_key(key: &IndexKey) -> Vec<IndexKey> {
+    match key {
+        IndexKey::Tuple(keys) => keys.clone(),
+        key => vec![key.clone()]
+    }
+}
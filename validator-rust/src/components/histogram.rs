@@ -226,7 +226,16 @@ impl Sensitivity for proto::Histogram {
                         .flatten()
                         .collect::<Vec<Float>>())?.into())
             },
-            _ => Err("Histogram sensitivity is only implemented for KNorm".into())
+            // used when the counts are utilities of the categories.
+            // Under either neighboring type, a single record changes each count by at most one
+            SensitivitySpace::Exponential => {
+                let categories_length = data_property.categories()?.num_records()[0];
+                let num_columns = data_property.num_columns()?;
+
+                Ok(Array::from_elem(
+                    vec![categories_length as usize, num_columns as usize], 1. as Float).into())
+            },
+            _ => Err("Histogram sensitivity is only implemented for KNorm and Exponential".into())
         }
     }
 }
@@ -169,3 +169,63 @@ pub fn to_name_vec<T: Clone>(columns: ArrayD<T>) -> Result<Vec<T>> {
         _ => Err("dimensionality of column names must be less than 2".into())
     }
 }
+
+#[cfg(test)]
+mod test_index {
+    use ndarray::arr1;
+
+    use crate::base::ArrayProperties;
+    use crate::bindings::Analysis;
+    use crate::components::sort::test_sort::utilities::*;
+
+    use super::*;
+
+    /// Select columns of an array by position only.
+    fn index_indices(analysis: &mut Analysis, data: u32, indices: Value) -> u32 {
+        let indices = analysis.literal().value(indices).value_public(true).build();
+        let index = analysis.index(data, indices, indices, indices).build();
+        analysis.components.get_mut(&index).unwrap().arguments = Some(proto::ArgumentNodeIds::new(
+            indexmap!["data".into() => data, "indices".into() => indices]));
+        index
+    }
+
+    fn get_array_property(analysis: &Analysis, id: u32) -> Result<ArrayProperties> {
+        Ok(analysis.properties(id)?.array()?.clone())
+    }
+
+    #[test]
+    fn test_index_naturally_ordered() {
+        let (mut analysis, data) = analysis_events();
+        let names = arr1(&["user".to_string(), "time".to_string()]).into_dyn();
+        let columns = index_names(&mut analysis, data, names.into());
+        let property = get_array_property(&analysis, columns).unwrap();
+        assert!(property.naturally_ordered);
+        assert_eq!(property.num_columns, Some(2));
+
+        // filtering removes rows, but does not reorder them
+        let user = column(&mut analysis, data, "user");
+        let target = analysis.literal().value("a".to_string().into()).value_public(true).build();
+        let mask = analysis.equal(user, target).build();
+        let filtered = analysis.filter(columns, mask).build();
+        let time = index_indices(&mut analysis, filtered, arr1(&[1]).into_dyn().into());
+        let property = get_array_property(&analysis, time).unwrap();
+        assert!(property.naturally_ordered);
+        assert_eq!(property.dataset_id, Some(filtered as i64));
+    }
+
+    #[test]
+    fn test_index_reordered() {
+        let (mut analysis, data) = analysis_events();
+        let sorted = sort_by(&mut analysis, data, &["time"]);
+        let names = arr1(&["user".to_string(), "time".to_string()]).into_dyn();
+        let columns = index_names(&mut analysis, sorted, names.into());
+        let property = get_array_property(&analysis, columns).unwrap();
+        assert!(!property.naturally_ordered);
+        assert_eq!(property.dataset_id, Some(sorted as i64));
+
+        let time = index_indices(&mut analysis, columns, arr1(&[1]).into_dyn().into());
+        let property = get_array_property(&analysis, time).unwrap();
+        assert!(!property.naturally_ordered);
+        assert_eq!(property.num_columns, Some(1));
+    }
+}
@@ -0,0 +1,236 @@
+use indexmap::map::IndexMap;
+use ndarray::arr0;
+
+use crate::{base, proto, Warnable};
+use crate::base::{ArrayProperties, DataframeProperties, DataType, IndexKey, Value, ValueProperties};
+use crate::components::{Component, Expandable, Named};
+use crate::errors::*;
+use crate::utilities::{get_argument, get_common_value, get_literal, prepend};
+use crate::utilities::inference::infer_property;
+
+impl Component for proto::Join {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: base::NodeProperties,
+        node_id: u32,
+    ) -> Result<Warnable<ValueProperties>> {
+        let how = self.how.to_lowercase();
+        if how != "inner" && how != "left" {
+            return Err(format!("how: unrecognized join {:?}, must be one of [inner, left]", self.how).into())
+        }
+        if self.max_matches == 0 {
+            return Err("max_matches: must be greater than zero".into())
+        }
+
+        let left_property = get_table_properties(&properties, "left")?;
+        let right_property = get_table_properties(&properties, "right")?;
+
+        let left_key = get_key(&public_arguments, "left_key")?;
+        let right_key = get_key(&public_arguments, "right_key")?;
+
+        let left_key_property = left_property.get(&left_key)
+            .ok_or_else(|| Error::from("left_key: column is not in the left table"))?;
+        let right_key_property = right_property.get(&right_key)
+            .ok_or_else(|| Error::from("right_key: column is not in the right table"))?;
+        if left_key_property.data_type != right_key_property.data_type {
+            return Err("left_key and right_key must share the same data type".into())
+        }
+        if left_key_property.data_type == DataType::Float || left_key_property.data_type == DataType::Unknown {
+            return Err("keys must be integers, strings or booleans, so that they may be compared".into())
+        }
+
+        // c-stability of each private table, or None if the table is public
+        let left_c_stability = get_c_stability(&left_property);
+        let right_c_stability = get_c_stability(&right_property);
+
+        let max_matches = self.max_matches;
+        let c_stability = match (left_c_stability, right_c_stability) {
+            (None, None) => 1,
+            // in a left join, a private right row may also replace the unmatched row of each bounded left row it matches
+            (None, Some(c_stability)) if how == "left" => 2 * max_matches * c_stability,
+            // the public table is bounded, so each private row contributes to at most max_matches rows
+            (Some(c_stability), None) | (None, Some(c_stability)) => max_matches * c_stability,
+            // a change to a private row may also displace another bounded row with the same key
+            (Some(left), Some(right)) => 2 * max_matches * (left + right),
+        };
+        let releasable = left_c_stability.is_none() && right_c_stability.is_none();
+
+        // private columns must belong to the same group
+        let group_ids = left_property.values().chain(right_property.values())
+            .filter(|property| !property.releasable)
+            .map(|property| property.group_id.clone())
+            .collect::<Vec<_>>();
+        let group_id = if group_ids.is_empty() { Vec::new() } else {
+            get_common_value(&group_ids)
+                .ok_or_else(|| Error::from("private tables must share the same group id"))?
+        };
+
+        let right_columns = right_property.into_iter()
+            .filter(|(name, _)| name != &right_key)
+            .map(|(name, mut property)| {
+                if how == "left" {
+                    if property.data_type != DataType::Float && property.data_type != DataType::Str {
+                        return Err(Error::from(format!(
+                            "{}: columns of the right table must be float or string in a left join, to represent unmatched rows",
+                            name.to_string())))
+                    }
+                    property.nullity = true;
+                }
+                Ok((name, property))
+            })
+            .collect::<Result<Vec<(IndexKey, ArrayProperties)>>>()?;
+
+        let mut children = IndexMap::new();
+        for (name, mut property) in left_property.into_iter().chain(right_columns) {
+            if children.contains_key(&name) {
+                return Err(format!("{}: column names must be unique across the joined tables", name.to_string()).into())
+            }
+            property.num_records = None;
+            property.releasable = releasable;
+            property.c_stability = c_stability;
+            property.dataset_id = Some(node_id as i64);
+            property.node_id = node_id as i64;
+            property.is_not_empty = how == "left" && property.is_not_empty;
+            property.group_id = group_id.clone();
+            property.naturally_ordered = false;
+            children.insert(name, ValueProperties::Array(property));
+        }
+
+        Ok(ValueProperties::Dataframe(DataframeProperties { children }).into())
+    }
+}
+
+impl Expandable for proto::Join {
+    /// Mark which tables must be bounded to `max_matches` rows per key.
+    /// A table is bounded when the other table is private.
+    fn expand_component(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        component: &proto::Component,
+        _public_arguments: &IndexMap<IndexKey, &Value>,
+        properties: &base::NodeProperties,
+        component_id: u32,
+        mut maximum_id: u32,
+    ) -> Result<base::ComponentExpansion> {
+        let mut expansion = base::ComponentExpansion::default();
+
+        let left_private = get_c_stability(&get_table_properties(properties, "left")?).is_some();
+        let right_private = get_c_stability(&get_table_properties(properties, "right")?).is_some();
+
+        // always overwrite the bounds. This is not something a user may configure
+        let mut component = component.clone();
+        for (name, bounded) in &[("bound_left", right_private), ("bound_right", left_private)] {
+            maximum_id += 1;
+            let id_bound = maximum_id;
+            let (patch_node, release) = get_literal(arr0(*bounded).into_dyn().into(), component.submission)?;
+            expansion.computation_graph.insert(id_bound, patch_node);
+            expansion.properties.insert(id_bound, infer_property(&release.value, None, id_bound)?);
+            expansion.releases.insert(id_bound, release);
+            component.insert_argument(&(*name).into(), id_bound);
+        }
+        expansion.computation_graph.insert(component_id, component);
+
+        Ok(expansion)
+    }
+}
+
+impl Named for proto::Join {
+    fn get_names(
+        &self,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
+        argument_variables: IndexMap<base::IndexKey, Vec<IndexKey>>,
+        _release: Option<&Value>
+    ) -> Result<Vec<IndexKey>> {
+        let right_key = get_key(&public_arguments, "right_key")?;
+
+        let left_names = argument_variables.get::<IndexKey>(&"left".into())
+            .ok_or_else(|| Error::from("column names on left must be known"))?;
+        let right_names = argument_variables.get::<IndexKey>(&"right".into())
+            .ok_or_else(|| Error::from("column names on right must be known"))?;
+
+        Ok(left_names.iter().cloned()
+            .chain(right_names.iter().filter(|name| *name != &right_key).cloned())
+            .collect())
+    }
+}
+
+/// Properties of each column of a table.
+fn get_table_properties(properties: &base::NodeProperties, name: &str) -> Result<IndexMap<IndexKey, ArrayProperties>> {
+    properties.get::<IndexKey>(&name.into())
+        .ok_or_else(|| Error::from(format!("{}: missing", name)))?.dataframe()
+        .map_err(prepend(&format!("{}:", name)))?
+        .children.iter()
+        .map(|(column_name, property)| {
+            let property = property.array().map_err(prepend(&format!("{}:", name)))?;
+            property.assert_is_not_aggregated()?;
+            if property.sample_proportion.is_some() {
+                return Err(format!("{}: cannot currently join sampled data", name).into())
+            }
+            Ok((column_name.clone(), property.clone()))
+        })
+        .collect()
+}
+
+/// Name of a key column.
+fn get_key(public_arguments: &IndexMap<IndexKey, &Value>, name: &str) -> Result<IndexKey> {
+    IndexKey::new(get_argument(public_arguments, name)?.ref_array()?.clone())
+        .map_err(prepend(&format!("{}:", name)))
+}
+
+/// The largest c-stability of the private columns of a table, or None if the table is public.
+fn get_c_stability(table_property: &IndexMap<IndexKey, ArrayProperties>) -> Option<u32> {
+    table_property.values()
+        .filter(|property| !property.releasable)
+        .map(|property| property.c_stability)
+        .max()
+}
+
+#[cfg(test)]
+mod test_join {
+    use ndarray::arr1;
+
+    use crate::bindings::Analysis;
+
+    use super::*;
+
+    /// Join a table of users with a table of events, each of which may be private.
+    fn join_c_stability(how: &str, users_private: bool, events_private: bool) -> Result<u32> {
+        let mut analysis = Analysis::new();
+        let mut materialize = |names: &[&str], file_path: &str, private: bool| {
+            let names = analysis.literal()
+                .value(arr1(names).mapv(String::from).into_dyn().into())
+                .value_public(true).build();
+            analysis.materialize(names, file_path.to_string()).public(!private).build()
+        };
+        let users = materialize(&["user", "age"], "users.csv", users_private);
+        let events = materialize(&["event_user", "duration"], "events.csv", events_private);
+
+        let left_key = analysis.literal().value("user".to_string().into()).value_public(true).build();
+        let right_key = analysis.literal().value("event_user".to_string().into()).value_public(true).build();
+        let joined = analysis.join(users, events, left_key, right_key)
+            .how(how.to_string())
+            .max_matches(2)
+            .build();
+
+        let children = analysis.properties(joined)?.dataframe()?.children.clone();
+        assert_eq!(children.keys().cloned().collect::<Vec<IndexKey>>(),
+                   vec!["user".into(), "age".into(), "duration".into()]);
+        let c_stabilities = children.values()
+            .map(|property| Ok(property.array()?.c_stability))
+            .collect::<Result<Vec<u32>>>()?;
+        get_common_value(&c_stabilities).ok_or_else(|| "c-stabilities must be equal".into())
+    }
+
+    #[test]
+    fn test_join_c_stability() {
+        assert_eq!(join_c_stability("inner", false, false).unwrap(), 1);
+        assert_eq!(join_c_stability("inner", true, false).unwrap(), 2);
+        assert_eq!(join_c_stability("inner", false, true).unwrap(), 2);
+        assert_eq!(join_c_stability("left", true, false).unwrap(), 2);
+        // a private right row may also replace the unmatched rows of the left rows it matches
+        assert_eq!(join_c_stability("left", false, true).unwrap(), 4);
+        assert_eq!(join_c_stability("left", true, true).unwrap(), 8);
+    }
+}
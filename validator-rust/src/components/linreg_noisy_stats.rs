@@ -0,0 +1,323 @@
+use indexmap::map::IndexMap;
+use itertools::Itertools;
+
+use crate::{base, proto, Warnable};
+use crate::base::{ArrayProperties, DataType, IndexKey, NodeProperties, Value, ValueProperties};
+use crate::components::{Component, Expandable, Mechanism, Named, Report};
+use crate::errors::*;
+use crate::utilities::{get_literal, prepend};
+use crate::utilities::inference::infer_property;
+use crate::utilities::privacy::{get_delta, privacy_usage_check};
+use crate::utilities::json::{AlgorithmInfo, JSONRelease, privacy_usage_to_json, value_to_json};
+use crate::Float;
+
+impl Component for proto::LinregNoisyStats {
+    fn propagate_property(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: NodeProperties,
+        node_id: u32,
+    ) -> Result<Warnable<ValueProperties>> {
+        let privacy_definition = privacy_definition.as_ref()
+            .ok_or_else(|| "privacy_definition must be defined")?;
+
+        if privacy_definition.protect_floating_point {
+            return Err("Floating-point protections are enabled. Perturbation of the sufficient statistics is susceptible to floating-point attacks.".into())
+        }
+
+        let (data_property_x, _) = get_data_properties(&properties)?;
+
+        properties.get::<IndexKey>(&"sensitivity".into())
+            .ok_or("sensitivity: missing")?;
+
+        let num_columns = data_property_x.num_columns()?;
+
+        if self.regularization.is_nan() || self.regularization < 0. {
+            return Err("regularization: must be non-negative".into())
+        }
+
+        let privacy_usage = self.privacy_usage.iter().cloned().map(Ok)
+            .fold1(|l, r| l? + r?).ok_or_else(|| "privacy_usage: must be defined")??;
+
+        let delta = get_delta(&privacy_usage)?;
+        match self.mechanism.to_lowercase().as_str() {
+            "laplace" => if delta != 0. {
+                return Err("delta: must be zero for the Laplace mechanism".into())
+            },
+            "gaussian" | "analyticgaussian" => if delta == 0. {
+                return Err("delta: must be greater than zero for the Gaussian mechanism".into())
+            },
+            _ => bail!("`{}` is not recognized as a valid mechanism. Must be one of [`Laplace`, `Gaussian`, `AnalyticGaussian`]", self.mechanism)
+        }
+
+        let warnings = privacy_usage_check(
+            &privacy_usage,
+            data_property_x.num_records,
+            privacy_definition.strict_parameter_checks)?;
+
+        Ok(Warnable(ValueProperties::Array(ArrayProperties {
+            num_records: Some(1),
+            num_columns: Some(num_columns + 1),
+            nullity: false,
+            releasable: true,
+            c_stability: 1,
+            aggregator: None,
+            nature: None,
+            data_type: DataType::Float,
+            dataset_id: None,
+            node_id: node_id as i64,
+            is_not_empty: true,
+            dimensionality: Some(2),
+            group_id: data_property_x.group_id,
+            naturally_ordered: true,
+            sample_proportion: None,
+        }), warnings))
+    }
+}
+
+impl Expandable for proto::LinregNoisyStats {
+    fn expand_component(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        component: &proto::Component,
+        _public_arguments: &IndexMap<IndexKey, &Value>,
+        properties: &base::NodeProperties,
+        component_id: u32,
+        mut maximum_id: u32,
+    ) -> Result<base::ComponentExpansion> {
+        let mut expansion = base::ComponentExpansion::default();
+
+        let privacy_definition = privacy_definition.as_ref()
+            .ok_or_else(|| "privacy definition must be defined")?;
+
+        if self.privacy_usage.len() != 1 {
+            return Err(Error::from("privacy usage must be of length one"));
+        }
+
+        let (data_property_x, data_property_y) = get_data_properties(properties)?;
+
+        let mechanism = match self.mechanism.to_lowercase().as_str() {
+            "automatic" => if get_delta(&self.privacy_usage[0])? == 0. {
+                "laplace"
+            } else {
+                "gaussian"
+            },
+            mechanism => mechanism
+        }.to_string();
+
+        // always overwrite sensitivity. This is not something a user may configure
+        let sensitivity = compute_sensitivity(
+            privacy_definition, &data_property_x, &data_property_y, &mechanism)?;
+
+        maximum_id += 1;
+        let id_sensitivity = maximum_id;
+        let (patch_node, release) = get_literal(Value::from(sensitivity), component.submission)?;
+        expansion.computation_graph.insert(id_sensitivity, patch_node);
+        expansion.properties.insert(id_sensitivity, infer_property(&release.value, None, id_sensitivity)?);
+        expansion.releases.insert(id_sensitivity, release);
+
+        let mut noise_component = component.clone();
+        noise_component.insert_argument(&"sensitivity".into(), id_sensitivity);
+
+        // update the mechanism and privacy usage
+        if let Some(proto::component::Variant::LinregNoisyStats(variant)) = &mut noise_component.variant {
+            variant.mechanism = mechanism;
+            variant.privacy_usage = vec![self.privacy_usage[0].actual_to_effective(
+                data_property_x.sample_proportion.unwrap_or(1.),
+                data_property_x.c_stability,
+                privacy_definition.group_size)?];
+            // this case should never happen
+        } else { return Err(Error::from("Variant must be defined")) }
+        expansion.computation_graph.insert(component_id, noise_component);
+
+        Ok(expansion)
+    }
+}
+
+impl Mechanism for proto::LinregNoisyStats {
+    fn get_privacy_usage(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        release_usage: Option<&Vec<proto::PrivacyUsage>>,
+        properties: &NodeProperties
+    ) -> Result<Option<Vec<proto::PrivacyUsage>>> {
+        let data_property_x = properties.get::<IndexKey>(&"data_x".into())
+            .ok_or("data_x: missing")?.array()
+            .map_err(prepend("data_x:"))?;
+
+        Some(release_usage.unwrap_or_else(|| &self.privacy_usage).iter()
+            .map(|usage| usage.effective_to_actual(
+                data_property_x.sample_proportion.unwrap_or(1.),
+                data_property_x.c_stability,
+                privacy_definition.group_size))
+            .collect::<Result<Vec<proto::PrivacyUsage>>>()).transpose()
+    }
+}
+
+impl Named for proto::LinregNoisyStats {
+    fn get_names(
+        &self,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        argument_variables: IndexMap<base::IndexKey, Vec<IndexKey>>,
+        _release: Option<&Value>
+    ) -> Result<Vec<IndexKey>> {
+        // one coefficient is released for each predictor, followed by the intercept
+        let mut names = argument_variables.get(&IndexKey::from("data_x")).cloned()
+            .ok_or_else(|| Error::from("data_x: variable names are missing"))?;
+        names.push("intercept".into());
+        Ok(names)
+    }
+}
+
+impl Report for proto::LinregNoisyStats {
+    fn summarize(
+        &self,
+        node_id: u32,
+        component: &proto::Component,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: NodeProperties,
+        release: &Value,
+        variable_names: Option<&Vec<base::IndexKey>>,
+    ) -> Result<Option<Vec<JSONRelease>>> {
+        let (data_property_x, data_property_y) = get_data_properties(&properties)?;
+
+        let mut variables = (0..data_property_x.num_columns()? as usize)
+            .map(|column_number| variable_names
+                .and_then(|names| names.get(column_number)).cloned()
+                .unwrap_or_else(|| "[Unknown]".into()).to_string())
+            .collect::<Vec<String>>();
+        variables.push("intercept".to_string());
+
+        let release = JSONRelease {
+            description: "DP release information".to_string(),
+            statistic: "DPLinearRegression".to_string(),
+            variables: serde_json::json!(variables),
+            release_info: value_to_json(release)?,
+            privacy_loss: serde_json::json!(self.privacy_usage.iter().map(privacy_usage_to_json).collect::<Vec<_>>()),
+            accuracy: None,
+            submission: component.submission,
+            node_id,
+            postprocess: false,
+            algorithm_info: AlgorithmInfo {
+                name: "Sufficient Statistics Perturbation".to_string(),
+                cite: "Wang (2018), Revisiting differentially private linear regression".to_string(),
+                mechanism: self.mechanism.clone(),
+                argument: serde_json::json!({
+                    "regularization": self.regularization,
+                    "constraint": {
+                        "lower_x": data_property_x.lower_float()?,
+                        "upper_x": data_property_x.upper_float()?,
+                        "lower_y": data_property_y.lower_float()?[0],
+                        "upper_y": data_property_y.upper_float()?[0],
+                    }
+                }),
+            },
+        };
+        Ok(Some(vec![release]))
+    }
+}
+
+fn get_data_properties(properties: &NodeProperties) -> Result<(ArrayProperties, ArrayProperties)> {
+    let data_property_x: ArrayProperties = properties.get::<IndexKey>(&"data_x".into())
+        .ok_or("data_x: missing")?.array()
+        .map_err(prepend("data_x:"))?.clone();
+
+    let data_property_y: ArrayProperties = properties.get::<IndexKey>(&"data_y".into())
+        .ok_or("data_y: missing")?.array()
+        .map_err(prepend("data_y:"))?.clone();
+
+    data_property_x.assert_is_not_aggregated()?;
+    data_property_y.assert_is_not_aggregated()?;
+    data_property_x.assert_non_null()?;
+    data_property_y.assert_non_null()?;
+    data_property_x.assert_is_not_empty()?;
+
+    if data_property_x.data_type != DataType::Float {
+        return Err("data_x: atomic type must be float".into());
+    }
+    if data_property_y.data_type != DataType::Float {
+        return Err("data_y: atomic type must be float".into());
+    }
+    if data_property_y.num_columns()? != 1 {
+        return Err("data_y: must have exactly one column".into());
+    }
+
+    if let (Some(num_records_x), Some(num_records_y)) = (data_property_x.num_records, data_property_y.num_records) {
+        if num_records_x != num_records_y {
+            return Err("data_x and data_y: must be same length".into());
+        }
+    }
+
+    if data_property_x.group_id != data_property_y.group_id {
+        return Err("data from separate partitions may not be mixed".into())
+    }
+    if data_property_x.dataset_id != data_property_y.dataset_id {
+        return Err("data_x and data_y must share the same dataset id".into())
+    }
+    // this check should be un-necessary due to the dataset id check
+    if data_property_x.c_stability != data_property_y.c_stability {
+        return Err(Error::from("data_x and data_y must share the same stabilities"))
+    }
+
+    Ok((data_property_x, data_property_y))
+}
+
+/// Compute the sensitivity of the upper triangle of XᵀX and of Xᵀy.
+///
+/// X is augmented with a constant column of ones, for the intercept.
+/// Each released entry is a sum of products of two bounded columns,
+/// and the per-entry sensitivities are combined in the norm of the mechanism.
+fn compute_sensitivity(
+    privacy_definition: &proto::PrivacyDefinition,
+    data_property_x: &ArrayProperties,
+    data_property_y: &ArrayProperties,
+    mechanism: &str,
+) -> Result<Float> {
+    let mut bounds_x = data_property_x.lower_float().map_err(prepend("data_x:"))?.into_iter()
+        .zip(data_property_x.upper_float().map_err(prepend("data_x:"))?)
+        .collect::<Vec<(Float, Float)>>();
+    bounds_x.push((1., 1.));
+
+    let bounds_y = (
+        data_property_y.lower_float().map_err(prepend("data_y:"))?[0],
+        data_property_y.upper_float().map_err(prepend("data_y:"))?[0]);
+
+    // bounds on the products of each pair of columns
+    let mut product_bounds = Vec::new();
+    for j in 0..bounds_x.len() {
+        product_bounds.push(square_bounds(bounds_x[j]));
+        for k in j + 1..bounds_x.len() {
+            product_bounds.push(product_bounds_of(bounds_x[j], bounds_x[k]));
+        }
+        product_bounds.push(product_bounds_of(bounds_x[j], bounds_y));
+    }
+
+    use proto::privacy_definition::Neighboring;
+    let neighboring_type = Neighboring::from_i32(privacy_definition.neighboring)
+        .ok_or_else(|| Error::from("neighboring definition must be either \"AddRemove\" or \"Substitute\""))?;
+
+    let entry_sensitivities = product_bounds.into_iter()
+        .map(|(lower, upper)| match neighboring_type {
+            Neighboring::AddRemove => lower.abs().max(upper.abs()),
+            Neighboring::Substitute => upper - lower
+        });
+
+    Ok(match mechanism {
+        "laplace" => entry_sensitivities.sum::<Float>(),
+        "gaussian" | "analyticgaussian" => entry_sensitivities.map(|v| v.powi(2)).sum::<Float>().sqrt(),
+        _ => bail!("`{}` is not recognized as a valid mechanism. Must be one of [`Laplace`, `Gaussian`, `AnalyticGaussian`]", mechanism)
+    })
+}
+
+fn square_bounds((lower, upper): (Float, Float)) -> (Float, Float) {
+    let max = lower.powi(2).max(upper.powi(2));
+    let min = if lower <= 0. && upper >= 0. { 0. } else { lower.powi(2).min(upper.powi(2)) };
+    (min, max)
+}
+
+fn product_bounds_of((lower_a, upper_a): (Float, Float), (lower_b, upper_b): (Float, Float)) -> (Float, Float) {
+    let corners = [lower_a * lower_b, lower_a * upper_b, upper_a * lower_b, upper_a * upper_b];
+    (corners.iter().cloned().fold(Float::INFINITY, Float::min),
+     corners.iter().cloned().fold(Float::NEG_INFINITY, Float::max))
+}
@@ -10,36 +10,61 @@
 use crate::errors::*;
 
 
-mod transforms;
-//mod bin;
+pub mod transforms;
+pub mod bin;
 mod cast;
 mod clamp;
 mod count;
 mod covariance;
 mod column_bind;
+mod conditional;
+mod datetime;
 mod digitize;
 mod dp_count;
 mod dp_variance;
+mod dp_chi_square_test;
+mod dp_confidence_interval;
+mod dp_correlation;
 mod dp_covariance;
+mod dp_decision_tree;
+mod dp_empirical_cdf;
+mod dp_goodness_of_fit;
 mod dp_gumbel_median;
 mod dp_histogram;
+mod dp_k_means;
+mod dp_kernel_density;
 mod dp_linear_regression;
+mod dp_logistic_regression;
+mod dp_marginals;
 mod dp_maximum;
 mod dp_median;
 mod dp_minimum;
+mod dp_mode;
+mod dp_naive_bayes;
+mod dp_pca;
 mod dp_mean;
 mod dp_quantile;
+mod dp_quantile_regression;
+mod dp_random_forest;
 mod dp_raw_moment;
 mod dp_sum;
+mod dp_synthetic_data;
+mod encoding;
 mod filter;
+pub mod group_by;
 mod histogram;
 mod impute;
+mod join;
 pub mod index;
 mod raw_moment;
+mod linreg_noisy_stats;
 mod literal;
 mod map;
 mod materialize;
+mod nulls;
 pub mod partition;
+mod predict;
+mod projection;
 mod quantile;
 mod reshape;
 mod mean;
@@ -49,6 +74,7 @@ mod laplace_mechanism;
 mod simple_geometric_mechanism;
 pub mod snapping_mechanism;
 mod resize;
+pub mod sort;
 mod theil_sen;
 mod to_dataframe;
 mod sum;
@@ -261,15 +287,21 @@ impl Component for proto::Component {
 
         propagate_property!(
             // INSERT COMPONENT LIST
-            Cast, Clamp, ColumnBind, Count, Covariance, Digitize,
-            Filter, Histogram, Impute, Index, Literal, Materialize, Mean,
-            Partition, Quantile, RawMoment, Reshape, Resize, Sum, ToDataframe, Union, Variance,
+            Bin, CaseWhen, Cast, Clamp, Coalesce, ColumnBind, Count, Covariance, DatetimeExtract, DatetimeTrunc, Digitize,
+            DropNull, FillNull, Filter, GroupBy, Histogram, IfElse, Impute, Index, IsNull, Join, Literal, Materialize, Mean,
+            OneHot, ParseDatetime, Partition, Quantile, RawMoment, Recode, Reshape, Resize, Sort, Sum, ToDataframe,
+            Union, Variance,
 
             ExponentialMechanism, GaussianMechanism, LaplaceMechanism,
             SimpleGeometricMechanism, SnappingMechanism,
 
-            Abs, Add, LogicalAnd, Divide, Equal, GreaterThan, LessThan, Log, Modulo, Multiply,
-            Negate, Negative, LogicalOr, Power, RowMax, RowMin, Subtract, TheilSen, DpGumbelMedian
+            Abs, Add, LogicalAnd, Ceil, Concat, Cos, Divide, Equal, Exp, Floor, GreaterThan, LessThan, Log, Lower,
+            Modulo, Multiply, Negate, Negative, LogicalOr, Power, RegexExtract, RegexMatch, Round, RowMax, RowMin,
+            Sign, Sin, Sqrt, Subtract, Substring, Tan, Trim, TheilSen, DpGumbelMedian,
+            DpLogisticRegression, LinregNoisyStats, DpPca, Projection, DpKMeans, DpMarginals,
+            DpSyntheticData, DpChiSquareTest, DpGoodnessOfFit, DpConfidenceInterval, DpCorrelation,
+            DpNaiveBayes, Predict, DpDecisionTree, DpRandomForest, DpQuantileRegression, DpEmpiricalCdf,
+            DpKernelDensity, DpMode
         );
 
         Err(format!("proto component {:?} is missing its Component trait", variant).into())
@@ -319,7 +351,7 @@ impl Expandable for proto::Component {
         }
 
         // list all components that accept partitioned data as arguments
-        expand_component!(Map);
+        expand_component!(Map, GroupBy);
 
         if properties.values().any(|props| props.partitions().is_ok()) {
             let mut component_expansion = base::ComponentExpansion::default();
@@ -337,13 +369,17 @@ impl Expandable for proto::Component {
 
         expand_component!(
             // INSERT COMPONENT LIST
-            Clamp, Digitize, Histogram, Impute, Map, Maximum, Median, Minimum, Partition, Resize,
+            Bin, Clamp, Digitize, Histogram, Impute, Join, Map, Maximum, Median, Minimum, OneHot, Partition, Resize,
+            Sort,
 
-            DpCount, DpCovariance, DpHistogram, DpLinearRegression, DpMaximum, DpMean, DpMedian,
-            DpMinimum, DpQuantile, DpRawMoment, DpSum, DpVariance,
+            DpCount, DpCovariance, DpHistogram, DpLinearRegression, DpLogisticRegression, DpMaximum,
+            DpMean, DpMedian, DpMinimum, DpQuantile, DpRawMoment, DpSum, DpVariance,
 
             ExponentialMechanism, GaussianMechanism, LaplaceMechanism,
-            SimpleGeometricMechanism, SnappingMechanism, DpGumbelMedian,
+            SimpleGeometricMechanism, SnappingMechanism, DpGumbelMedian, LinregNoisyStats, DpPca, DpKMeans,
+            DpMarginals, DpSyntheticData, DpChiSquareTest, DpGoodnessOfFit, DpConfidenceInterval,
+            DpCorrelation, DpNaiveBayes, DpDecisionTree, DpRandomForest, DpQuantileRegression,
+            DpEmpiricalCdf, DpKernelDensity, DpMode,
 
             ToBool, ToFloat, ToInt, ToString
         );
@@ -380,7 +416,10 @@ impl Mechanism for proto::Component {
         get_privacy_usage!(
             // INSERT COMPONENT LIST
             ExponentialMechanism, GaussianMechanism, LaplaceMechanism,
-            SimpleGeometricMechanism, SnappingMechanism
+            SimpleGeometricMechanism, SnappingMechanism, DpLogisticRegression, LinregNoisyStats, DpPca,
+            DpKMeans, DpMarginals, DpSyntheticData, DpChiSquareTest, DpGoodnessOfFit,
+            DpConfidenceInterval, DpCorrelation, DpNaiveBayes, DpDecisionTree, DpRandomForest,
+            DpQuantileRegression, DpEmpiricalCdf, DpKernelDensity, DpMode
         );
 
         Ok(None)
@@ -452,7 +491,9 @@ impl Accuracy for proto::Component {
              LaplaceMechanism,
              GaussianMechanism,
              SimpleGeometricMechanism,
-             SnappingMechanism
+             SnappingMechanism,
+             DpEmpiricalCdf,
+             DpKernelDensity
         );
 
         Ok(None)
@@ -489,7 +530,9 @@ impl Accuracy for proto::Component {
             LaplaceMechanism,
             GaussianMechanism,
             SimpleGeometricMechanism,
-            SnappingMechanism
+            SnappingMechanism,
+            DpEmpiricalCdf,
+            DpKernelDensity
         );
 
         Ok(None)
@@ -528,8 +571,11 @@ impl Report for proto::Component {
 
         summarize!(
             // INSERT COMPONENT LIST
-            DpCount, DpCovariance, DpHistogram, DpMaximum, DpMean, DpMinimum, DpQuantile,
-            DpRawMoment, DpSum, DpVariance
+            DpCount, DpCovariance, DpHistogram, DpLogisticRegression, DpMaximum, DpMean,
+            DpMinimum, DpQuantile, DpRawMoment, DpSum, DpVariance, LinregNoisyStats, DpPca,
+            DpKMeans, DpMarginals, DpSyntheticData, DpChiSquareTest, DpGoodnessOfFit,
+            DpConfidenceInterval, DpCorrelation, DpNaiveBayes, DpDecisionTree, DpRandomForest,
+            DpQuantileRegression, DpEmpiricalCdf, DpKernelDensity, DpMode
         );
 
         Ok(None)
@@ -565,7 +611,8 @@ impl Named for proto::Component {
         // TODO: transforms, covariance/cross-covariance, extended indexing, columnbind
         get_names!(
             // INSERT COMPONENT LIST
-            ToDataframe, Index, Literal, Materialize
+            ToDataframe, GroupBy, Index, Join, Literal, Materialize, DpLogisticRegression, LinregNoisyStats,
+            DpSyntheticData, DpNaiveBayes, DpQuantileRegression
         );
 
         // default implementation
@@ -0,0 +1,290 @@
+use indexmap::map::IndexMap;
+
+use crate::{base, proto, Warnable};
+use crate::base::{Array, ArrayProperties, DataType, IndexKey, Jagged, Nature, NatureCategorical, NatureContinuous, NodeProperties, Value, ValueProperties, Vector1DNull};
+use crate::components::Component;
+use crate::components::transforms::propagate_union_property;
+use crate::errors::*;
+use crate::utilities::{prepend, standardize_null_target_argument};
+
+impl Component for proto::IsNull {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: NodeProperties,
+        _node_id: u32
+    ) -> Result<Warnable<ValueProperties>> {
+        let mut data_property = get_data_property(&properties)?;
+        let num_columns = data_property.num_columns()
+            .map_err(prepend("data:"))?;
+
+        // only floats and strings have a representation for null, and only nullable data contains nulls
+        let categories = match data_property.data_type {
+            DataType::Float | DataType::Str if data_property.nullity => vec![false, true],
+            _ => vec![false]
+        };
+
+        data_property.nature = Some(Nature::Categorical(NatureCategorical {
+            categories: Jagged::Bool((0..num_columns).map(|_| categories.clone()).collect())
+        }));
+        data_property.data_type = DataType::Bool;
+        data_property.nullity = false;
+        Ok(ValueProperties::Array(data_property).into())
+    }
+}
+
+impl Component for proto::Coalesce {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: NodeProperties,
+        node_id: u32
+    ) -> Result<Warnable<ValueProperties>> {
+        let left_property: ArrayProperties = properties.get(&IndexKey::from("left"))
+            .ok_or("left: missing")?.array()
+            .map_err(prepend("left:"))?.clone();
+        let right_property: ArrayProperties = properties.get::<IndexKey>(&"right".into())
+            .ok_or("right: missing")?.array()
+            .map_err(prepend("right:"))?.clone();
+
+        // if the left argument is public, the values belong to the dataset of the right argument
+        let mut property = propagate_union_property(&left_property, &right_property, node_id)?;
+
+        // a value is only null if it is null in both arguments
+        property.nullity = left_property.nullity && right_property.nullity;
+        Ok(ValueProperties::Array(property).into())
+    }
+}
+
+impl Component for proto::FillNull {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: NodeProperties,
+        _node_id: u32
+    ) -> Result<Warnable<ValueProperties>> {
+        let mut data_property = get_data_property(&properties)?;
+        let num_columns = data_property.num_columns()
+            .map_err(prepend("data:"))?;
+
+        let value = public_arguments.get::<IndexKey>(&"fill_value".into())
+            .ok_or_else(|| Error::from("fill_value: missing, must be public"))?.ref_array()?;
+
+        data_property.nature = match (&data_property.data_type, value) {
+            (DataType::Float, Array::Float(value)) => {
+                let value = standardize_null_target_argument(value.clone(), num_columns)?;
+                if !value.iter().all(|v| v.is_finite()) {
+                    return Err("fill_value: must be finite".into())
+                }
+                match &data_property.nature {
+                    Some(Nature::Continuous(_)) => Some(Nature::Continuous(NatureContinuous {
+                        lower: Vector1DNull::Float(data_property.lower_float_option()?.into_iter()
+                            .zip(value.iter())
+                            .map(|(lower, value)| lower.map(|lower| lower.min(*value)))
+                            .collect()),
+                        upper: Vector1DNull::Float(data_property.upper_float_option()?.into_iter()
+                            .zip(value.iter())
+                            .map(|(upper, value)| upper.map(|upper| upper.max(*value)))
+                            .collect()),
+                    })),
+                    _ => None
+                }
+            }
+            (DataType::Str, Array::Str(value)) => {
+                let value = standardize_null_target_argument(value.clone(), num_columns)?;
+                if value.iter().any(String::is_empty) {
+                    return Err("fill_value: must not be empty".into())
+                }
+                match &data_property.nature {
+                    Some(Nature::Categorical(nature)) => Some(Nature::Categorical(NatureCategorical {
+                        categories: Jagged::Str(nature.categories.clone().standardize(num_columns)?
+                            .string()?.into_iter()
+                            .zip(value)
+                            .map(|(categories, value)| categories.into_iter()
+                                .filter(|category| !category.is_empty())
+                                .chain(std::iter::once(value))
+                                .collect())
+                            .collect::<Vec<Vec<String>>>()).deduplicate()?
+                    })),
+                    _ => None
+                }
+            }
+            // integers and booleans have no representation for null
+            (DataType::Int, Array::Int(_)) | (DataType::Bool, Array::Bool(_)) =>
+                data_property.nature.clone(),
+            _ => return Err("data and fill_value must share the same data type".into())
+        };
+
+        data_property.nullity = false;
+        Ok(ValueProperties::Array(data_property).into())
+    }
+}
+
+impl Component for proto::DropNull {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: NodeProperties,
+        node_id: u32
+    ) -> Result<Warnable<ValueProperties>> {
+        let mut data_property = get_data_property(&properties)?;
+
+        // the number of records is not known after dropping rows
+        data_property.num_records = None;
+
+        // This exists to prevent binary ops on non-conformable arrays from being approved
+        data_property.dataset_id = Some(node_id as i64);
+
+        // no longer know if the data has a nonzero number of records
+        data_property.is_not_empty = false;
+
+        // empty strings are null, so they are no longer a category
+        if let Some(Nature::Categorical(NatureCategorical { categories: Jagged::Str(categories) })) = &mut data_property.nature {
+            categories.iter_mut()
+                .for_each(|categories| categories.retain(|category| !category.is_empty()));
+        }
+
+        data_property.nullity = false;
+        Ok(ValueProperties::Array(data_property).into())
+    }
+}
+
+fn get_data_property(properties: &NodeProperties) -> Result<ArrayProperties> {
+    let data_property = properties.get::<IndexKey>(&"data".into())
+        .ok_or("data: missing")?.array()
+        .map_err(prepend("data:"))?.clone();
+
+    if !data_property.releasable {
+        data_property.assert_is_not_aggregated()?;
+    }
+
+    if data_property.data_type == DataType::Unknown {
+        return Err("data: data_type must be known".into())
+    }
+    Ok(data_property)
+}
+
+#[cfg(test)]
+mod test_nulls {
+    use ndarray::arr1;
+
+    use crate::base::test_data;
+    use crate::bindings::Analysis;
+    use crate::components::clamp::test_clamp;
+    use crate::components::literal::test_literal;
+
+    use super::*;
+
+    /// Float data, either public, or cast from a private column that may contain nulls.
+    fn analysis_float(public: bool) -> (Analysis, u32) {
+        if public {
+            let (mut analysis, literal) = test_literal::analysis_literal(test_data::array1d_f64_10_uniform(), true);
+            let casted = analysis.to_float(literal).build();
+            return (analysis, casted)
+        }
+
+        let mut analysis = Analysis::new();
+        let names = analysis.literal().value(arr1(&["x".to_string()]).into_dyn().into()).value_public(true).build();
+        let data = analysis.materialize(names, "data.csv".to_string()).build();
+        let name = analysis.literal().value("x".to_string().into()).value_public(true).build();
+        let column = analysis.index(data, name, name, name).build();
+        // select the column by name only
+        analysis.components.get_mut(&column).unwrap().arguments = Some(proto::ArgumentNodeIds::new(
+            indexmap!["data".into() => data, "names".into() => name]));
+        let casted = analysis.to_float(column).build();
+        (analysis, casted)
+    }
+
+    fn get_array_property(analysis: &Analysis, id: u32) -> Result<ArrayProperties> {
+        Ok(analysis.properties(id)?.array()?.clone())
+    }
+
+    fn get_bool_categories(property: &ArrayProperties) -> Vec<bool> {
+        property.categories().unwrap().bool().unwrap()[0].clone()
+    }
+
+    #[test]
+    fn test_is_null() {
+        let (mut analysis, data) = analysis_float(false);
+        let is_null = analysis.is_null(data).build();
+        let property = get_array_property(&analysis, is_null).unwrap();
+        assert_eq!(property.data_type, DataType::Bool);
+        assert_eq!(get_bool_categories(&property), vec![false, true]);
+        assert!(!property.nullity);
+
+        // data that is not nullable is never null
+        let (mut analysis, data) = analysis_float(true);
+        let is_null = analysis.is_null(data).build();
+        let property = get_array_property(&analysis, is_null).unwrap();
+        assert_eq!(get_bool_categories(&property), vec![false]);
+        assert_eq!(property.num_records, Some(10));
+
+        let (mut analysis, data) = test_clamp::utilities::analysis_i64_cont(
+            test_data::array1d_i64_10_uniform(), None, None);
+        let is_null = analysis.is_null(data).build();
+        assert_eq!(get_bool_categories(&get_array_property(&analysis, is_null).unwrap()), vec![false]);
+    }
+
+    #[test]
+    fn test_coalesce() {
+        let (mut analysis, private) = analysis_float(false);
+        let public = analysis.literal().value(1.0.into()).value_public(true).build();
+        let coalesced = analysis.coalesce(private, public).build();
+        let property = get_array_property(&analysis, coalesced).unwrap();
+        assert!(!property.nullity);
+        assert!(!property.releasable);
+        assert_eq!(property.dataset_id, get_array_property(&analysis, private).unwrap().dataset_id);
+
+        // the values belong to the private dataset, even when the public argument is on the left
+        let coalesced = analysis.coalesce(public, private).build();
+        let property = get_array_property(&analysis, coalesced).unwrap();
+        assert!(!property.nullity);
+        assert_eq!(property.dataset_id, get_array_property(&analysis, private).unwrap().dataset_id);
+
+        // a value is null only if it is null in both arguments
+        let coalesced = analysis.coalesce(private, private).build();
+        assert!(get_array_property(&analysis, coalesced).unwrap().nullity);
+    }
+
+    #[test]
+    fn test_fill_null() {
+        let (mut analysis, data) = analysis_float(false);
+        let lower = analysis.literal().value(0.0.into()).value_public(true).build();
+        let upper = analysis.literal().value(1.0.into()).value_public(true).build();
+        let clamped = analysis.clamp(data).lower(lower).upper(upper).build();
+        let fill_value = analysis.literal().value(2.0.into()).value_public(true).build();
+        let filled = analysis.fill_null(clamped, fill_value).build();
+
+        let property = get_array_property(&analysis, filled).unwrap();
+        assert!(!property.nullity);
+        assert_eq!(property.lower_float().unwrap(), vec![0.]);
+        assert_eq!(property.upper_float().unwrap(), vec![2.]);
+
+        // an empty string would still be null
+        let (mut analysis, data) = test_clamp::utilities::analysis_string_cat(
+            test_data::array1d_string_10_uniform(), None, None);
+        let fill_value = analysis.literal().value("".to_string().into()).value_public(true).build();
+        let filled = analysis.fill_null(data, fill_value).build();
+        assert!(analysis.properties(filled).is_err());
+    }
+
+    #[test]
+    fn test_drop_null() {
+        let categories = vec!["a", "", "b"].into_iter().map(String::from).collect::<Vec<String>>();
+        let (mut analysis, data) = test_clamp::utilities::analysis_string_cat(
+            test_data::array1d_string_10_uniform(),
+            Some(Value::Jagged(vec![categories].into())), None);
+        let dropped = analysis.drop_null(data).build();
+
+        let property = get_array_property(&analysis, dropped).unwrap();
+        assert!(!property.nullity);
+        assert_eq!(property.num_records, None);
+        assert_eq!(property.dataset_id, Some(dropped as i64));
+        assert_eq!(property.categories().unwrap().string().unwrap(),
+                   vec![vec!["a".to_string(), "b".to_string(), "e".to_string()]]);
+    }
+}
@@ -0,0 +1,107 @@
+use indexmap::map::IndexMap;
+
+use crate::{base, proto, Warnable};
+use crate::base::{Array, ArrayProperties, DataType, IndexKey, Jagged, Nature, NatureCategorical, Value, ValueProperties};
+use crate::components::Component;
+use crate::errors::*;
+use crate::utilities::prepend;
+
+impl Component for proto::Predict {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: base::NodeProperties,
+        _node_id: u32
+    ) -> Result<Warnable<ValueProperties>> {
+        let model = match public_arguments.get::<IndexKey>(&"model".into())
+            .ok_or_else(|| Error::from("model: missing, must be public"))? {
+            Value::Dataframe(model) => model,
+            _ => return Err("model: must be a dataframe".into())
+        };
+        let get_model_array = |name: &str| model.get::<IndexKey>(&name.into())
+            .ok_or_else(|| Error::from(format!("model: {} is missing", name)))?
+            .ref_array().map_err(prepend(&format!("model: {}:", name)));
+
+        let classes = get_model_array("classes")?;
+
+        // tree models split on one matrix of numeric features
+        let arguments: &[(&str, &str)] = if model.contains_key::<IndexKey>(&"thresholds".into()) {
+            &[("data", "features")]
+        } else {
+            &[("data_categorical", "num_categories"), ("data_continuous", "means")]
+        };
+
+        let mut data_property: Option<ArrayProperties> = None;
+        for (name, model_name) in arguments {
+            let property = match properties.get::<IndexKey>(&name.to_string().into()) {
+                Some(property) => property.array().map_err(prepend(&format!("{}:", name)))?.clone(),
+                None => if model.contains_key::<IndexKey>(&model_name.to_string().into()) {
+                    return Err(format!("{}: missing, the model was fit with these features", name).into())
+                } else { continue }
+            };
+
+            if !property.releasable {
+                property.assert_is_not_aggregated()?;
+                property.assert_is_not_sampled()?;
+            }
+            property.assert_non_null()?;
+
+            let num_features = match get_model_array(model_name)? {
+                Array::Int(num_categories) if *name == "data_categorical" => num_categories.len() as i64,
+                Array::Float(means) if means.ndim() == 2 => means.shape()[1] as i64,
+                // the splits may not use every column
+                Array::Int(features) => features.iter().max().map(|v| v + 1).unwrap_or(0),
+                _ => return Err(format!("model: {} is not valid", model_name).into())
+            };
+            if *name == "data" {
+                if property.num_columns()? < num_features {
+                    return Err("data: must contain each column that the model splits on".into())
+                }
+                if property.data_type != DataType::Float && property.data_type != DataType::Int {
+                    return Err("data: atomic type must be numeric".into())
+                }
+            } else if property.num_columns()? != num_features {
+                return Err(format!("{}: must have one column for each feature of the model", name).into())
+            }
+            if *name == "data_continuous" && property.data_type != DataType::Float {
+                return Err("data_continuous: atomic type must be float".into())
+            }
+
+            if let Some(data_property) = &data_property {
+                if data_property.num_records.is_some() && property.num_records.is_some()
+                    && data_property.num_records != property.num_records {
+                    return Err("data_categorical and data_continuous: must be same length".into())
+                }
+                if data_property.group_id != property.group_id {
+                    return Err("data from separate partitions may not be mixed".into())
+                }
+                if data_property.dataset_id != property.dataset_id {
+                    return Err("data_categorical and data_continuous must share the same dataset id".into())
+                }
+            } else {
+                data_property = Some(property);
+            }
+        }
+
+        let mut data_property = data_property
+            .ok_or_else(|| Error::from("at least one of data_categorical and data_continuous must be supplied"))?;
+
+        // each record is labeled with one of the classes
+        let classes = match classes {
+            Array::Bool(classes) => Jagged::Bool(vec![classes.iter().cloned().collect()]),
+            Array::Int(classes) => Jagged::Int(vec![classes.iter().cloned().collect()]),
+            Array::Float(classes) => Jagged::Float(vec![classes.iter().cloned().collect()]),
+            Array::Str(classes) => Jagged::Str(vec![classes.iter().cloned().collect()]),
+        };
+        data_property.data_type = classes.data_type();
+        data_property.nature = match data_property.data_type {
+            // float data may not be categorical
+            DataType::Float => None,
+            _ => Some(Nature::Categorical(NatureCategorical { categories: classes }))
+        };
+        data_property.num_columns = Some(1);
+        data_property.dimensionality = Some(1);
+        Ok(ValueProperties::Array(data_property).into())
+    }
+}
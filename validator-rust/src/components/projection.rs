@@ -0,0 +1,65 @@
+use indexmap::map::IndexMap;
+use ndarray::Axis;
+
+use crate::{base, proto, Warnable};
+use crate::base::{ArrayProperties, DataType, IndexKey, Nature, NatureContinuous, Value, ValueProperties, Vector1DNull};
+use crate::components::Component;
+use crate::errors::*;
+use crate::utilities::prepend;
+
+impl Component for proto::Projection {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: base::NodeProperties,
+        _node_id: u32
+    ) -> Result<Warnable<ValueProperties>> {
+        let mut data_property: ArrayProperties = properties.get(&IndexKey::from("data"))
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        if !data_property.releasable {
+            data_property.assert_is_not_aggregated()?;
+            data_property.assert_is_not_sampled()?;
+        }
+
+        if data_property.data_type != DataType::Float {
+            return Err("data: atomic type must be float".into());
+        }
+
+        let components = public_arguments.get::<IndexKey>(&"components".into())
+            .ok_or_else(|| Error::from("components: missing, must be public"))?.ref_array()?.ref_float()?;
+
+        if components.ndim() != 2 {
+            return Err("components: must be a 2-dimensional array".into())
+        }
+        let num_columns = data_property.num_columns()?;
+        if components.len_of(Axis(1)) as i64 != num_columns + 1 {
+            return Err("components: must have one more column than data".into())
+        }
+
+        // bounds on the dot product of each record with each direction
+        data_property.nature = match (data_property.lower_float(), data_property.upper_float()) {
+            (Ok(lower), Ok(upper)) => {
+                let (lower, upper): (Vec<_>, Vec<_>) = components.outer_iter()
+                    .map(|component| component.iter().skip(1)
+                        .zip(lower.iter().zip(upper.iter()))
+                        .fold((0., 0.), |(sum_lower, sum_upper), (weight, (lower, upper))| (
+                            sum_lower + (weight * lower).min(weight * upper),
+                            sum_upper + (weight * lower).max(weight * upper))))
+                    .map(|(lower, upper)| (Some(lower), Some(upper)))
+                    .unzip();
+                Some(Nature::Continuous(NatureContinuous {
+                    lower: Vector1DNull::Float(lower),
+                    upper: Vector1DNull::Float(upper),
+                }))
+            }
+            _ => None
+        };
+
+        data_property.num_columns = Some(components.len_of(Axis(0)) as i64);
+        data_property.dimensionality = Some(2);
+        Ok(ValueProperties::Array(data_property).into())
+    }
+}
@@ -0,0 +1,246 @@
+use indexmap::map::IndexMap;
+use ndarray::arr0;
+
+use crate::{base, proto, Warnable};
+use crate::base::{Array, ArrayProperties, DataframeProperties, DataType, IndexKey, Value, ValueProperties};
+use crate::components::{Component, Expandable};
+use crate::components::index::to_name_vec;
+use crate::errors::*;
+use crate::utilities::{get_argument, get_common_value, get_literal, prepend};
+use crate::utilities::inference::infer_property;
+
+impl Component for proto::Sort {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: base::NodeProperties,
+        node_id: u32,
+    ) -> Result<Warnable<ValueProperties>> {
+        let data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.dataframe()
+            .map_err(prepend("data:"))?
+            .children.iter()
+            .map(|(name, property)| Ok((name.clone(), property.array()
+                .map_err(prepend(&format!("{}:", name.to_string())))?.clone())))
+            .collect::<Result<IndexMap<IndexKey, ArrayProperties>>>()?;
+
+        let keys = get_key_names(get_argument(&public_arguments, "keys")?.ref_array()?.clone())
+            .map_err(prepend("keys:"))?;
+
+        let num_descending = get_argument(&public_arguments, "descending")?.ref_array()?.ref_bool()
+            .map_err(prepend("descending:"))?.len();
+        if num_descending != 1 && num_descending != keys.len() {
+            return Err("descending: must contain one value, or one value for each key".into())
+        }
+
+        for key in &keys {
+            let key_property = data_property.get(key)
+                .ok_or_else(|| Error::from(format!("keys: {} is not a column of data", key.to_string())))?;
+            if key_property.num_columns()? != 1 {
+                return Err(format!("{}: key columns must contain one column", key.to_string()).into())
+            }
+            if key_property.data_type == DataType::Unknown {
+                return Err(format!("{}: data_type must be known", key.to_string()).into())
+            }
+        }
+
+        data_property.values().try_for_each(|property| if property.releasable { Ok(()) } else {
+            property.assert_is_not_aggregated()
+        })?;
+
+        // every row is moved as a whole, so the columns must be conformable
+        let columns = data_property.values().collect::<Vec<&ArrayProperties>>();
+        let num_records = get_common_value(&columns.iter().map(|property| property.num_records).collect())
+            .unwrap_or(None);
+        let dataset_id = get_common_value(&columns.iter().map(|property| property.dataset_id).collect())
+            .unwrap_or(None);
+        if num_records.is_none() && dataset_id.is_none() {
+            return Err("data: columns may not be conformable".into())
+        }
+        if !columns.iter().all(|property| property.naturally_ordered) && dataset_id.is_none() {
+            return Err("data: cannot sort columns that may have been reordered".into())
+        }
+        let private_group_ids = columns.iter()
+            .filter(|property| !property.releasable)
+            .map(|property| property.group_id.clone())
+            .collect::<Vec<_>>();
+        if !private_group_ids.is_empty() && get_common_value(&private_group_ids).is_none() {
+            return Err("data: private columns must share the same group id".into())
+        }
+
+        let children = data_property.into_iter()
+            .map(|(name, mut property)| {
+                // the rows are permuted, so the sorted columns are only conformable with each other
+                property.naturally_ordered = false;
+                property.dataset_id = Some(node_id as i64);
+                property.node_id = node_id as i64;
+                (name, ValueProperties::Array(property))
+            })
+            .collect();
+
+        Ok(ValueProperties::Dataframe(DataframeProperties { children }).into())
+    }
+}
+
+impl Expandable for proto::Sort {
+    /// If descending is not supplied, then sort every key in ascending order
+    fn expand_component(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        component: &proto::Component,
+        _public_arguments: &IndexMap<IndexKey, &Value>,
+        properties: &base::NodeProperties,
+        component_id: u32,
+        mut maximum_id: u32,
+    ) -> Result<base::ComponentExpansion> {
+        let mut component = component.clone();
+        let mut expansion = base::ComponentExpansion::default();
+
+        if !properties.contains_key::<IndexKey>(&"descending".into()) {
+            maximum_id += 1;
+            let id_descending = maximum_id;
+            let value = Value::Array(Array::Bool(arr0(false).into_dyn()));
+            expansion.properties.insert(id_descending, infer_property(&value, None, id_descending)?);
+            let (patch_node, release) = get_literal(value, component.submission)?;
+            expansion.computation_graph.insert(id_descending, patch_node);
+            expansion.releases.insert(id_descending, release);
+            component.insert_argument(&"descending".into(), id_descending);
+        }
+
+        expansion.computation_graph.insert(component_id, component);
+
+        Ok(expansion)
+    }
+}
+
+/// Names of the key columns, in order of precedence.
+pub fn get_key_names(keys: Array) -> Result<Vec<IndexKey>> {
+    Ok(match keys {
+        Array::Str(keys) => to_name_vec(keys)?.into_iter().map(IndexKey::from).collect(),
+        Array::Int(keys) => to_name_vec(keys)?.into_iter().map(IndexKey::from).collect(),
+        Array::Bool(keys) => to_name_vec(keys)?.into_iter().map(IndexKey::from).collect(),
+        Array::Float(_) => return Err("column names may not be floats".into())
+    })
+}
+
+#[cfg(test)]
+pub mod test_sort {
+    use ndarray::arr1;
+
+    use super::*;
+    use self::utilities::*;
+
+    pub mod utilities {
+        use ndarray::arr1;
+
+        use crate::base::Value;
+        use crate::bindings::Analysis;
+        use crate::proto;
+
+        /// Private events, with a user and time column.
+        pub fn analysis_events() -> (Analysis, u32) {
+            let mut analysis = Analysis::new();
+            let names = analysis.literal()
+                .value(arr1(&["user".to_string(), "time".to_string()]).into_dyn().into())
+                .value_public(true).build();
+            let data = analysis.materialize(names, "events.csv".to_string()).build();
+            (analysis, data)
+        }
+
+        pub fn sort_by(analysis: &mut Analysis, data: u32, keys: &[&str]) -> u32 {
+            let keys = analysis.literal()
+                .value(arr1(keys).mapv(String::from).into_dyn().into())
+                .value_public(true).build();
+            let descending = analysis.literal().value(false.into()).value_public(true).build();
+            analysis.sort(data, keys, descending).build()
+        }
+
+        /// Select columns of a dataframe by name only.
+        pub fn index_names(analysis: &mut Analysis, data: u32, names: Value) -> u32 {
+            let names = analysis.literal().value(names).value_public(true).build();
+            let index = analysis.index(data, names, names, names).build();
+            analysis.components.get_mut(&index).unwrap().arguments = Some(proto::ArgumentNodeIds::new(
+                indexmap!["data".into() => data, "names".into() => names]));
+            index
+        }
+
+        pub fn column(analysis: &mut Analysis, data: u32, name: &str) -> u32 {
+            index_names(analysis, data, name.to_string().into())
+        }
+    }
+
+    #[test]
+    fn test_sort() {
+        let (mut analysis, data) = analysis_events();
+        let sorted = sort_by(&mut analysis, data, &["user", "time"]);
+
+        let properties = analysis.properties(sorted).unwrap();
+        let children = &properties.dataframe().unwrap().children;
+        assert_eq!(children.len(), 2);
+        children.values().for_each(|property| {
+            let property = property.array().unwrap();
+            // the sorted columns are only conformable with each other
+            assert!(!property.naturally_ordered);
+            assert_eq!(property.dataset_id, Some(sorted as i64));
+            assert_eq!(property.node_id, sorted as i64);
+        });
+
+        let sorted = sort_by(&mut analysis, data, &["date"]);
+        assert!(analysis.properties(sorted).is_err());
+    }
+
+    #[test]
+    fn test_sort_not_conformable() {
+        let (analysis, data) = analysis_events();
+        let mut properties = analysis.properties(data).unwrap().dataframe().unwrap().clone();
+        // the columns belong to different datasets, of unknown length
+        if let ValueProperties::Array(property) = properties.children.get_mut::<IndexKey>(&"time".into()).unwrap() {
+            property.dataset_id = Some(-1);
+        }
+
+        let keys = Value::Array(Array::Str(arr1(&["user".to_string()]).into_dyn()));
+        let descending = Value::Array(Array::Bool(arr0(false).into_dyn()));
+        let error = proto::Sort {}.propagate_property(
+            &None,
+            indexmap!["keys".into() => &keys, "descending".into() => &descending],
+            indexmap!["data".into() => ValueProperties::Dataframe(properties)],
+            0).unwrap_err();
+        assert!(error.to_string().contains("columns may not be conformable"));
+    }
+
+    #[test]
+    fn test_sort_filter() {
+        // the events of a single user, in order of time
+        let (mut analysis, data) = analysis_events();
+        let sorted = sort_by(&mut analysis, data, &["user", "time"]);
+        let user = column(&mut analysis, sorted, "user");
+        let target = analysis.literal().value("a".to_string().into()).value_public(true).build();
+        let mask = analysis.equal(user, target).build();
+
+        let time = column(&mut analysis, sorted, "time");
+        let filtered = analysis.filter(time, mask).build();
+        let property = analysis.properties(filtered).unwrap().array().unwrap().clone();
+        assert!(!property.naturally_ordered);
+        assert_eq!(property.dataset_id, Some(filtered as i64));
+
+        // the unsorted column is not conformable with the sorted mask
+        let time = column(&mut analysis, data, "time");
+        let filtered = analysis.filter(time, mask).build();
+        assert!(analysis.properties(filtered).is_err());
+    }
+
+    #[test]
+    fn test_sort_concat() {
+        let (mut analysis, data) = analysis_events();
+        let sorted = sort_by(&mut analysis, data, &["user"]);
+        let user = column(&mut analysis, sorted, "user");
+        let time = column(&mut analysis, sorted, "time");
+
+        // the concatenation of sorted columns is still sorted
+        let concat = analysis.concat(user, time).build();
+        let property = analysis.properties(concat).unwrap().array().unwrap().clone();
+        assert!(!property.naturally_ordered);
+        assert_eq!(property.dataset_id, Some(sorted as i64));
+    }
+}
@@ -4,16 +4,18 @@ use crate::base::{Nature, NatureCategorical, Vector1DNull, Jagged, ArrayProperti
 
 use crate::{proto, base, Warnable, Integer, Float};
 
-use crate::utilities::{prepend};
+use crate::utilities::{prepend, standardize_null_target_argument};
 
 use crate::components::{Component};
 
-use crate::base::{IndexKey, Value, NatureContinuous};
+use crate::base::{Array, IndexKey, Value, NatureContinuous};
 use num::{CheckedAdd, CheckedSub, Zero};
 use indexmap::map::IndexMap;
 use std::ops::{Mul, Div};
 use std::cmp::Ordering;
+use std::f64::consts::PI;
 use itertools::Itertools;
+use regex::Regex;
 
 
 impl Component for proto::Abs {
@@ -90,9 +92,13 @@ impl Component for proto::Add {
         }
 
         let (num_columns, num_records) = propagate_binary_shape(&left_property, &right_property)?;
-        if left_property.data_type != right_property.data_type {
-            return Err("left and right arguments must share the same data types".into())
-        }
+        // a timestamp may be offset by an integer number of seconds
+        let data_type = match (&left_property.data_type, &right_property.data_type) {
+            (DataType::Timestamp, DataType::Int) | (DataType::Int, DataType::Timestamp) => DataType::Timestamp,
+            (DataType::Timestamp, DataType::Timestamp) => return Err("timestamps may not be added to each other".into()),
+            (left, right) if left == right => left.clone(),
+            _ => return Err("left and right arguments must share the same data types".into())
+        };
 
         Ok(ValueProperties::Array(ArrayProperties {
             nullity: left_property.nullity || right_property.nullity,
@@ -130,7 +136,7 @@ impl Component for proto::Add {
             num_records,
             aggregator: None,
             group_id: propagate_binary_group_id(&left_property, &right_property)?,
-            data_type: left_property.data_type,
+            data_type,
             dataset_id: left_property.dataset_id,
             node_id: node_id as i64,
             is_not_empty: left_property.is_not_empty && right_property.is_not_empty,
@@ -198,6 +204,126 @@ impl Component for proto::And {
     }
 }
 
+impl Component for proto::Ceil {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: base::NodeProperties,
+        _node_id: u32
+    ) -> Result<Warnable<ValueProperties>> {
+        let mut data_property = get_float_property(&properties)?;
+
+        data_property.nature = propagate_unary_nature(
+            &data_property,
+            &UnaryOperators {
+                float: Some(Box::new(|v| Ok(v.ceil()))),
+                int: None,
+                bool: None,
+                str: None,
+            },
+            &OptimizeUnaryOperators {
+                float: Some(Box::new(|bounds| Ok((
+                    finite(bounds.lower.map(|v| v.ceil())),
+                    finite(bounds.upper.map(|v| v.ceil())))))),
+                int: None,
+            }, data_property.num_columns()?)?;
+
+        Ok(ValueProperties::Array(data_property).into())
+    }
+}
+
+impl Component for proto::Concat {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: base::NodeProperties,
+        node_id: u32
+    ) -> Result<Warnable<ValueProperties>> {
+        let left_property: ArrayProperties = properties.get(&IndexKey::from("left"))
+            .ok_or("left: missing")?.array()
+            .map_err(prepend("left:"))?.clone();
+        let right_property: ArrayProperties = properties.get::<IndexKey>(&"right".into())
+            .ok_or("right: missing")?.array()
+            .map_err(prepend("right:"))?.clone();
+
+        if !left_property.releasable {
+            left_property.assert_is_not_aggregated()?;
+            left_property.assert_is_not_sampled()?;
+        }
+        if !right_property.releasable {
+            right_property.assert_is_not_aggregated()?;
+            right_property.assert_is_not_sampled()?;
+        }
+
+        let (num_columns, num_records) = propagate_binary_shape(&left_property, &right_property)?;
+        if left_property.data_type != DataType::Str || right_property.data_type != DataType::Str {
+            return Err("left and right arguments must be strings".into())
+        }
+
+        let separator = self.separator.clone();
+        Ok(ValueProperties::Array(ArrayProperties {
+            nullity: left_property.nullity || right_property.nullity,
+            releasable: left_property.releasable && right_property.releasable,
+            // different pairs of categories may concatenate to the same string
+            nature: deduplicate_categories(propagate_binary_nature(&left_property, &right_property, &BinaryOperators {
+                float: None,
+                int: None,
+                str: Some(Box::new(move |l: &String, r: &String| Ok(format!("{}{}{}", l, separator, r)))),
+                bool: None,
+            }, &OptimizeBinaryOperators { float: None, int: None }, num_columns)?)?,
+            // checks to ensure this is correct are made in propagate_binary_shape
+            c_stability: left_property.c_stability
+                .max(right_property.c_stability),
+            num_columns: Some(num_columns),
+            num_records,
+            aggregator: None,
+            group_id: propagate_binary_group_id(&left_property, &right_property)?,
+            data_type: DataType::Str,
+            dataset_id: left_property.dataset_id,
+            node_id: node_id as i64,
+            is_not_empty: left_property.is_not_empty && right_property.is_not_empty,
+            dimensionality: left_property.dimensionality
+                .max(right_property.dimensionality),
+            naturally_ordered: left_property.naturally_ordered && right_property.naturally_ordered,
+            // checks are made within propagate_binary_shape that sampling proportion is equal and permissible
+            sample_proportion: left_property.sample_proportion
+        }).into())
+    }
+}
+
+impl Component for proto::Cos {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: base::NodeProperties,
+        _node_id: u32
+    ) -> Result<Warnable<ValueProperties>> {
+        let mut data_property = get_float_property(&properties)?;
+
+        data_property.nature = propagate_unary_nature(
+            &data_property,
+            &UnaryOperators {
+                float: Some(Box::new(|v| Ok(v.cos()))),
+                int: None,
+                bool: None,
+                str: None,
+            },
+            &OptimizeUnaryOperators {
+                float: Some(Box::new(|bounds| {
+                    let (lower, upper) = sinusoid_bounds(bounds.lower, bounds.upper, PI / 2.);
+                    Ok((Some(lower), Some(upper)))
+                })),
+                int: None,
+            }, data_property.num_columns()?)?
+            .or_else(|| unit_interval(&data_property));
+
+        Ok(ValueProperties::Array(data_property).into())
+    }
+}
+
 impl Component for proto::Divide {
     #[allow(clippy::float_cmp)]
     fn propagate_property(
@@ -406,6 +532,74 @@ impl Component for proto::Equal {
 }
 
 
+impl Component for proto::Exp {
+    fn propagate_property(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: base::NodeProperties,
+        _node_id: u32
+    ) -> Result<Warnable<ValueProperties>> {
+        let mut data_property = get_float_property(&properties)?;
+
+        let protect_overflow = privacy_definition.as_ref()
+            .map(|privacy_definition| privacy_definition.protect_overflow)
+            .unwrap_or(false);
+        if protect_overflow && !data_property.upper_float()
+            .map(|upper| upper.iter().all(|v| v.exp().is_finite()))
+            .unwrap_or(false) {
+            return Err("data: upper bound must be small enough that exp does not overflow".into())
+        }
+
+        // exp is increasing, but an upper bound is lost if it overflows
+        data_property.nature = propagate_unary_nature(
+            &data_property,
+            &UnaryOperators {
+                float: Some(Box::new(|v| Ok(v.exp()))),
+                int: None,
+                bool: None,
+                str: None,
+            },
+            &OptimizeUnaryOperators {
+                float: Some(Box::new(|bounds| Ok((
+                    finite(bounds.lower.map(|v| v.exp())),
+                    finite(bounds.upper.map(|v| v.exp())))))),
+                int: None,
+            }, data_property.num_columns()?)?;
+
+        Ok(ValueProperties::Array(data_property).into())
+    }
+}
+
+impl Component for proto::Floor {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: base::NodeProperties,
+        _node_id: u32
+    ) -> Result<Warnable<ValueProperties>> {
+        let mut data_property = get_float_property(&properties)?;
+
+        data_property.nature = propagate_unary_nature(
+            &data_property,
+            &UnaryOperators {
+                float: Some(Box::new(|v| Ok(v.floor()))),
+                int: None,
+                bool: None,
+                str: None,
+            },
+            &OptimizeUnaryOperators {
+                float: Some(Box::new(|bounds| Ok((
+                    finite(bounds.lower.map(|v| v.floor())),
+                    finite(bounds.upper.map(|v| v.floor())))))),
+                int: None,
+            }, data_property.num_columns()?)?;
+
+        Ok(ValueProperties::Array(data_property).into())
+    }
+}
+
 impl Component for proto::GreaterThan {
     fn propagate_property(
         &self,
@@ -433,11 +627,13 @@ impl Component for proto::GreaterThan {
         if left_property.data_type != right_property.data_type {
             return Err("left and right must be homogeneously typed".into())
         }
-        if left_property.data_type != DataType::Int && left_property.data_type != DataType::Float {
-            return Err("left must be numeric".into())
+        if left_property.data_type != DataType::Int && left_property.data_type != DataType::Float
+            && left_property.data_type != DataType::Timestamp {
+            return Err("left must be numeric or timestamps".into())
         }
-        if right_property.data_type != DataType::Int && right_property.data_type != DataType::Float {
-            return Err("right must be numeric".into())
+        if right_property.data_type != DataType::Int && right_property.data_type != DataType::Float
+            && right_property.data_type != DataType::Timestamp {
+            return Err("right must be numeric or timestamps".into())
         }
 
         let (num_columns, num_records) = propagate_binary_shape(&left_property, &right_property)?;
@@ -496,11 +692,13 @@ impl Component for proto::LessThan {
         if left_property.data_type != right_property.data_type {
             return Err("left and right must be homogeneously typed".into())
         }
-        if left_property.data_type != DataType::Int && left_property.data_type != DataType::Float {
-            return Err("left must be numeric".into())
+        if left_property.data_type != DataType::Int && left_property.data_type != DataType::Float
+            && left_property.data_type != DataType::Timestamp {
+            return Err("left must be numeric or timestamps".into())
         }
-        if right_property.data_type != DataType::Int && right_property.data_type != DataType::Float {
-            return Err("right must be numeric".into())
+        if right_property.data_type != DataType::Int && right_property.data_type != DataType::Float
+            && right_property.data_type != DataType::Timestamp {
+            return Err("right must be numeric or timestamps".into())
         }
 
         let (num_columns, num_records) = propagate_binary_shape(&left_property, &right_property)?;
@@ -597,6 +795,20 @@ impl Component for proto::Log {
 }
 
 
+impl Component for proto::Lower {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: base::NodeProperties,
+        _node_id: u32
+    ) -> Result<Warnable<ValueProperties>> {
+        Ok(ValueProperties::Array(propagate_string_property(
+            &properties, |v| Ok(v.to_lowercase()))?).into())
+    }
+}
+
+
 impl Component for proto::Modulo {
     fn propagate_property(
         &self,
@@ -970,6 +1182,109 @@ impl Component for proto::Power {
 }
 
 
+impl Component for proto::RegexExtract {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: base::NodeProperties,
+        _node_id: u32
+    ) -> Result<Warnable<ValueProperties>> {
+        let mut data_property = get_string_property(&properties)?;
+        let num_columns = data_property.num_columns()?;
+
+        let pattern = compile_pattern(&self.pattern)?;
+        let group = self.group as usize;
+        if group >= pattern.captures_len() {
+            return Err(format!("group: pattern only has {} capture groups", pattern.captures_len() - 1).into())
+        }
+
+        let null = match public_arguments.get::<IndexKey>(&"null_value".into())
+            .ok_or_else(|| Error::from("null_value: missing, must be public"))?.ref_array()? {
+            Array::Str(null) => standardize_null_target_argument(null.clone(), num_columns)?,
+            _ => return Err("null_value: must be a string".into())
+        };
+        if null.iter().any(String::is_empty) {
+            return Err("null_value: may not be empty, because empty strings are null".into())
+        }
+
+        // each category maps to its extract, or to the null value if it does not match
+        data_property.nature = match &data_property.nature {
+            Some(Nature::Categorical(nature)) => Some(Nature::Categorical(NatureCategorical {
+                categories: Jagged::Str(nature.categories.clone().standardize(num_columns)?.string()?.iter()
+                    .zip(null.iter())
+                    .map(|(categories, null)| categories.iter()
+                        .map(|v| regex_extract(&pattern, v, group).unwrap_or_else(|| null.clone()))
+                        .unique().collect())
+                    .collect())
+            })),
+            _ => None
+        };
+        // a capture group may match an empty string
+        data_property.nullity = may_contain_empty(&data_property.nature);
+
+        Ok(ValueProperties::Array(data_property).into())
+    }
+}
+
+impl Component for proto::RegexMatch {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: base::NodeProperties,
+        _node_id: u32
+    ) -> Result<Warnable<ValueProperties>> {
+        let mut data_property = get_string_property(&properties)?;
+        let num_columns = data_property.num_columns()?;
+
+        let pattern = compile_pattern(&self.pattern)?;
+
+        data_property.nature = Some(Nature::Categorical(NatureCategorical {
+            categories: Jagged::Bool(match &data_property.nature {
+                Some(Nature::Categorical(nature)) => nature.categories.clone().standardize(num_columns)?.string()?.iter()
+                    .map(|categories| categories.iter().map(|v| pattern.is_match(v)).unique().collect())
+                    .collect(),
+                _ => (0..num_columns).map(|_| vec![true, false]).collect()
+            })
+        }));
+        data_property.data_type = DataType::Bool;
+        data_property.nullity = false;
+
+        Ok(ValueProperties::Array(data_property).into())
+    }
+}
+
+impl Component for proto::Round {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: base::NodeProperties,
+        _node_id: u32
+    ) -> Result<Warnable<ValueProperties>> {
+        let mut data_property = get_float_property(&properties)?;
+
+        // rounds half away from zero
+        data_property.nature = propagate_unary_nature(
+            &data_property,
+            &UnaryOperators {
+                float: Some(Box::new(|v| Ok(v.round()))),
+                int: None,
+                bool: None,
+                str: None,
+            },
+            &OptimizeUnaryOperators {
+                float: Some(Box::new(|bounds| Ok((
+                    finite(bounds.lower.map(|v| v.round())),
+                    finite(bounds.upper.map(|v| v.round())))))),
+                int: None,
+            }, data_property.num_columns()?)?;
+
+        Ok(ValueProperties::Array(data_property).into())
+    }
+}
+
 impl Component for proto::RowMax {
     fn propagate_property(
         &self,
@@ -1140,6 +1455,128 @@ impl Component for proto::RowMin {
     }
 }
 
+impl Component for proto::Sign {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: base::NodeProperties,
+        _node_id: u32
+    ) -> Result<Warnable<ValueProperties>> {
+        let mut data_property: ArrayProperties = properties.get(&IndexKey::from("data"))
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        if !data_property.releasable {
+            data_property.assert_is_not_aggregated()?;
+        }
+        if data_property.data_type != DataType::Float && data_property.data_type != DataType::Int {
+            return Err("data: atomic type must be numeric".into())
+        }
+
+        data_property.nature = deduplicate_categories(propagate_unary_nature(
+            &data_property,
+            &UnaryOperators {
+                float: Some(Box::new(|v| Ok(sign(*v)))),
+                int: Some(Box::new(|v| Ok(v.signum()))),
+                bool: None,
+                str: None,
+            },
+            &OptimizeUnaryOperators {
+                float: Some(Box::new(|bounds| Ok((
+                    Some(bounds.lower.map(sign).unwrap_or(-1.)),
+                    Some(bounds.upper.map(sign).unwrap_or(1.)))))),
+                int: Some(Box::new(|bounds| Ok((
+                    Some(bounds.lower.map(|v| v.signum()).unwrap_or(-1)),
+                    Some(bounds.upper.map(|v| v.signum()).unwrap_or(1)))))),
+            }, data_property.num_columns()?)?)?
+            .or_else(|| unit_interval(&data_property));
+
+        Ok(ValueProperties::Array(data_property).into())
+    }
+}
+
+impl Component for proto::Sin {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: base::NodeProperties,
+        _node_id: u32
+    ) -> Result<Warnable<ValueProperties>> {
+        let mut data_property = get_float_property(&properties)?;
+
+        data_property.nature = propagate_unary_nature(
+            &data_property,
+            &UnaryOperators {
+                float: Some(Box::new(|v| Ok(v.sin()))),
+                int: None,
+                bool: None,
+                str: None,
+            },
+            &OptimizeUnaryOperators {
+                float: Some(Box::new(|bounds| {
+                    let (lower, upper) = sinusoid_bounds(bounds.lower, bounds.upper, 0.);
+                    Ok((Some(lower), Some(upper)))
+                })),
+                int: None,
+            }, data_property.num_columns()?)?
+            .or_else(|| unit_interval(&data_property));
+
+        Ok(ValueProperties::Array(data_property).into())
+    }
+}
+
+impl Component for proto::Sqrt {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: base::NodeProperties,
+        _node_id: u32
+    ) -> Result<Warnable<ValueProperties>> {
+        let mut data_property = get_float_property(&properties)?;
+
+        if !data_property.lower_float()?.iter().all(|lower| lower >= &0.) {
+            return Err("data may potentially be less than zero".into())
+        }
+
+        data_property.nature = propagate_unary_nature(
+            &data_property,
+            &UnaryOperators {
+                float: Some(Box::new(|v| Ok(v.sqrt()))),
+                int: None,
+                bool: None,
+                str: None,
+            },
+            &OptimizeUnaryOperators {
+                float: Some(Box::new(|bounds| Ok((
+                    finite(bounds.lower.map(|v| v.sqrt())),
+                    finite(bounds.upper.map(|v| v.sqrt())))))),
+                int: None,
+            }, data_property.num_columns()?)?;
+
+        Ok(ValueProperties::Array(data_property).into())
+    }
+}
+
+impl Component for proto::Substring {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: base::NodeProperties,
+        _node_id: u32
+    ) -> Result<Warnable<ValueProperties>> {
+        let (start, length) = (self.start as usize, self.length as usize);
+        let mut data_property = propagate_string_property(
+            &properties, move |v| Ok(substring(v, start, length)))?;
+        // strings shorter than start become empty
+        data_property.nullity = may_contain_empty(&data_property.nature);
+        Ok(ValueProperties::Array(data_property).into())
+    }
+}
+
 impl Component for proto::Subtract {
     fn propagate_property(
         &self,
@@ -1165,9 +1602,14 @@ impl Component for proto::Subtract {
         }
 
         let (num_columns, num_records) = propagate_binary_shape(&left_property, &right_property)?;
-        if left_property.data_type != right_property.data_type {
-            return Err("left and right arguments must share the same data types".into())
-        }
+        // the difference of two timestamps is an integer number of seconds
+        let data_type = match (&left_property.data_type, &right_property.data_type) {
+            (DataType::Timestamp, DataType::Timestamp) => DataType::Int,
+            (DataType::Timestamp, DataType::Int) => DataType::Timestamp,
+            (DataType::Int, DataType::Timestamp) => return Err("timestamps may not be subtracted from integers".into()),
+            (left, right) if left == right => left.clone(),
+            _ => return Err("left and right arguments must share the same data types".into())
+        };
 
         Ok(ValueProperties::Array(ArrayProperties {
             nullity: left_property.nullity || right_property.nullity,
@@ -1205,7 +1647,7 @@ impl Component for proto::Subtract {
             num_records,
             aggregator: None,
             group_id: propagate_binary_group_id(&left_property, &right_property)?,
-            data_type: left_property.data_type,
+            data_type,
             dataset_id: left_property.dataset_id,
             node_id: node_id as i64,
             is_not_empty: left_property.is_not_empty && right_property.is_not_empty,
@@ -1218,6 +1660,196 @@ impl Component for proto::Subtract {
     }
 }
 
+impl Component for proto::Tan {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: base::NodeProperties,
+        _node_id: u32
+    ) -> Result<Warnable<ValueProperties>> {
+        let mut data_property = get_float_property(&properties)?;
+
+        // tan is increasing between asymptotes, and unbounded across them
+        data_property.nature = propagate_unary_nature(
+            &data_property,
+            &UnaryOperators {
+                float: Some(Box::new(|v| Ok(v.tan()))),
+                int: None,
+                bool: None,
+                str: None,
+            },
+            &OptimizeUnaryOperators {
+                float: Some(Box::new(|bounds| Ok(match (bounds.lower, bounds.upper) {
+                    (Some(lower), Some(upper)) if !contains_period(*lower, *upper, PI / 2., PI) =>
+                        (finite(Some(lower.tan())), finite(Some(upper.tan()))),
+                    _ => (None, None)
+                }))),
+                int: None,
+            }, data_property.num_columns()?)?;
+
+        Ok(ValueProperties::Array(data_property).into())
+    }
+}
+
+impl Component for proto::Trim {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: base::NodeProperties,
+        _node_id: u32
+    ) -> Result<Warnable<ValueProperties>> {
+        let mut data_property = propagate_string_property(
+            &properties, |v| Ok(v.trim().to_string()))?;
+        // strings of only whitespace become empty
+        data_property.nullity = may_contain_empty(&data_property.nature);
+        Ok(ValueProperties::Array(data_property).into())
+    }
+}
+
+/// Retrieve the properties of the data argument, which must be floats.
+fn get_float_property(properties: &base::NodeProperties) -> Result<ArrayProperties> {
+    let data_property: ArrayProperties = properties.get(&IndexKey::from("data"))
+        .ok_or("data: missing")?.array()
+        .map_err(prepend("data:"))?.clone();
+
+    if !data_property.releasable {
+        data_property.assert_is_not_aggregated()?;
+    }
+    if data_property.data_type != DataType::Float {
+        return Err("data: atomic type must be float".into())
+    }
+    Ok(data_property)
+}
+
+/// Discard bounds that are infinite or NaN.
+fn finite(bound: Option<Float>) -> Option<Float> {
+    bound.filter(|v| v.is_finite())
+}
+
+/// Whether the closed interval contains `offset + k * period` for some integer k.
+fn contains_period(lower: Float, upper: Float, offset: Float, period: Float) -> bool {
+    offset + ((lower - offset) / period).ceil() * period <= upper
+}
+
+/// Range of `sin(v + phase)` over the bounds of v.
+fn sinusoid_bounds(lower: &Option<Float>, upper: &Option<Float>, phase: Float) -> (Float, Float) {
+    let (lower, upper) = match (lower, upper) {
+        (Some(lower), Some(upper)) => (lower + phase, upper + phase),
+        _ => return (-1., 1.)
+    };
+    let endpoints = (lower.sin(), upper.sin());
+    (
+        if contains_period(lower, upper, -PI / 2., 2. * PI) { -1. } else { endpoints.0.min(endpoints.1) },
+        if contains_period(lower, upper, PI / 2., 2. * PI) { 1. } else { endpoints.0.max(endpoints.1) }
+    )
+}
+
+/// Bounds of [-1, 1] on each column.
+fn unit_interval(data_property: &ArrayProperties) -> Option<Nature> {
+    let num_columns = data_property.num_columns().ok()? as usize;
+    Some(Nature::Continuous(match data_property.data_type {
+        DataType::Float => NatureContinuous {
+            lower: Vector1DNull::Float(vec![Some(-1.); num_columns]),
+            upper: Vector1DNull::Float(vec![Some(1.); num_columns]),
+        },
+        DataType::Int => NatureContinuous {
+            lower: Vector1DNull::Int(vec![Some(-1); num_columns]),
+            upper: Vector1DNull::Int(vec![Some(1); num_columns]),
+        },
+        _ => return None
+    }))
+}
+
+/// Sign of a float, where zero and NaN are left unchanged.
+pub fn sign(value: Float) -> Float {
+    if value > 0. { 1. } else if value < 0. { -1. } else { value }
+}
+
+/// Merge categories that an operator mapped to the same value.
+fn deduplicate_categories(nature: Option<Nature>) -> Result<Option<Nature>> {
+    Ok(match nature {
+        Some(Nature::Categorical(nature)) => Some(Nature::Categorical(NatureCategorical {
+            categories: nature.categories.deduplicate()?
+        })),
+        nature => nature
+    })
+}
+
+/// Retrieve the properties of the data argument, which must be strings.
+fn get_string_property(properties: &base::NodeProperties) -> Result<ArrayProperties> {
+    let data_property: ArrayProperties = properties.get(&IndexKey::from("data"))
+        .ok_or("data: missing")?.array()
+        .map_err(prepend("data:"))?.clone();
+
+    if !data_property.releasable {
+        data_property.assert_is_not_aggregated()?;
+    }
+    if data_property.data_type != DataType::Str {
+        return Err("data: atomic type must be string".into())
+    }
+    Ok(data_property)
+}
+
+/// Properties of string data after applying an operator to each string.
+///
+/// Categories that the operator maps to the same string are merged.
+fn propagate_string_property(
+    properties: &base::NodeProperties, operator: impl Fn(&String) -> Result<String> + 'static
+) -> Result<ArrayProperties> {
+    let mut data_property = get_string_property(properties)?;
+
+    data_property.nature = deduplicate_categories(propagate_unary_nature(
+        &data_property,
+        &UnaryOperators { float: None, int: None, str: Some(Box::new(operator)), bool: None },
+        &OptimizeUnaryOperators { float: None, int: None },
+        data_property.num_columns()?)?)?;
+    Ok(data_property)
+}
+
+/// Whether string data may contain empty strings, which are treated as null.
+///
+/// Only the categories of the data can show that no string is empty.
+fn may_contain_empty(nature: &Option<Nature>) -> bool {
+    match nature {
+        Some(Nature::Categorical(NatureCategorical { categories: Jagged::Str(categories) })) =>
+            categories.iter().flatten().any(String::is_empty),
+        _ => true
+    }
+}
+
+/// Compile a regular expression.
+pub fn compile_pattern(pattern: &str) -> Result<Regex> {
+    Regex::new(pattern).map_err(|e| Error::from(format!("pattern: {}", e)))
+}
+
+/// Take a substring, counting by characters.
+///
+/// # Arguments
+/// * `value` - String to take a substring of.
+/// * `start` - Index of the first character to keep.
+/// * `length` - Number of characters to keep. If zero, all characters from `start` onwards are kept.
+///
+/// # Example
+/// ```
+/// use smartnoise_validator::components::transforms::substring;
+/// assert_eq!(substring("région", 1, 3), "égi");
+/// assert_eq!(substring("région", 4, 0), "on");
+/// ```
+pub fn substring(value: &str, start: usize, length: usize) -> String {
+    let characters = value.chars().skip(start);
+    if length == 0 { characters.collect() } else { characters.take(length).collect() }
+}
+
+/// Extract a capture group from the first match of a regular expression.
+///
+/// # Return
+/// The group, or None if the pattern does not match or the group does not participate in the match.
+pub fn regex_extract(pattern: &Regex, value: &str, group: usize) -> Option<String> {
+    pattern.captures(value)?.get(group).map(|group| group.as_str().to_string())
+}
+
 type UnaryOperator<T> = Option<Box<dyn Fn(&T) -> Result<T>>>;
 pub struct UnaryOperators {
     pub float: UnaryOperator<Float>,
@@ -1554,6 +2186,98 @@ pub fn propagate_binary_group_id(
     Ok(left_property.group_id.clone())
 }
 
+/// Properties of data where each value is drawn from either the left or the right argument.
+///
+/// The nature is the union of the categories, or the hull of the bounds, of both arguments.
+pub fn propagate_union_property(
+    left_property: &ArrayProperties, right_property: &ArrayProperties, node_id: u32
+) -> Result<ArrayProperties> {
+    if !left_property.releasable {
+        left_property.assert_is_not_aggregated()?;
+        left_property.assert_is_not_sampled()?;
+    }
+    if !right_property.releasable {
+        right_property.assert_is_not_aggregated()?;
+        right_property.assert_is_not_sampled()?;
+    }
+
+    let (num_columns, num_records) = propagate_binary_shape(left_property, right_property)?;
+    if left_property.data_type != right_property.data_type {
+        return Err("left and right arguments must share the same data types".into())
+    }
+
+    let nature = match (&left_property.nature, &right_property.nature) {
+        (Some(Nature::Categorical(left)), Some(Nature::Categorical(right))) =>
+            Some(Nature::Categorical(NatureCategorical {
+                categories: union_categories(
+                    left.categories.clone(), right.categories.clone(), num_columns)?
+            })),
+        _ => propagate_binary_nature(left_property, right_property, &BinaryOperators {
+            float: None, int: None, str: None, bool: None
+        }, &OptimizeBinaryOperators {
+            float: Some(&|bounds| Ok((
+                match (bounds.left_lower, bounds.right_lower) {
+                    (Some(left_lower), Some(right_lower)) => Some(left_lower.min(*right_lower)),
+                    _ => None
+                },
+                match (bounds.left_upper, bounds.right_upper) {
+                    (Some(left_upper), Some(right_upper)) => Some(left_upper.max(*right_upper)),
+                    _ => None
+                }
+            ))),
+            int: Some(&|bounds| Ok((
+                match (bounds.left_lower, bounds.right_lower) {
+                    (Some(left_lower), Some(right_lower)) => Some(*left_lower.min(right_lower)),
+                    _ => None
+                },
+                match (bounds.left_upper, bounds.right_upper) {
+                    (Some(left_upper), Some(right_upper)) => Some(*left_upper.max(right_upper)),
+                    _ => None
+                }
+            ))),
+        }, num_columns)?
+    };
+
+    Ok(ArrayProperties {
+        nullity: left_property.nullity || right_property.nullity,
+        releasable: left_property.releasable && right_property.releasable,
+        nature,
+        // checks to ensure this is correct are made in propagate_binary_shape
+        c_stability: left_property.c_stability
+            .max(right_property.c_stability),
+        num_columns: Some(num_columns),
+        num_records,
+        aggregator: None,
+        group_id: propagate_binary_group_id(left_property, right_property)?,
+        data_type: left_property.data_type.clone(),
+        // public arguments do not determine which dataset the values belong to
+        dataset_id: if left_property.releasable { right_property.dataset_id } else { left_property.dataset_id },
+        node_id: node_id as i64,
+        is_not_empty: left_property.is_not_empty && right_property.is_not_empty,
+        dimensionality: left_property.dimensionality
+            .max(right_property.dimensionality),
+        naturally_ordered: true,
+        // checks are made within propagate_binary_shape that sampling proportion is equal and permissible
+        sample_proportion: left_property.sample_proportion
+    })
+}
+
+/// Categories of each column that may be drawn from either set of categories.
+fn union_categories(left: Jagged, right: Jagged, num_columns: i64) -> Result<Jagged> {
+    fn union<T>(left: Vec<Vec<T>>, right: Vec<Vec<T>>) -> Vec<Vec<T>> {
+        left.into_iter().zip(right)
+            .map(|(left, right)| left.into_iter().chain(right).collect())
+            .collect()
+    }
+
+    match (left.standardize(num_columns)?, right.standardize(num_columns)?) {
+        (Jagged::Int(left), Jagged::Int(right)) => Jagged::Int(union(left, right)),
+        (Jagged::Str(left), Jagged::Str(right)) => Jagged::Str(union(left, right)),
+        (Jagged::Bool(left), Jagged::Bool(right)) => Jagged::Bool(union(left, right)),
+        _ => return Err("categories must be homogeneously typed, and may not be float".into())
+    }.deduplicate()
+}
+
 fn broadcast<T: Clone>(data: &[T], length: i64) -> Result<Vec<T>> {
     if data.len() as i64 == length {
         return Ok(data.to_owned());
@@ -1565,3 +2289,106 @@ fn broadcast<T: Clone>(data: &[T], length: i64) -> Result<Vec<T>> {
 
     Ok((0..length).map(|_| data[0].clone()).collect())
 }
+
+#[cfg(test)]
+mod test_transforms {
+    use crate::base::test_data;
+    use crate::bindings::Analysis;
+    use crate::components::cast::test_cast;
+    use crate::components::clamp::test_clamp;
+
+    use super::*;
+
+    #[test]
+    fn test_sinusoid_bounds() {
+        // sin increases over [-pi/2, pi/2]
+        assert_eq!(sinusoid_bounds(&Some(-1.), &Some(1.), 0.), (Float::sin(-1.), Float::sin(1.)));
+        // the peak at pi/2 is within the bounds
+        assert_eq!(sinusoid_bounds(&Some(1.), &Some(2.), 0.), (Float::sin(1.), 1.));
+        // cos over [pi/2, 3pi/2] reaches its trough at pi
+        assert_eq!(sinusoid_bounds(&Some(PI / 2.), &Some(3. * PI / 2.), PI / 2.).0, -1.);
+        assert_eq!(sinusoid_bounds(&None, &Some(0.), 0.), (-1., 1.));
+        assert!(contains_period(1., 2., PI / 2., PI));
+        assert!(!contains_period(-1., 1., PI / 2., PI));
+    }
+
+    /// Properties of categorical string data after applying a string transform.
+    fn string_property(
+        categories: &[&str], transform: impl Fn(&mut Analysis, u32) -> u32
+    ) -> Result<ArrayProperties> {
+        let categories = categories.iter().map(|v| v.to_string()).collect::<Vec<String>>();
+        let (mut analysis, clamped) = test_clamp::utilities::analysis_string_cat(
+            test_data::array1d_string_10_uniform(),
+            Some(Value::Jagged(vec![categories].into())),
+            Some("null".to_string().into()));
+        let transformed = transform(&mut analysis, clamped);
+        Ok(analysis.properties(transformed)?.array()?.clone())
+    }
+
+    fn string_categories(property: &ArrayProperties) -> Vec<String> {
+        let mut categories = property.categories().unwrap().string().unwrap()[0].clone();
+        categories.sort();
+        categories
+    }
+
+    #[test]
+    fn test_substring_nature() {
+        let property = string_property(&["apple", "avocado", "fig"], |analysis, data|
+            analysis.substring(data).start(1).length(2).build()).unwrap();
+        assert_eq!(string_categories(&property), vec!["ig", "pp", "ul", "vo"]);
+        assert!(!property.nullity);
+
+        // strings shorter than start become empty
+        let property = string_property(&["apple", "fig"], |analysis, data|
+            analysis.substring(data).start(4).build()).unwrap();
+        assert_eq!(string_categories(&property), vec!["", "e"]);
+        assert!(property.nullity);
+
+        // without categories, any string may become empty
+        let (mut analysis, data) = test_cast::utilities::analysis_string(test_data::array1d_string_10_uniform());
+        let substring = analysis.substring(data).start(1).build();
+        assert!(analysis.properties(substring).unwrap().array().unwrap().nullity);
+    }
+
+    #[test]
+    fn test_trim_nature() {
+        let property = string_property(&[" a", "a ", "b"], |analysis, data|
+            analysis.trim(data).build()).unwrap();
+        assert_eq!(string_categories(&property), vec!["a", "b", "null"]);
+        assert!(!property.nullity);
+
+        let property = string_property(&["a", "  "], |analysis, data|
+            analysis.trim(data).build()).unwrap();
+        assert_eq!(string_categories(&property), vec!["", "a", "null"]);
+        assert!(property.nullity);
+    }
+
+    #[test]
+    fn test_regex_extract_nature() {
+        let extract = |categories: &[&str], pattern: &str, null_value: &str| string_property(categories, |analysis, data| {
+            let null_value = analysis.literal().value(null_value.to_string().into()).value_public(true).build();
+            analysis.regex_extract(data, null_value, pattern.to_string()).build()
+        });
+
+        let property = extract(&["a1", "b22", "c"], "[0-9]+", "none").unwrap();
+        assert_eq!(string_categories(&property), vec!["1", "22", "none"]);
+        assert!(!property.nullity);
+
+        // the match may be empty
+        let property = extract(&["1a", "b"], "^[0-9]*", "none").unwrap();
+        assert_eq!(string_categories(&property), vec!["", "1"]);
+        assert!(property.nullity);
+
+        // an empty null value would be indistinguishable from null
+        assert!(extract(&["a1", "b"], "[0-9]+", "").is_err());
+    }
+
+    #[test]
+    fn test_concat_nature() {
+        // "a" + "aa" and "aa" + "a" are the same category
+        let property = string_property(&["a", "aa"], |analysis, data|
+            analysis.concat(data, data).separator("".to_string()).build()).unwrap();
+        assert_eq!(string_categories(&property), vec![
+            "aa", "aaa", "aaaa", "aanull", "anull", "nulla", "nullaa", "nullnull"]);
+    }
+}
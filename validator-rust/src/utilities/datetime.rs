@@ -0,0 +1,310 @@
+//! Calendar arithmetic over timestamps.
+//!
+//! Timestamps are integer seconds since the Unix epoch, in UTC.
+//! Dates follow the proleptic Gregorian calendar.
+
+use crate::errors::*;
+use crate::Integer;
+
+const SECONDS_PER_MINUTE: Integer = 60;
+const SECONDS_PER_HOUR: Integer = 60 * SECONDS_PER_MINUTE;
+const SECONDS_PER_DAY: Integer = 24 * SECONDS_PER_HOUR;
+
+/// Granularity that timestamps may be truncated to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DatetimeUnit {
+    Hour,
+    Day,
+    /// Weeks start on Monday.
+    Week,
+    Month,
+    Year,
+}
+
+impl DatetimeUnit {
+    pub fn new(unit: &str) -> Result<DatetimeUnit> {
+        Ok(match unit.to_lowercase().as_str() {
+            "hour" => DatetimeUnit::Hour,
+            "day" => DatetimeUnit::Day,
+            "week" => DatetimeUnit::Week,
+            "month" => DatetimeUnit::Month,
+            "year" => DatetimeUnit::Year,
+            _ => bail!("unit: must be one of [hour, day, week, month, year]")
+        })
+    }
+}
+
+/// Field that may be extracted from a timestamp.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DatetimePart {
+    Year,
+    Month,
+    Day,
+    Hour,
+    Minute,
+    Second,
+    /// Monday is 0, Sunday is 6.
+    Weekday,
+}
+
+impl DatetimePart {
+    pub fn new(part: &str) -> Result<DatetimePart> {
+        Ok(match part.to_lowercase().as_str() {
+            "year" => DatetimePart::Year,
+            "month" => DatetimePart::Month,
+            "day" => DatetimePart::Day,
+            "hour" => DatetimePart::Hour,
+            "minute" => DatetimePart::Minute,
+            "second" => DatetimePart::Second,
+            "weekday" => DatetimePart::Weekday,
+            _ => bail!("part: must be one of [year, month, day, hour, minute, second, weekday]")
+        })
+    }
+
+    /// Every value the part may take, if the set is fixed.
+    pub fn categories(&self) -> Option<Vec<Integer>> {
+        Some(match self {
+            DatetimePart::Year => return None,
+            DatetimePart::Month => (1..=12).collect(),
+            DatetimePart::Day => (1..=31).collect(),
+            DatetimePart::Hour => (0..24).collect(),
+            DatetimePart::Minute | DatetimePart::Second => (0..60).collect(),
+            DatetimePart::Weekday => (0..7).collect(),
+        })
+    }
+}
+
+/// Number of days from 1970-01-01 to the given date.
+pub fn days_from_civil(year: Integer, month: Integer, day: Integer) -> Integer {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Year, month and day of the date the given number of days from 1970-01-01.
+pub fn civil_from_days(days: Integer) -> (Integer, Integer, Integer) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    (if month <= 2 { year_of_era + era * 400 + 1 } else { year_of_era + era * 400 }, month, day)
+}
+
+fn is_leap_year(year: Integer) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+fn days_in_month(year: Integer, month: Integer) -> Integer {
+    match month {
+        2 => if is_leap_year(year) { 29 } else { 28 },
+        4 | 6 | 9 | 11 => 30,
+        _ => 31
+    }
+}
+
+/// Parse a timestamp from a string.
+///
+/// Supported directives are `%Y` (year), `%m` (month), `%d` (day), `%H` (hour), `%M` (minute),
+/// `%S` (second) and `%%` (a literal percent sign). All other characters must match exactly.
+/// Fields that are not in the format default to the start of the enclosing period.
+///
+/// # Arguments
+/// * `value` - String to parse.
+/// * `format` - Format of the string.
+///
+/// # Return
+/// Seconds since the Unix epoch, or None if the string does not match the format.
+///
+/// # Example
+/// ```
+/// use smartnoise_validator::utilities::datetime::parse_datetime;
+/// assert_eq!(parse_datetime("2020-03-01 12:30:00", "%Y-%m-%d %H:%M:%S"), Some(1583065800));
+/// assert_eq!(parse_datetime("2020-02-30", "%Y-%m-%d"), None);
+/// ```
+pub fn parse_datetime(value: &str, format: &str) -> Option<Integer> {
+    let (mut year, mut month, mut day) = (1970, 1, 1);
+    let (mut hour, mut minute, mut second) = (0, 0, 0);
+
+    let mut value = value.chars().peekable();
+    let mut format = format.chars();
+    while let Some(directive) = format.next() {
+        if directive != '%' {
+            if value.next()? != directive { return None }
+            continue
+        }
+        let (field, width) = match format.next()? {
+            'Y' => (&mut year, 4),
+            'm' => (&mut month, 2),
+            'd' => (&mut day, 2),
+            'H' => (&mut hour, 2),
+            'M' => (&mut minute, 2),
+            'S' => (&mut second, 2),
+            '%' => {
+                if value.next()? != '%' { return None }
+                continue
+            }
+            _ => return None
+        };
+        let mut digits = String::new();
+        while digits.len() < width {
+            match value.peek() {
+                Some(digit) if digit.is_ascii_digit() => digits.push(value.next()?),
+                _ => break
+            }
+        }
+        *field = digits.parse().ok()?;
+    }
+    if value.next().is_some() { return None }
+
+    if !(1..=12).contains(&month) || !(1..=days_in_month(year, month)).contains(&day)
+        || !(0..24).contains(&hour) || !(0..60).contains(&minute) || !(0..60).contains(&second) {
+        return None
+    }
+
+    Some(days_from_civil(year, month, day) * SECONDS_PER_DAY
+        + hour * SECONDS_PER_HOUR + minute * SECONDS_PER_MINUTE + second)
+}
+
+/// Round a timestamp down to the start of its hour, day, week, month or year.
+pub fn truncate_timestamp(timestamp: Integer, unit: &DatetimeUnit) -> Integer {
+    let days = timestamp.div_euclid(SECONDS_PER_DAY);
+    match unit {
+        DatetimeUnit::Hour => timestamp - timestamp.rem_euclid(SECONDS_PER_HOUR),
+        DatetimeUnit::Day => days * SECONDS_PER_DAY,
+        DatetimeUnit::Week => (days - weekday(days)) * SECONDS_PER_DAY,
+        DatetimeUnit::Month => {
+            let (year, month, _) = civil_from_days(days);
+            days_from_civil(year, month, 1) * SECONDS_PER_DAY
+        }
+        DatetimeUnit::Year => {
+            let (year, _, _) = civil_from_days(days);
+            days_from_civil(year, 1, 1) * SECONDS_PER_DAY
+        }
+    }
+}
+
+/// Start of the unit following the unit that contains the timestamp.
+///
+/// # Return
+/// The next truncated timestamp, or None if it overflows.
+pub fn next_timestamp(timestamp: Integer, unit: &DatetimeUnit) -> Option<Integer> {
+    let timestamp = truncate_timestamp(timestamp, unit);
+    match unit {
+        DatetimeUnit::Hour => timestamp.checked_add(SECONDS_PER_HOUR),
+        DatetimeUnit::Day => timestamp.checked_add(SECONDS_PER_DAY),
+        DatetimeUnit::Week => timestamp.checked_add(7 * SECONDS_PER_DAY),
+        DatetimeUnit::Month | DatetimeUnit::Year => {
+            let (year, month, _) = civil_from_days(timestamp.div_euclid(SECONDS_PER_DAY));
+            let (year, month) = match (unit, month) {
+                (DatetimeUnit::Year, _) | (_, 12) => (year.checked_add(1)?, 1),
+                _ => (year, month + 1)
+            };
+            days_from_civil(year, month, 1).checked_mul(SECONDS_PER_DAY)
+        }
+    }
+}
+
+/// Every truncated timestamp between the truncations of `lower` and `upper`, inclusive.
+///
+/// # Return
+/// The timestamps, or None if there are more than `limit`.
+pub fn enumerate_timestamps(lower: Integer, upper: Integer, unit: &DatetimeUnit, limit: usize) -> Option<Vec<Integer>> {
+    let mut timestamps = Vec::new();
+    let mut timestamp = truncate_timestamp(lower, unit);
+    while timestamp <= upper {
+        if timestamps.len() == limit { return None }
+        timestamps.push(timestamp);
+        timestamp = match next_timestamp(timestamp, unit) {
+            Some(timestamp) => timestamp,
+            None => break
+        };
+    }
+    Some(timestamps)
+}
+
+/// Extract a calendar field from a timestamp.
+///
+/// # Example
+/// ```
+/// use smartnoise_validator::utilities::datetime::{extract_timestamp, DatetimePart};
+/// // Sunday, 1 March 2020
+/// assert_eq!(extract_timestamp(1583065800, &DatetimePart::Weekday), 6);
+/// assert_eq!(extract_timestamp(1583065800, &DatetimePart::Minute), 30);
+/// ```
+pub fn extract_timestamp(timestamp: Integer, part: &DatetimePart) -> Integer {
+    let days = timestamp.div_euclid(SECONDS_PER_DAY);
+    let seconds = timestamp.rem_euclid(SECONDS_PER_DAY);
+    match part {
+        DatetimePart::Year => civil_from_days(days).0,
+        DatetimePart::Month => civil_from_days(days).1,
+        DatetimePart::Day => civil_from_days(days).2,
+        DatetimePart::Hour => seconds / SECONDS_PER_HOUR,
+        DatetimePart::Minute => seconds % SECONDS_PER_HOUR / SECONDS_PER_MINUTE,
+        DatetimePart::Second => seconds % SECONDS_PER_MINUTE,
+        DatetimePart::Weekday => weekday(days),
+    }
+}
+
+/// Day of the week, where Monday is 0. 1970-01-01 was a Thursday.
+fn weekday(days: Integer) -> Integer {
+    (days + 3).rem_euclid(7)
+}
+
+#[cfg(test)]
+mod test_datetime {
+    use super::*;
+
+    #[test]
+    fn test_civil_round_trip() {
+        (-800_000..800_000).step_by(97).for_each(|days| {
+            let (year, month, day) = civil_from_days(days);
+            assert!((1..=days_in_month(year, month)).contains(&day));
+            assert_eq!(days_from_civil(year, month, day), days);
+        });
+        assert_eq!(days_from_civil(2000, 2, 29), 11016);
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+    }
+
+    #[test]
+    fn test_truncate() {
+        // Wednesday, 15 January 2020 13:45:30
+        let timestamp = parse_datetime("2020-01-15 13:45:30", "%Y-%m-%d %H:%M:%S").unwrap();
+        let truncate = |unit| truncate_timestamp(timestamp, &unit);
+        let parse = |value| parse_datetime(value, "%Y-%m-%d %H").unwrap();
+        assert_eq!(truncate(DatetimeUnit::Hour), parse("2020-01-15 13"));
+        assert_eq!(truncate(DatetimeUnit::Day), parse("2020-01-15 00"));
+        assert_eq!(truncate(DatetimeUnit::Week), parse("2020-01-13 00"));
+        assert_eq!(truncate(DatetimeUnit::Month), parse("2020-01-01 00"));
+        assert_eq!(truncate(DatetimeUnit::Year), parse("2020-01-01 00"));
+
+        // before the epoch
+        assert_eq!(truncate_timestamp(-1, &DatetimeUnit::Day), -SECONDS_PER_DAY);
+    }
+
+    #[test]
+    fn test_enumerate() {
+        let lower = parse_datetime("2019-11-20", "%Y-%m-%d").unwrap();
+        let upper = parse_datetime("2020-02-01", "%Y-%m-%d").unwrap();
+        let months = enumerate_timestamps(lower, upper, &DatetimeUnit::Month, 100).unwrap();
+        assert_eq!(months.iter().map(|v| extract_timestamp(*v, &DatetimePart::Month)).collect::<Vec<_>>(),
+                   vec![11, 12, 1, 2]);
+        assert!(enumerate_timestamps(lower, upper, &DatetimeUnit::Hour, 100).is_none());
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse_datetime("1970-01-01", "%Y-%m-%d"), Some(0));
+        assert_eq!(parse_datetime("01/02/1970 100%", "%d/%m/%Y 100%%"), Some(31 * SECONDS_PER_DAY));
+        assert_eq!(parse_datetime("1970-1-1", "%Y-%m-%d"), Some(0));
+        assert_eq!(parse_datetime("1970-01-01 24:00", "%Y-%m-%d %H:%M"), None);
+        assert_eq!(parse_datetime("1970-01-01 extra", "%Y-%m-%d"), None);
+        assert_eq!(parse_datetime("1970-01", "%Y-%m-%d"), None);
+    }
+}
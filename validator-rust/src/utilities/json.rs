@@ -74,7 +74,10 @@ pub fn value_to_json(value: &base::Value) -> Result<serde_json::Value> {
             base::Array::Str(value) => arraynd_to_json(value),
             base::Array::Bool(value) => arraynd_to_json(value)
         },
-        _ => Err("only arrayND to json is implemented".into())
+        base::Value::Dataframe(dataframe) => Ok(serde_json::Value::Object(dataframe.iter()
+            .map(|(name, column)| Ok((name.to_string(), value_to_json(column)?)))
+            .collect::<Result<serde_json::Map<String, serde_json::Value>>>()?)),
+        _ => Err("only arrayND and dataframe to json are implemented".into())
     }
 }
 
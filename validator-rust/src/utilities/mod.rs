@@ -19,6 +19,7 @@ pub mod json;
 pub mod inference;
 pub mod serial;
 pub mod array;
+pub mod datetime;
 pub mod privacy;
 pub mod properties;
 
@@ -578,7 +579,9 @@ pub fn expand_mechanism(
             }
         }
     }
-    assign_usage!(LaplaceMechanism, GaussianMechanism, SimpleGeometricMechanism, SnappingMechanism);
+    assign_usage!(
+        LaplaceMechanism, GaussianMechanism, SimpleGeometricMechanism, SnappingMechanism,
+        DpEmpiricalCdf, DpKernelDensity, DpMode);
 
     expansion.computation_graph.insert(component_id, noise_component);
 
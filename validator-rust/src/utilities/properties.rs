@@ -108,7 +108,7 @@ pub fn stack_properties(
         is_not_empty: all_properties.iter().all(|prop| prop.is_not_empty),
         dimensionality,
         group_id,
-        naturally_ordered: true,
+        naturally_ordered: all_properties.iter().all(|prop| prop.naturally_ordered),
         sample_proportion
     }))
 }
@@ -119,7 +119,7 @@ fn get_common_continuous_nature(natures: &[Option<&Nature>], data_type: DataType
         Some(Nature::Categorical(_)) => None,
         _ => Some(match data_type {
             DataType::Float => Vector1DNull::Float(vec![None]),
-            DataType::Int => Vector1DNull::Int(vec![None]),
+            DataType::Int | DataType::Timestamp => Vector1DNull::Int(vec![None]),
             _ => return None
         })
     }).collect::<Option<Vec<Vector1DNull>>>()?.into_iter()
@@ -130,7 +130,7 @@ fn get_common_continuous_nature(natures: &[Option<&Nature>], data_type: DataType
         Some(Nature::Categorical(_)) => None,
         None => Some(match data_type {
             DataType::Float => Vector1DNull::Float(vec![None]),
-            DataType::Int => Vector1DNull::Int(vec![None]),
+            DataType::Int | DataType::Timestamp => Vector1DNull::Int(vec![None]),
             _ => return None
         })
     }).collect::<Option<Vec<Vector1DNull>>>()?.into_iter()
@@ -118,6 +118,7 @@ pub fn parse_data_type(value: proto::DataType) -> DataType {
         proto::DataType::F64 => DataType::Float,
         proto::DataType::I64 => DataType::Int,
         proto::DataType::String => DataType::Str,
+        proto::DataType::Timestamp => DataType::Timestamp,
     }
 }
 
@@ -134,7 +135,7 @@ pub fn parse_jagged(value: proto::Jagged) -> Jagged {
                 Vector1D::Float(vector) => vector,
                 _ => panic!()
             }).collect::<Vec<Vec<Float>>>()),
-        proto::DataType::I64 => Jagged::Int(value.data.into_iter()
+        proto::DataType::I64 | proto::DataType::Timestamp => Jagged::Int(value.data.into_iter()
             .map(|column| match parse_array1d(column) {
                 Vector1D::Int(vector) => vector,
                 _ => panic!()
@@ -433,6 +434,7 @@ pub fn serialize_data_type(value: DataType) -> proto::DataType {
         DataType::Float => proto::DataType::F64,
         DataType::Int => proto::DataType::I64,
         DataType::Str => proto::DataType::String,
+        DataType::Timestamp => proto::DataType::Timestamp,
     }
 }
 